@@ -21,7 +21,7 @@ use structopt::StructOpt;
 use synth::WaveformSynth;
 use tune::{
     key::{Keyboard, PianoKey},
-    ratio::Ratio,
+    ratio::{FractionConstraints, Ratio},
     scala::Scl,
     temperament::{EqualTemperament, TemperamentPreference},
 };
@@ -96,10 +96,14 @@ struct RunOptions {
     #[structopt(long = "ss")]
     secondary_step: Option<i16>,
 
-    /// Integer limit for frequency ratio indicators
+    /// Integer limit for frequency ratio indicators (ignoring powers of two)
     #[structopt(long = "lim", default_value = "11")]
     limit: u16,
 
+    /// Prime limit for frequency ratio indicators
+    #[structopt(long = "prime-lim")]
+    prime_limit: Option<u16>,
+
     #[structopt(subcommand)]
     command: Option<SclCommand>,
 }
@@ -189,12 +193,17 @@ fn start(app: &App, config: RunOptions) -> Result<Model, String> {
         .build()
         .unwrap();
 
+    let fraction_constraints = FractionConstraints {
+        prime_limit: config.prime_limit,
+        ..FractionConstraints::odd_limit(config.limit)
+    };
+
     Ok(Model::new(
         audio,
         engine,
         engine_snapshot,
         keyboard,
-        config.limit,
+        fraction_constraints,
         midi_in,
         receive_updates,
     ))
@@ -18,7 +18,7 @@ use tune::{
     key::Keyboard,
     note::NoteLetter,
     pitch::{Pitch, Pitched},
-    ratio::Ratio,
+    ratio::{FractionConstraints, Ratio},
 };
 
 pub struct Model {
@@ -27,7 +27,7 @@ pub struct Model {
     pub engine: Arc<PianoEngine>,
     pub engine_snapshot: PianoEngineSnapshot,
     pub keyboard: Keyboard,
-    pub limit: u16,
+    pub fraction_constraints: FractionConstraints,
     pub midi_in: Option<MidiInputConnection<()>>,
     pub lowest_note: Pitch,
     pub highest_note: Pitch,
@@ -61,7 +61,7 @@ impl Model {
         engine: Arc<PianoEngine>,
         engine_snapshot: PianoEngineSnapshot,
         keyboard: Keyboard,
-        limit: u16,
+        fraction_constraints: FractionConstraints,
         midi_in: Option<MidiInputConnection<()>>,
         program_updates: Receiver<SelectedProgram>,
     ) -> Self {
@@ -73,7 +73,7 @@ impl Model {
             engine,
             engine_snapshot,
             keyboard,
-            limit,
+            fraction_constraints,
             midi_in,
             lowest_note,
             highest_note,
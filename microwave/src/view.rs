@@ -2,6 +2,7 @@ use crate::{piano::SynthMode, Model};
 use geom::Range;
 use nannou::prelude::*;
 use tune::{
+    interval,
     note::{Note, NoteLetter},
     pitch::Pitched,
     ratio::Ratio,
@@ -102,8 +103,12 @@ pub fn view(app: &App, model: &Model, frame: Frame) {
             .font_size(24);
 
         for first in others.iter() {
-            let approximation =
-                Ratio::between_pitches(*first, *second).nearest_fraction(model.limit);
+            let approximation = match Ratio::between_pitches(*first, *second)
+                .nearest_fraction_with(&model.fraction_constraints)
+            {
+                Some(approximation) => approximation,
+                None => continue,
+            };
 
             let width = approximation.deviation.as_semitones() * key_stride * w as f64;
             let deviation_bar_rect = Rect {
@@ -119,11 +124,17 @@ pub fn view(app: &App, model: &Model, frame: Frame) {
 
             let deviation_text_rect = curr_rect.below(curr_rect);
 
+            let interval_name =
+                interval::find_ji_fraction(approximation.numer.into(), approximation.denom.into())
+                    .map(|interval| format!(" {}", interval.name))
+                    .unwrap_or_default();
+
             draw.text(&format!(
-                "{}/{} [{:.0}c]",
+                "{}/{} [{:.0}c]{}",
                 approximation.numer,
                 approximation.denom,
-                approximation.deviation.as_cents().abs()
+                approximation.deviation.as_cents().abs(),
+                interval_name
             ))
             .xy(deviation_text_rect.xy())
             .wh(deviation_text_rect.wh())
@@ -0,0 +1,266 @@
+//! Index and search directory trees of [Scala](http://www.huygens-fokker.org/scala/) files.
+
+use crate::{
+    ratio::Ratio,
+    scala::{Scl, SclImportError},
+};
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Largest EDO considered when determining [`ArchiveEntry::edo`].
+pub const MAX_EDO: u16 = 72;
+
+/// Largest deviation of any pitch from its EDO step s.t. a scale is considered a subset of that EDO.
+pub const EDO_TOLERANCE_IN_CENTS: f64 = 0.5;
+
+/// A collection of indexed [`Scl`]s.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::archive::{Archive, ArchiveQuery};
+/// # use tune::ratio::Ratio;
+/// # use tune::scala::Scl;
+/// let major = Scl::builder()
+///     .push_fraction(9, 8)
+///     .push_fraction(5, 4)
+///     .push_fraction(4, 3)
+///     .push_fraction(3, 2)
+///     .push_fraction(5, 3)
+///     .push_fraction(15, 8)
+///     .push_int(2)
+///     .build()
+///     .unwrap();
+/// let septimal = Scl::builder().push_fraction(7, 6).push_fraction(7, 4).push_int(2).build().unwrap();
+///
+/// let archive = Archive::from_scls(vec![("major.scl".into(), major), ("septimal.scl".into(), septimal)]);
+///
+/// let query = ArchiveQuery::any().with_max_prime_limit(5);
+/// let found = archive.search(&query).map(|entry| entry.path.to_str().unwrap()).collect::<Vec<_>>();
+/// assert_eq!(found, ["major.scl"]);
+///
+/// let query = ArchiveQuery::any().containing(Ratio::from_float(1.75));
+/// let found = archive.search(&query).map(|entry| entry.path.to_str().unwrap()).collect::<Vec<_>>();
+/// assert_eq!(found, ["septimal.scl"]);
+///
+/// let query = ArchiveQuery::any().with_edo_subset(12).with_tolerance(Ratio::from_cents(20.0));
+/// let found = archive.search(&query).map(|entry| entry.path.to_str().unwrap()).collect::<Vec<_>>();
+/// assert_eq!(found, ["major.scl"]);
+///
+/// let query = ArchiveQuery::any().with_edo_subset(0);
+/// assert_eq!(archive.search(&query).count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct Archive {
+    entries: Vec<ArchiveEntry>,
+    errors: Vec<(PathBuf, SclImportError)>,
+}
+
+impl Archive {
+    /// Imports all `.scl` files in `dir` and its subdirectories.
+    ///
+    /// Files that cannot be imported are skipped and reported by [`Archive::errors`].
+    pub fn import_dir(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file_names = Vec::new();
+        collect_scl_files(dir.as_ref(), &mut file_names)?;
+        file_names.sort();
+
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        for file_name in file_names {
+            match File::open(&file_name)
+                .map_err(SclImportError::IoError)
+                .and_then(Scl::import)
+            {
+                Ok(scl) => entries.push(ArchiveEntry::new(file_name, scl)),
+                Err(err) => errors.push((file_name, err)),
+            }
+        }
+
+        Ok(Self { entries, errors })
+    }
+
+    pub fn from_scls(scls: Vec<(PathBuf, Scl)>) -> Self {
+        Self {
+            entries: scls
+                .into_iter()
+                .map(|(path, scl)| ArchiveEntry::new(path, scl))
+                .collect(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn entries(&self) -> &[ArchiveEntry] {
+        &self.entries
+    }
+
+    pub fn errors(&self) -> &[(PathBuf, SclImportError)] {
+        &self.errors
+    }
+
+    /// Finds all entries matching the given [`ArchiveQuery`].
+    pub fn search<'a>(
+        &'a self,
+        query: &'a ArchiveQuery,
+    ) -> impl Iterator<Item = &'a ArchiveEntry> + 'a {
+        self.entries
+            .iter()
+            .filter(move |entry| query.matches(entry))
+    }
+}
+
+fn collect_scl_files(dir: &Path, file_names: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_scl_files(&path, file_names)?;
+        } else if path
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("scl"))
+            .unwrap_or_default()
+        {
+            file_names.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// An [`Scl`] together with its indexed properties.
+#[derive(Clone, Debug)]
+pub struct ArchiveEntry {
+    pub path: PathBuf,
+    pub scl: Scl,
+    /// See [`Scl::prime_limit`].
+    pub prime_limit: Option<u32>,
+    /// Whether the scale is a moment of symmetry, see [`Scl::is_mos`].
+    pub is_mos: bool,
+    /// The smallest EDO up to [`MAX_EDO`] containing all pitches within [`EDO_TOLERANCE_IN_CENTS`].
+    pub edo: Option<u16>,
+}
+
+impl ArchiveEntry {
+    pub fn new(path: PathBuf, scl: Scl) -> Self {
+        let tolerance = Ratio::from_cents(EDO_TOLERANCE_IN_CENTS);
+        Self {
+            prime_limit: scl.prime_limit(),
+            is_mos: scl.is_mos(tolerance),
+            edo: (1..=MAX_EDO)
+                .find(|&num_steps_per_octave| is_edo_subset(&scl, num_steps_per_octave, tolerance)),
+            path,
+            scl,
+        }
+    }
+}
+
+fn is_edo_subset(scl: &Scl, num_steps_per_octave: u16, tolerance: Ratio) -> bool {
+    scl.tempered_to_edo(num_steps_per_octave)
+        .map(|(_, approximations)| {
+            approximations.iter().all(|approximation| {
+                approximation.deviation.as_cents().abs() <= tolerance.as_cents().abs()
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Criteria for searching an [`Archive`]. All criteria must be satisfied.
+#[derive(Clone, Debug)]
+pub struct ArchiveQuery {
+    /// Exact number of notes.
+    pub size: Option<usize>,
+
+    /// Period of the scale within [`ArchiveQuery::tolerance`].
+    pub period: Option<Ratio>,
+
+    /// Largest acceptable prime limit. Scales containing cents values never match.
+    pub max_prime_limit: Option<u32>,
+
+    /// Intervals that must be contained in the scale within [`ArchiveQuery::tolerance`].
+    pub containing: Vec<Ratio>,
+
+    /// Whether the scale must (not) be a moment of symmetry.
+    pub mos: Option<bool>,
+
+    /// EDO that must contain all pitches within [`ArchiveQuery::tolerance`]. An EDO without any steps never matches.
+    pub edo_subset: Option<u16>,
+
+    /// Largest acceptable deviation of the pitches from the criteria above.
+    pub tolerance: Ratio,
+}
+
+impl ArchiveQuery {
+    /// Creates a query matching any scale with a default tolerance of 1 cent.
+    pub fn any() -> Self {
+        Self {
+            size: None,
+            period: None,
+            max_prime_limit: None,
+            containing: Vec::new(),
+            mos: None,
+            edo_subset: None,
+            tolerance: Ratio::from_cents(1.0),
+        }
+    }
+
+    pub fn with_size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_period(mut self, period: Ratio) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    pub fn with_max_prime_limit(mut self, max_prime_limit: u32) -> Self {
+        self.max_prime_limit = Some(max_prime_limit);
+        self
+    }
+
+    pub fn containing(mut self, interval: Ratio) -> Self {
+        self.containing.push(interval);
+        self
+    }
+
+    pub fn with_mos(mut self, mos: bool) -> Self {
+        self.mos = Some(mos);
+        self
+    }
+
+    pub fn with_edo_subset(mut self, num_steps_per_octave: u16) -> Self {
+        self.edo_subset = Some(num_steps_per_octave);
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: Ratio) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn matches(&self, entry: &ArchiveEntry) -> bool {
+        let tolerance_in_cents = self.tolerance.as_cents().abs();
+        let is_within_tolerance =
+            |deviation: Ratio| deviation.as_cents().abs() <= tolerance_in_cents;
+
+        self.size.iter().all(|&size| entry.scl.size() == size)
+            && self
+                .period
+                .iter()
+                .all(|&period| is_within_tolerance(entry.scl.period().deviation_from(period)))
+            && self.max_prime_limit.iter().all(|&max_prime_limit| {
+                entry
+                    .prime_limit
+                    .filter(|&prime_limit| prime_limit <= max_prime_limit)
+                    .is_some()
+            })
+            && self.containing.iter().all(|&interval| {
+                is_within_tolerance(entry.scl.find_by_relative_pitch(interval).deviation)
+            })
+            && self.mos.iter().all(|&mos| entry.is_mos == mos)
+            && self.edo_subset.iter().all(|&num_steps_per_octave| {
+                is_edo_subset(&entry.scl, num_steps_per_octave, self.tolerance)
+            })
+    }
+}
@@ -0,0 +1,313 @@
+//! Find just intonation chords in scales.
+
+use crate::{math, ratio::Ratio, scala::Scl};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// A just intonation chord given as a segment of the harmonic series, e.g. `4:5:6:7`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Chord {
+    harmonics: Vec<u16>,
+}
+
+impl Chord {
+    pub fn from_harmonics(harmonics: Vec<u16>) -> Self {
+        Self { harmonics }
+    }
+
+    pub fn harmonics(&self) -> &[u16] {
+        &self.harmonics
+    }
+
+    /// Returns the intervals of all chord tones relative to the first chord tone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::chord::Chord;
+    /// let chord = Chord::from_harmonics(vec![4, 5, 6]);
+    /// let intervals = chord.intervals().map(|interval| interval.as_float()).collect::<Vec<_>>();
+    ///
+    /// assert_approx_eq!(intervals[0], 1.0);
+    /// assert_approx_eq!(intervals[1], 1.25);
+    /// assert_approx_eq!(intervals[2], 1.5);
+    /// ```
+    pub fn intervals(&self) -> impl Iterator<Item = Ratio> + '_ {
+        let root_harmonic = self.harmonics.first().copied().unwrap_or(1);
+        self.harmonics
+            .iter()
+            .map(move |&harmonic| Ratio::from_float(f64::from(harmonic) / f64::from(root_harmonic)))
+    }
+
+    /// Returns the largest odd limit of all intervals between any two chord tones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::chord::Chord;
+    /// assert_eq!(Chord::from_harmonics(vec![4, 5, 6]).odd_limit(), 5);
+    /// assert_eq!(Chord::from_harmonics(vec![4, 5, 6, 7]).odd_limit(), 7);
+    /// assert_eq!(Chord::from_harmonics(vec![6, 7, 9]).odd_limit(), 9);
+    /// ```
+    pub fn odd_limit(&self) -> u16 {
+        let mut odd_limit = 1;
+        for (index, &lower) in self.harmonics.iter().enumerate() {
+            for &upper in &self.harmonics[index + 1..] {
+                odd_limit = odd_limit.max(interval_odd_limit(lower, upper));
+            }
+        }
+        odd_limit
+    }
+
+    /// Finds every root degree of `scl` on which the chord can be played.
+    ///
+    /// Each chord tone is mapped to the nearest scale degree using [`Scl::find_by_relative_pitch`].
+    /// Realizations where two chord tones fall onto the same degree are skipped.
+    /// The result is ordered by the total error, most accurate realization first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::chord::Chord;
+    /// # use tune::ratio::Ratio;
+    /// # use tune::scala::Scl;
+    /// let edo_22 = (1..=22)
+    ///     .fold(Scl::builder(), |builder, step| {
+    ///         builder.push_ratio(Ratio::octave().divided_into_equal_steps(22).repeated(step))
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let realizations = Chord::from_harmonics(vec![4, 5, 6, 7]).find_in(&edo_22);
+    /// assert_eq!(realizations.len(), 22);
+    /// assert_eq!(realizations[0].degrees, [0, 7, 13, 18]);
+    /// assert_approx_eq!(realizations[0].total_error_in_cents(), 24.623716);
+    /// ```
+    pub fn find_in(&self, scl: &Scl) -> Vec<ChordRealization> {
+        let mut realizations = (0..scl.size() as i32)
+            .filter_map(|root_degree| {
+                let root_pitch = scl.relative_pitch_of(root_degree);
+                let mut degrees = Vec::new();
+                let mut deviations = Vec::new();
+                for interval in self.intervals() {
+                    let target = root_pitch.stretched_by(interval);
+                    let degree = scl.find_by_relative_pitch(target).approx_value;
+                    if degrees.contains(&degree) {
+                        return None;
+                    }
+                    degrees.push(degree);
+                    deviations.push(scl.relative_pitch_of(degree).deviation_from(target));
+                }
+                Some(ChordRealization {
+                    degrees,
+                    deviations,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Round the errors s.t. equally good realizations keep the order of their root degrees
+        realizations
+            .sort_by_key(|realization| (realization.total_error_in_cents() * 1e6).round() as i64);
+        realizations
+    }
+
+    /// Lists all chords with `num_notes` notes within one octave whose intervals have an odd limit of at most `odd_limit`.
+    ///
+    /// The chords are ordered by their largest harmonic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::chord::Chord;
+    /// let triads = Chord::all_of_odd_limit(5, 3)
+    ///     .iter()
+    ///     .map(|chord| chord.to_string())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(triads, ["3:4:5", "4:5:6", "5:6:8", "10:12:15", "12:15:20", "15:20:24"]);
+    /// ```
+    pub fn all_of_odd_limit(odd_limit: u16, num_notes: usize) -> Vec<Chord> {
+        let mut intervals = Vec::new();
+        for numer in (1..=odd_limit).step_by(2) {
+            for denom in (1..=odd_limit).step_by(2) {
+                if let Some(interval) = octave_reduced(numer, denom) {
+                    if !intervals.contains(&interval) {
+                        intervals.push(interval);
+                    }
+                }
+            }
+        }
+        intervals.sort_by(|a, b| (a.0 * b.1).cmp(&(b.0 * a.1)));
+
+        let mut chords = Vec::new();
+        let mut selection = Vec::new();
+        collect_chords(
+            &intervals,
+            odd_limit,
+            num_notes.saturating_sub(1),
+            &mut selection,
+            &mut chords,
+        );
+
+        chords.sort_by_key(|chord: &Chord| chord.harmonics.iter().copied().max());
+        chords
+    }
+}
+
+fn collect_chords(
+    intervals: &[(u16, u16)],
+    odd_limit: u16,
+    num_remaining: usize,
+    selection: &mut Vec<(u16, u16)>,
+    chords: &mut Vec<Chord>,
+) {
+    if num_remaining == 0 {
+        if let Some(chord) = chord_of_intervals(selection) {
+            if chord.odd_limit() <= odd_limit {
+                chords.push(chord);
+            }
+        }
+        return;
+    }
+    for (index, &interval) in intervals.iter().enumerate() {
+        selection.push(interval);
+        collect_chords(
+            &intervals[index + 1..],
+            odd_limit,
+            num_remaining - 1,
+            selection,
+            chords,
+        );
+        selection.pop();
+    }
+}
+
+fn chord_of_intervals(intervals: &[(u16, u16)]) -> Option<Chord> {
+    let mut common_denom = 1u64;
+    for &(_, denom) in intervals {
+        let denom = u64::from(denom);
+        common_denom = common_denom / math::gcd_u64(common_denom, denom) * denom;
+    }
+
+    let mut harmonics = vec![common_denom];
+    for &(numer, denom) in intervals {
+        harmonics.push(u64::from(numer) * common_denom / u64::from(denom));
+    }
+    let common_factor = harmonics.iter().copied().fold(0, math::gcd_u64);
+
+    harmonics
+        .iter()
+        .map(|&harmonic| u16::try_from(harmonic / common_factor).ok())
+        .collect::<Option<_>>()
+        .map(Chord::from_harmonics)
+}
+
+/// Reduces `numer/denom` to the range (1, 2).
+fn octave_reduced(numer: u16, denom: u16) -> Option<(u16, u16)> {
+    let (mut numer, mut denom) = math::simplify_u16(numer, denom);
+    while numer >= 2 * denom {
+        denom *= 2;
+    }
+    while numer < denom {
+        numer *= 2;
+    }
+    let (numer, denom) = math::simplify_u16(numer, denom);
+    if numer == denom || numer == 2 * denom {
+        None
+    } else {
+        Some((numer, denom))
+    }
+}
+
+fn interval_odd_limit(lower: u16, upper: u16) -> u16 {
+    let (numer, denom) = math::simplify_u16(upper, lower);
+    math::odd_factors_u16(numer).max(math::odd_factors_u16(denom))
+}
+
+impl Display for Chord {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (index, harmonic) in self.harmonics.iter().enumerate() {
+            if index > 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{}", harmonic)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a colon-separated list of harmonics.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::chord::Chord;
+/// assert_eq!("4:5:6:7".parse::<Chord>().unwrap().harmonics(), [4, 5, 6, 7]);
+/// assert_eq!(
+///     "4:5:x".parse::<Chord>().unwrap_err(),
+///     "Invalid chord '4:5:x': Must be a colon-separated list of positive integers (e.g. 4:5:6:7)"
+/// );
+/// assert_eq!(
+///     "4".parse::<Chord>().unwrap_err(),
+///     "Invalid chord '4': Must contain at least two notes"
+/// );
+/// ```
+impl FromStr for Chord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let harmonics = s
+            .split(':')
+            .map(|harmonic| harmonic.trim().parse::<u16>().ok().filter(|&h| h > 0))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                format!(
+                    "Invalid chord '{}': Must be a colon-separated list of positive integers (e.g. 4:5:6:7)",
+                    s
+                )
+            })?;
+        if harmonics.len() < 2 {
+            return Err(format!(
+                "Invalid chord '{}': Must contain at least two notes",
+                s
+            ));
+        }
+        Ok(Chord::from_harmonics(harmonics))
+    }
+}
+
+/// A realization of a [`Chord`] on the degrees of a scale.
+#[derive(Clone, Debug)]
+pub struct ChordRealization {
+    /// The scale degrees of the chord tones. The first degree is the root.
+    pub degrees: Vec<i32>,
+    /// The deviation of each scale degree from its exact chord tone.
+    pub deviations: Vec<Ratio>,
+}
+
+impl ChordRealization {
+    pub fn root_degree(&self) -> i32 {
+        self.degrees[0]
+    }
+
+    /// The sum of the absolute deviations of all chord tones.
+    pub fn total_error_in_cents(&self) -> f64 {
+        self.deviations
+            .iter()
+            .map(|deviation| deviation.as_cents().abs())
+            .sum()
+    }
+
+    /// The largest absolute deviation of any chord tone.
+    pub fn max_error_in_cents(&self) -> f64 {
+        self.deviations
+            .iter()
+            .map(|deviation| deviation.as_cents().abs())
+            .fold(0.0, f64::max)
+    }
+}
@@ -0,0 +1,373 @@
+//! Sensory dissonance and harmonicity metrics for intervals, chords and scales.
+
+use crate::{math, pitch::Pitch, ratio::Ratio};
+use std::str::FromStr;
+
+/// A single sinusoidal component of a [`Spectrum`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Partial {
+    /// Frequency of the partial relative to the fundamental frequency.
+    pub ratio: f64,
+    pub amplitude: f64,
+}
+
+/// Parses a [`Partial`] given as `ratio:amplitude` or `ratio` (with an amplitude of 1).
+///
+/// # Examples
+///
+/// ```
+/// # use tune::consonance::Partial;
+/// assert_eq!(
+///     "2.01:0.5".parse::<Partial>(),
+///     Ok(Partial { ratio: 2.01, amplitude: 0.5 })
+/// );
+/// assert_eq!(
+///     "3".parse::<Partial>(),
+///     Ok(Partial { ratio: 3.0, amplitude: 1.0 })
+/// );
+/// assert_eq!(
+///     "2:loud".parse::<Partial>(),
+///     Err("Invalid partial '2:loud': Must be ratio:amplitude (e.g. 2.01:0.5)".to_owned())
+/// );
+/// ```
+impl FromStr for Partial {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, ':');
+        let ratio = parts.next().unwrap_or_default().trim().parse();
+        let amplitude = parts.next().map(|amplitude| amplitude.trim().parse());
+        match (ratio, amplitude) {
+            (Ok(ratio), None) => Ok(Partial {
+                ratio,
+                amplitude: 1.0,
+            }),
+            (Ok(ratio), Some(Ok(amplitude))) => Ok(Partial { ratio, amplitude }),
+            _ => Err(format!(
+                "Invalid partial '{}': Must be ratio:amplitude (e.g. 2.01:0.5)",
+                s
+            )),
+        }
+    }
+}
+
+/// The partials of a timbre.
+#[derive(Clone, Debug)]
+pub struct Spectrum {
+    partials: Vec<Partial>,
+}
+
+impl Spectrum {
+    pub fn from_partials(partials: Vec<Partial>) -> Self {
+        Self { partials }
+    }
+
+    /// A pure tone without any overtones.
+    pub fn sine() -> Self {
+        Self::harmonic(1, 1, |_| 1.0)
+    }
+
+    /// All harmonics with amplitudes of 1/n.
+    pub fn sawtooth(num_partials: u16) -> Self {
+        Self::harmonic(num_partials, 1, |harmonic| 1.0 / harmonic)
+    }
+
+    /// Odd harmonics with amplitudes of 1/n.
+    pub fn square(num_partials: u16) -> Self {
+        Self::harmonic(num_partials, 2, |harmonic| 1.0 / harmonic)
+    }
+
+    /// Odd harmonics with amplitudes of 1/n².
+    pub fn triangle(num_partials: u16) -> Self {
+        Self::harmonic(num_partials, 2, |harmonic| 1.0 / (harmonic * harmonic))
+    }
+
+    fn harmonic(num_partials: u16, stride: u16, amplitude: impl Fn(f64) -> f64) -> Self {
+        Self::from_partials(
+            (0..num_partials)
+                .map(|index| {
+                    let harmonic = f64::from(1 + index * stride);
+                    Partial {
+                        ratio: harmonic,
+                        amplitude: amplitude(harmonic),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    pub fn partials(&self) -> &[Partial] {
+        &self.partials
+    }
+
+    /// Calculates the sensory dissonance of two tones of the given [`Spectrum`] using the Plomp–Levelt curve as parametrized by Sethares.
+    ///
+    /// The lower tone sounds at `base`, the upper tone at `base * ratio`.
+    /// Since the critical bandwidth depends on the absolute frequency the result depends on `base` as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::consonance::Spectrum;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitched;
+    /// # use tune::ratio::Ratio;
+    /// let sawtooth = Spectrum::sawtooth(8);
+    /// let c4 = NoteLetter::C.in_octave(4).pitch();
+    /// let dissonance = |float_value| sawtooth.dissonance(c4, Ratio::from_float(float_value));
+    ///
+    /// // A just fifth is a local minimum of the dissonance curve
+    /// assert!(dissonance(1.5) < dissonance(1.47));
+    /// assert!(dissonance(1.5) < dissonance(1.53));
+    ///
+    /// // A semitone is more dissonant than a major third
+    /// assert!(dissonance(16.0 / 15.0) > dissonance(5.0 / 4.0));
+    /// ```
+    pub fn dissonance(&self, base: Pitch, ratio: Ratio) -> f64 {
+        let lower_tone = self
+            .partials
+            .iter()
+            .map(|partial| (base.as_hz() * partial.ratio, partial.amplitude));
+        let upper_tone = self.partials.iter().map(|partial| {
+            (
+                base.as_hz() * ratio.as_float() * partial.ratio,
+                partial.amplitude,
+            )
+        });
+        let all_partials = lower_tone.chain(upper_tone).collect::<Vec<_>>();
+
+        let mut dissonance = 0.0;
+        for (index, &(freq_a, amplitude_a)) in all_partials.iter().enumerate() {
+            for &(freq_b, amplitude_b) in &all_partials[index + 1..] {
+                dissonance += plomp_levelt(freq_a, amplitude_a, freq_b, amplitude_b);
+            }
+        }
+        dissonance
+    }
+}
+
+fn plomp_levelt(freq_a: f64, amplitude_a: f64, freq_b: f64, amplitude_b: f64) -> f64 {
+    const D_STAR: f64 = 0.24;
+    const S1: f64 = 0.0207;
+    const S2: f64 = 18.96;
+    const B1: f64 = 3.51;
+    const B2: f64 = 5.75;
+
+    let scaling = D_STAR / (S1 * freq_a.min(freq_b) + S2);
+    let freq_diff = (freq_a - freq_b).abs();
+    amplitude_a.min(amplitude_b)
+        * ((-B1 * scaling * freq_diff).exp() - (-B2 * scaling * freq_diff).exp())
+}
+
+/// Basic harmonic timbres, matching the sine, triangle, square and sawtooth waveforms of `microwave`.
+///
+/// Other timbres, e.g. the inharmonic or filtered waveforms of `microwave`, can be modelled with [`Spectrum::from_partials`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Timbre {
+    Sine,
+    Triangle,
+    Square,
+    Sawtooth,
+}
+
+impl Timbre {
+    pub fn spectrum(self, num_partials: u16) -> Spectrum {
+        match self {
+            Timbre::Sine => Spectrum::sine(),
+            Timbre::Triangle => Spectrum::triangle(num_partials),
+            Timbre::Square => Spectrum::square(num_partials),
+            Timbre::Sawtooth => Spectrum::sawtooth(num_partials),
+        }
+    }
+}
+
+/// Parses a [`Timbre`] name.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::consonance::Timbre;
+/// assert_eq!("saw".parse::<Timbre>(), Ok(Timbre::Sawtooth));
+/// assert_eq!(
+///     "noise".parse::<Timbre>(),
+///     Err("Unknown timbre 'noise'. Use sine, triangle, square or saw".to_owned())
+/// );
+/// ```
+impl FromStr for Timbre {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sine" => Ok(Timbre::Sine),
+            "triangle" => Ok(Timbre::Triangle),
+            "square" => Ok(Timbre::Square),
+            "saw" => Ok(Timbre::Sawtooth),
+            _ => Err(format!(
+                "Unknown timbre '{}'. Use sine, triangle, square or saw",
+                s
+            )),
+        }
+    }
+}
+
+/// Calculates the harmonic entropy of an interval in nats.
+///
+/// The interval is heard as any fraction with a Benedetti height `numer*denom` up to `max_benedetti_height`.
+/// The probability of each fraction is given by a Gaussian distribution of width `spread` around the interval, weighted by `1/sqrt(numer*denom)`.
+/// Low values indicate that the interval is heard as a simple fraction unambiguously.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::consonance;
+/// # use tune::ratio::Ratio;
+/// let entropy = |float_value| {
+///     consonance::harmonic_entropy(Ratio::from_float(float_value), Ratio::from_cents(17.0), 10000)
+/// };
+///
+/// assert!(entropy(3.0 / 2.0) < entropy(1.45));
+/// assert!(entropy(5.0 / 4.0) < entropy(1.29));
+/// assert!(entropy(2.0) < entropy(3.0 / 2.0));
+/// ```
+pub fn harmonic_entropy(ratio: Ratio, spread: Ratio, max_benedetti_height: u32) -> f64 {
+    let spread_in_cents = spread.as_cents().abs();
+    let window = Ratio::from_cents(5.0 * spread_in_cents);
+    let (lower_bound, upper_bound) = (
+        ratio.as_float() / window.as_float(),
+        ratio.as_float() * window.as_float(),
+    );
+    let max_benedetti_height = u64::from(max_benedetti_height);
+
+    let mut weights = Vec::new();
+    let mut denom = 1u64;
+    while (lower_bound * (denom * denom) as f64) <= max_benedetti_height as f64 {
+        let min_numer = (lower_bound * denom as f64).ceil().max(1.0) as u64;
+        let max_numer = (upper_bound * denom as f64).floor() as u64;
+        for numer in min_numer..=max_numer {
+            let benedetti_height = numer * denom;
+            if benedetti_height > max_benedetti_height || math::gcd_u64(numer, denom) != 1 {
+                continue;
+            }
+            let deviation_in_cents = ratio
+                .deviation_from(Ratio::from_float(numer as f64 / denom as f64))
+                .as_cents();
+            let gaussian = (-deviation_in_cents * deviation_in_cents
+                / (2.0 * spread_in_cents * spread_in_cents))
+                .exp();
+            weights.push(gaussian / (benedetti_height as f64).sqrt());
+        }
+        denom += 1;
+    }
+
+    let total_weight = weights.iter().sum::<f64>();
+    weights
+        .iter()
+        .map(|weight| weight / total_weight)
+        .filter(|&probability| probability > 0.0)
+        .map(|probability| -probability * probability.ln())
+        .sum()
+}
+
+/// Calculates the Benedetti height `numer*denom` of the simplified fraction `numer/denom`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::consonance;
+/// assert_eq!(consonance::benedetti_height(3, 2), 6);
+/// assert_eq!(consonance::benedetti_height(10, 8), 20);
+/// ```
+pub fn benedetti_height(numer: u64, denom: u64) -> u64 {
+    let gcd = math::gcd_u64(numer, denom);
+    (numer / gcd) * (denom / gcd)
+}
+
+/// Calculates the Tenney height `log2(numer*denom)` of the simplified fraction `numer/denom`.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::consonance;
+/// assert_approx_eq!(consonance::tenney_height(3, 2), 2.584963);
+/// assert_approx_eq!(consonance::tenney_height(10, 8), 4.321928);
+/// ```
+pub fn tenney_height(numer: u64, denom: u64) -> f64 {
+    (benedetti_height(numer, denom) as f64).log2()
+}
+
+/// A chord interpreted as a segment of the harmonic series.
+#[derive(Clone, Debug)]
+pub struct Otonality {
+    /// The harmonic numbers of the chord tones, e.g. `[4, 5, 6]` for a major triad.
+    pub harmonics: Vec<u32>,
+    /// The largest deviation of a chord tone from its harmonic.
+    pub max_deviation: Ratio,
+}
+
+impl Otonality {
+    /// Finds the lowest fundamental that makes all chord tones harmonics within the given `tolerance`.
+    ///
+    /// The chord tones are given relative to an arbitrary reference.
+    /// If no fundamental with all harmonics up to `max_harmonic` fits [`None`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::consonance::Otonality;
+    /// # use tune::ratio::Ratio;
+    /// let chord_of_12_edo = |semitones: &[f64]| {
+    ///     semitones.iter().copied().map(Ratio::from_semitones).collect::<Vec<_>>()
+    /// };
+    /// let tolerance = Ratio::from_cents(20.0);
+    ///
+    /// let major = Otonality::of_chord(&chord_of_12_edo(&[0.0, 4.0, 7.0]), tolerance, 32).unwrap();
+    /// assert_eq!(major.harmonics, [4, 5, 6]);
+    ///
+    /// let harmonic_seventh = [1.0, 5.0 / 4.0, 3.0 / 2.0, 7.0 / 4.0].iter().copied().map(Ratio::from_float).collect::<Vec<_>>();
+    /// let harmonic_seventh = Otonality::of_chord(&harmonic_seventh, tolerance, 32).unwrap();
+    /// assert_eq!(harmonic_seventh.harmonics, [4, 5, 6, 7]);
+    ///
+    /// // The 12-EDO minor seventh is too far away from 7/4
+    /// assert!(Otonality::of_chord(&chord_of_12_edo(&[0.0, 4.0, 7.0, 10.0]), tolerance, 8).is_none());
+    ///
+    /// let minor = Otonality::of_chord(&chord_of_12_edo(&[0.0, 3.0, 7.0]), tolerance, 32).unwrap();
+    /// assert_eq!(minor.harmonics, [10, 12, 15]);
+    /// assert!(minor.complexity() > major.complexity());
+    /// ```
+    pub fn of_chord(chord: &[Ratio], tolerance: Ratio, max_harmonic: u32) -> Option<Self> {
+        let lowest_tone = chord
+            .iter()
+            .map(|tone| tone.as_float())
+            .fold(f64::INFINITY, f64::min);
+        let tolerance_in_cents = tolerance.as_cents().abs();
+
+        (1..=max_harmonic).find_map(|root_harmonic| {
+            let mut harmonics = Vec::new();
+            let mut max_deviation = Ratio::default();
+            for tone in chord {
+                let exact_harmonic = f64::from(root_harmonic) * tone.as_float() / lowest_tone;
+                let harmonic = exact_harmonic.round();
+                let deviation = Ratio::from_float(exact_harmonic / harmonic);
+                if harmonic > f64::from(max_harmonic)
+                    || deviation.as_cents().abs() > tolerance_in_cents
+                {
+                    return None;
+                }
+                if deviation.as_cents().abs() > max_deviation.as_cents().abs() {
+                    max_deviation = deviation;
+                }
+                harmonics.push(harmonic as u32);
+            }
+            Some(Self {
+                harmonics,
+                max_deviation,
+            })
+        })
+    }
+
+    /// The complexity of the harmonic series segment, i.e. `log2` of the largest harmonic. Lower values are more otonal.
+    pub fn complexity(&self) -> f64 {
+        f64::from(self.harmonics.iter().copied().max().unwrap_or(1)).log2()
+    }
+}
@@ -1,6 +1,33 @@
+//! Period/generator chains and the naming of their notes.
+
 use crate::math;
-use std::{cmp::Ordering, convert::TryInto, iter};
+use std::{
+    cmp::Ordering,
+    convert::{TryFrom, TryInto},
+    iter,
+};
 
+/// A period/generator pair forming the skeleton of a rank-2 structure, e.g. a temperament or a MOS scale.
+///
+/// The period and the generator are measured in arbitrary steps, e.g. in steps of an EDO or in degrees of a MOS scale.
+/// If the period and the generator are not coprime, the steps are distributed over several *cycles*.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::generators::PerGen;
+/// let twelve_edo_fifths = PerGen::new(12, 7);
+///
+/// // 7 fifths up lead to the chromatic semitone
+/// assert_eq!(twelve_edo_fifths.get_generation(1), 7);
+/// assert_eq!(twelve_edo_fifths.num_cycles(), 1);
+///
+/// // The whole-tone scale splits 12-EDO into two cycles
+/// let whole_tone = PerGen::new(12, 2);
+/// assert_eq!(whole_tone.num_cycles(), 2);
+/// assert_eq!(whole_tone.get_generation(5), 2);
+/// assert_eq!(whole_tone.get_cycle(5), 1);
+/// ```
 #[derive(Clone, Debug)]
 pub struct PerGen {
     period: u16,
@@ -9,7 +36,13 @@ pub struct PerGen {
 }
 
 impl PerGen {
+    /// Creates a new [`PerGen`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period == 0`.
     pub fn new(period: u16, generator: u16) -> Self {
+        assert!(period > 0, "Period must be positive");
         Self {
             period,
             generator,
@@ -21,6 +54,10 @@ impl PerGen {
         self.period
     }
 
+    pub fn generator(&self) -> u16 {
+        self.generator
+    }
+
     pub fn num_cycles(&self) -> u16 {
         self.num_cycles
     }
@@ -29,6 +66,9 @@ impl PerGen {
         self.period / self.num_cycles
     }
 
+    /// Returns the number of generators needed to reach step `index` starting at the origin of its cycle.
+    ///
+    /// The result is always reduced to the range [0, [`PerGen::num_steps_per_cycle`]).
     pub fn get_generation(&self, index: i32) -> u16 {
         let reduced_index = index.div_euclid(i32::from(self.num_cycles));
         let reduced_period = i32::from(self.period / self.num_cycles);
@@ -40,6 +80,7 @@ impl PerGen {
             .unwrap()
     }
 
+    /// Returns the cycle step `index` belongs to.
     pub fn get_cycle(&self, index: i32) -> u16 {
         index
             .rem_euclid(i32::from(self.num_cycles))
@@ -48,29 +89,198 @@ impl PerGen {
     }
 }
 
+/// Names the notes of a rank-2 structure based on a genchain of note names.
+///
+/// The `note_names` list the natural notes in genchain order, i.e. each name is one generator above its predecessor.
+/// The natural note at `genchain_origin` is located at step 0.
+/// Walking the genchain beyond the last name adds the next-cycle sign, walking it below the first name adds the previous-cycle sign.
+/// The sharpness is the size, in steps, of the accidental displayed first in case of ambiguity, i.e. of the next-cycle sign for [`NoteOrder::Normal`] and of the previous-cycle sign for [`NoteOrder::Reversed`].
+///
+/// If `num_periods` is greater than 1, `note_names` is a concatenation of the genchains of all periods of the octave (or any other equave).
+///
+/// # Examples
+///
+/// Name the notes of 13-EDT (Bohlen–Pierce) based on the 4L 5s genchain with a generator of 3 steps:
+///
+/// ```
+/// # use tune::generators::NoteFormatter;
+/// # use tune::generators::PerGen;
+/// let per_gen = PerGen::new(13, 3);
+/// let formatter = NoteFormatter::new(
+///     ["D", "F", "H", "A", "C", "E", "G", "J", "B"]
+///         .iter()
+///         .map(|&name| name.to_owned())
+///         .collect(),
+///     1,
+///     4,
+/// )
+/// .unwrap();
+///
+/// let names = (0..13)
+///     .map(|index| formatter.get_name_by_step(&per_gen, index))
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     names,
+///     [
+///         "C", "D", "D# / Eb", "E", "F", "F# / Gb", "G", "H", "H# / Jb", "J", "A", "A# / Bb",
+///         "B"
+///     ]
+/// );
+///
+/// // Chain positions do not require an EDO, i.e. they work for any MOS tuning
+/// assert_eq!(formatter.get_name_by_chain_position(0, 0), "C");
+/// assert_eq!(formatter.get_name_by_chain_position(9, 0), "C#");
+/// assert_eq!(formatter.get_name_by_chain_position(-5, 0), "Bb");
+/// ```
 #[derive(Clone, Debug)]
 pub struct NoteFormatter {
-    pub note_names: &'static [&'static str],
-    pub genchain_origin: i16,
-    pub next_cycle_sign: char,
-    pub prev_cycle_sign: char,
-    pub sharpness: i16,
-    pub note_order: NoteOrder,
+    note_names: Vec<String>,
+    num_periods: u16,
+    genchain_origin: i16,
+    next_cycle_sign: char,
+    prev_cycle_sign: char,
+    cycle_sign: char,
+    sharpness: i16,
+    note_order: NoteOrder,
 }
 
+/// Reasons why a [`NoteFormatter`] cannot be created.
 #[derive(Clone, Debug)]
+pub enum NoteFormatterError {
+    /// The number of periods is 0.
+    NoPeriods,
+
+    /// The list of note names is empty.
+    NoNoteNames,
+
+    /// The number of note names is not a multiple of the number of periods.
+    UnevenNoteNames,
+
+    /// The genchain origin does not refer to one of the note names of a single period.
+    OriginOutOfRange,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum NoteOrder {
     Normal,
     Reversed,
 }
 
 impl NoteFormatter {
+    /// Creates a formatter with the accidentals `#`, `b` and `▲` and a sharpness of 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::generators::NoteFormatter;
+    /// # use tune::generators::NoteFormatterError;
+    /// let names = || vec!["A".to_owned(), "B".to_owned(), "C".to_owned()];
+    ///
+    /// assert!(NoteFormatter::new(names(), 1, 0).is_ok());
+    /// assert!(NoteFormatter::new(names(), 3, 0).is_ok());
+    /// assert!(matches!(
+    ///     NoteFormatter::new(names(), 0, 0),
+    ///     Err(NoteFormatterError::NoPeriods)
+    /// ));
+    /// assert!(matches!(
+    ///     NoteFormatter::new(Vec::new(), 1, 0),
+    ///     Err(NoteFormatterError::NoNoteNames)
+    /// ));
+    /// assert!(matches!(
+    ///     NoteFormatter::new(names(), 2, 0),
+    ///     Err(NoteFormatterError::UnevenNoteNames)
+    /// ));
+    /// assert!(matches!(
+    ///     NoteFormatter::new(names(), 1, 3),
+    ///     Err(NoteFormatterError::OriginOutOfRange)
+    /// ));
+    /// assert!(matches!(
+    ///     NoteFormatter::new(names(), 3, 1),
+    ///     Err(NoteFormatterError::OriginOutOfRange)
+    /// ));
+    /// assert!(matches!(
+    ///     NoteFormatter::new(names(), 1, -1),
+    ///     Err(NoteFormatterError::OriginOutOfRange)
+    /// ));
+    /// ```
+    pub fn new(
+        note_names: Vec<String>,
+        num_periods: u16,
+        genchain_origin: i16,
+    ) -> Result<Self, NoteFormatterError> {
+        if num_periods == 0 {
+            return Err(NoteFormatterError::NoPeriods);
+        }
+        if note_names.is_empty() {
+            return Err(NoteFormatterError::NoNoteNames);
+        }
+        if !note_names.len().is_multiple_of(usize::from(num_periods)) {
+            return Err(NoteFormatterError::UnevenNoteNames);
+        }
+        let num_notes_per_period = note_names.len() / usize::from(num_periods);
+        if usize::try_from(genchain_origin).map_or(true, |origin| origin >= num_notes_per_period) {
+            return Err(NoteFormatterError::OriginOutOfRange);
+        }
+        Ok(Self {
+            note_names,
+            num_periods,
+            genchain_origin,
+            next_cycle_sign: '#',
+            prev_cycle_sign: 'b',
+            cycle_sign: '▲',
+            sharpness: 1,
+            note_order: NoteOrder::Normal,
+        })
+    }
+
+    /// Sets the accidentals for walking the genchain upwards (`next_cycle_sign`), downwards (`prev_cycle_sign`) and for moving to the next cycle of a non-coprime [`PerGen`] (`cycle_sign`).
+    pub fn with_cycle_signs(
+        mut self,
+        next_cycle_sign: char,
+        prev_cycle_sign: char,
+        cycle_sign: char,
+    ) -> Self {
+        self.next_cycle_sign = next_cycle_sign;
+        self.prev_cycle_sign = prev_cycle_sign;
+        self.cycle_sign = cycle_sign;
+        self
+    }
+
+    pub fn with_sharpness(mut self, sharpness: i16) -> Self {
+        self.sharpness = sharpness;
+        self
+    }
+
+    pub fn with_note_order(mut self, note_order: NoteOrder) -> Self {
+        self.note_order = note_order;
+        self
+    }
+
+    pub fn note_names(&self) -> &[String] {
+        &self.note_names
+    }
+
+    pub fn num_periods(&self) -> u16 {
+        self.num_periods
+    }
+
+    pub fn genchain_origin(&self) -> i16 {
+        self.genchain_origin
+    }
+
+    pub fn sharpness(&self) -> i16 {
+        self.sharpness
+    }
+
+    pub fn note_order(&self) -> NoteOrder {
+        self.note_order
+    }
+
+    /// Names step `index` of the structure given by `per_gen`, e.g. the steps of an EDO.
+    ///
+    /// Enharmonic alternatives with the same number of accidentals are separated by ` / `.
     pub fn get_name_by_step(&self, per_gen: &PerGen, index: i32) -> String {
-        let num_notes = self
-            .note_names
-            .len()
-            .try_into()
-            .expect("Too many note names");
+        let num_notes = self.num_notes_per_period();
 
         let up_generation =
             i32::from(per_gen.get_generation(index)) + i32::from(self.genchain_origin);
@@ -78,36 +288,79 @@ impl NoteFormatter {
         let cycle = per_gen.get_cycle(index);
 
         if up_generation == i32::from(self.genchain_origin) {
-            return self.format_note(up_generation, cycle, 0, '\0');
+            let period = self.get_period(per_gen, index, up_generation, cycle);
+            return self.format_note(period, up_generation, cycle, 0, '\0');
         }
 
         let num_sharps: u16 = up_generation.div_euclid(num_notes).try_into().unwrap();
         let num_flats: u16 = (-down_generation.div_euclid(num_notes)).try_into().unwrap();
 
+        let sharp_name = || {
+            let period = self.get_period(per_gen, index, up_generation, cycle);
+            self.format_note(
+                period,
+                up_generation,
+                cycle,
+                num_sharps,
+                self.next_cycle_sign,
+            )
+        };
+        let flat_name = || {
+            let period = self.get_period(per_gen, index, down_generation, cycle);
+            self.format_note(
+                period,
+                down_generation,
+                cycle,
+                num_flats,
+                self.prev_cycle_sign,
+            )
+        };
+
         match num_flats.cmp(&num_sharps) {
-            Ordering::Less => {
-                self.format_note(down_generation, cycle, num_flats, self.prev_cycle_sign)
-            }
-            Ordering::Greater => {
-                self.format_note(up_generation, cycle, num_sharps, self.next_cycle_sign)
-            }
+            Ordering::Less => flat_name(),
+            Ordering::Greater => sharp_name(),
             Ordering::Equal => match (self.sharpness > 0, &self.note_order) {
-                (true, NoteOrder::Normal) | (false, NoteOrder::Reversed) => format!(
-                    "{} / {}",
-                    self.format_note(up_generation, cycle, num_sharps, self.next_cycle_sign),
-                    self.format_note(down_generation, cycle, num_flats, self.prev_cycle_sign),
-                ),
-                (false, NoteOrder::Normal) | (true, NoteOrder::Reversed) => format!(
-                    "{} / {}",
-                    self.format_note(down_generation, cycle, num_flats, self.prev_cycle_sign),
-                    self.format_note(up_generation, cycle, num_sharps, self.next_cycle_sign),
-                ),
+                (true, NoteOrder::Normal) | (false, NoteOrder::Reversed) => {
+                    format!("{} / {}", sharp_name(), flat_name())
+                }
+                (false, NoteOrder::Normal) | (true, NoteOrder::Reversed) => {
+                    format!("{} / {}", flat_name(), sharp_name())
+                }
             },
         }
     }
 
+    /// Names the note `chain_position` generators above the origin using the note names of the given `period`.
+    ///
+    /// In contrast to [`NoteFormatter::get_name_by_step`], this method does not depend on an EDO and can be used to name the notes of any MOS tuning.
+    pub fn get_name_by_chain_position(&self, chain_position: i32, period: i32) -> String {
+        let num_notes = self.num_notes_per_period();
+        let generation = chain_position + i32::from(self.genchain_origin);
+        let num_accidentals = generation.div_euclid(num_notes);
+        let period = period.rem_euclid(i32::from(self.num_periods));
+
+        if num_accidentals >= 0 {
+            self.format_note(
+                period,
+                generation,
+                0,
+                num_accidentals.try_into().unwrap(),
+                self.next_cycle_sign,
+            )
+        } else {
+            self.format_note(
+                period,
+                generation,
+                0,
+                (-num_accidentals).try_into().unwrap(),
+                self.prev_cycle_sign,
+            )
+        }
+    }
+
     fn format_note(
         &self,
+        period: i32,
         generation: i32,
         cycle: u16,
         num_accidentals: u16,
@@ -115,18 +368,45 @@ impl NoteFormatter {
     ) -> String {
         format!(
             "{}{}{}",
-            repeated_char('▲', cycle),
-            self.note_name(generation),
+            repeated_char(self.cycle_sign, cycle),
+            self.note_name(period, generation),
             repeated_char(accidental, num_accidentals),
         )
     }
 
-    fn note_name(&self, generation: i32) -> &'static str {
-        let index: usize = generation
-            .rem_euclid(self.note_names.len().try_into().unwrap())
+    /// Determines the period the note letter belongs to which can differ from the period of `index` when accidentals are applied.
+    fn get_period(&self, per_gen: &PerGen, index: i32, generation: i32, cycle: u16) -> i32 {
+        let num_notes = self.num_notes_per_period();
+        let period = i32::from(per_gen.period());
+        let num_accidentals = generation.div_euclid(num_notes);
+        let natural_pitch = ((generation.rem_euclid(num_notes) - i32::from(self.genchain_origin))
+            * i32::from(per_gen.generator))
+        .rem_euclid(period);
+        let value_of_next_cycle_sign = match self.note_order {
+            NoteOrder::Normal => i32::from(self.sharpness),
+            NoteOrder::Reversed => -i32::from(self.sharpness),
+        };
+        let pitch = natural_pitch + num_accidentals * value_of_next_cycle_sign + i32::from(cycle);
+        (index - pitch)
+            .div_euclid(period)
+            .rem_euclid(i32::from(self.num_periods))
+    }
+
+    fn note_name(&self, period: i32, generation: i32) -> &str {
+        let num_notes = self.num_notes_per_period();
+        let index: usize = (period * num_notes + generation.rem_euclid(num_notes))
             .try_into()
             .unwrap();
-        self.note_names[index]
+        &self.note_names[index]
+    }
+
+    fn num_notes_per_period(&self) -> i32 {
+        let num_notes: i32 = self
+            .note_names
+            .len()
+            .try_into()
+            .expect("Too many note names");
+        num_notes / i32::from(self.num_periods)
     }
 }
 
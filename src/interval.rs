@@ -0,0 +1,304 @@
+//! Names of just intonation intervals and equal temperament steps.
+
+use crate::ratio::Ratio;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// An interval with a name that musicians would use to talk about it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NamedInterval {
+    pub name: &'static str,
+    pub value: IntervalValue,
+}
+
+/// The exact value of a [`NamedInterval`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum IntervalValue {
+    /// A just intonation interval, i.e. a frequency ratio of two integers.
+    Just { numer: u32, denom: u32 },
+    /// A number of equal steps of an octave.
+    EdoSteps {
+        num_steps: u16,
+        num_steps_per_octave: u16,
+    },
+}
+
+impl NamedInterval {
+    const fn ji(numer: u32, denom: u32, name: &'static str) -> Self {
+        Self {
+            name,
+            value: IntervalValue::Just { numer, denom },
+        }
+    }
+
+    const fn edo(num_steps: u16, num_steps_per_octave: u16, name: &'static str) -> Self {
+        Self {
+            name,
+            value: IntervalValue::EdoSteps {
+                num_steps,
+                num_steps_per_octave,
+            },
+        }
+    }
+
+    /// Returns the [`Ratio`] of the given interval.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::interval;
+    /// let syntonic_comma = &interval::JI_INTERVALS[3];
+    /// assert_eq!(syntonic_comma.name, "syntonic comma");
+    /// assert_approx_eq!(syntonic_comma.ratio().as_cents(), 21.506290);
+    ///
+    /// let fifth_of_12_edo = &interval::EDO_STEPS[7];
+    /// assert_eq!(fifth_of_12_edo.name, "perfect fifth");
+    /// assert_approx_eq!(fifth_of_12_edo.ratio().as_cents(), 700.0);
+    /// ```
+    pub fn ratio(&self) -> Ratio {
+        match self.value {
+            IntervalValue::Just { numer, denom } => {
+                Ratio::from_float(f64::from(numer) / f64::from(denom))
+            }
+            IntervalValue::EdoSteps {
+                num_steps,
+                num_steps_per_octave,
+            } => Ratio::octave()
+                .divided_into_equal_steps(num_steps_per_octave)
+                .repeated(num_steps),
+        }
+    }
+}
+
+/// Formats the interval value followed by its name.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::interval;
+/// assert_eq!(interval::JI_INTERVALS[3].to_string(), "81/80 syntonic comma");
+/// assert_eq!(interval::EDO_STEPS[7].to_string(), "7\\12 perfect fifth");
+/// ```
+impl Display for NamedInterval {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.value {
+            IntervalValue::Just { numer, denom } => write!(f, "{}/{} {}", numer, denom, self.name),
+            IntervalValue::EdoSteps {
+                num_steps,
+                num_steps_per_octave,
+            } => write!(f, "{}\\{} {}", num_steps, num_steps_per_octave, self.name),
+        }
+    }
+}
+
+/// Curated list of named just intonation intervals between 1/1 and 2/1, ordered by size.
+pub const JI_INTERVALS: &[NamedInterval] = &[
+    NamedInterval::ji(1, 1, "unison"),
+    NamedInterval::ji(32805, 32768, "schisma"),
+    NamedInterval::ji(2048, 2025, "diaschisma"),
+    NamedInterval::ji(81, 80, "syntonic comma"),
+    NamedInterval::ji(531_441, 524_288, "Pythagorean comma"),
+    NamedInterval::ji(64, 63, "septimal comma"),
+    NamedInterval::ji(50, 49, "jubilisma"),
+    NamedInterval::ji(49, 48, "slendro diesis"),
+    NamedInterval::ji(128, 125, "lesser diesis"),
+    NamedInterval::ji(36, 35, "septimal diesis"),
+    NamedInterval::ji(33, 32, "undecimal quarter tone"),
+    NamedInterval::ji(648, 625, "greater diesis"),
+    NamedInterval::ji(28, 27, "septimal third tone"),
+    NamedInterval::ji(25, 24, "chromatic semitone"),
+    NamedInterval::ji(22, 21, "undecimal minor second"),
+    NamedInterval::ji(21, 20, "septimal minor second"),
+    NamedInterval::ji(256, 243, "Pythagorean limma"),
+    NamedInterval::ji(135, 128, "major chroma"),
+    NamedInterval::ji(16, 15, "minor second"),
+    NamedInterval::ji(15, 14, "septimal diatonic semitone"),
+    NamedInterval::ji(14, 13, "tridecimal minor second"),
+    NamedInterval::ji(13, 12, "tridecimal neutral second"),
+    NamedInterval::ji(12, 11, "undecimal neutral second"),
+    NamedInterval::ji(11, 10, "undecimal submajor second"),
+    NamedInterval::ji(10, 9, "minor whole tone"),
+    NamedInterval::ji(9, 8, "major whole tone"),
+    NamedInterval::ji(8, 7, "septimal whole tone"),
+    NamedInterval::ji(7, 6, "septimal minor third"),
+    NamedInterval::ji(13, 11, "tridecimal minor third"),
+    NamedInterval::ji(32, 27, "Pythagorean minor third"),
+    NamedInterval::ji(6, 5, "minor third"),
+    NamedInterval::ji(11, 9, "undecimal neutral third"),
+    NamedInterval::ji(5, 4, "major third"),
+    NamedInterval::ji(81, 64, "Pythagorean major third"),
+    NamedInterval::ji(14, 11, "undecimal diminished fourth"),
+    NamedInterval::ji(9, 7, "septimal major third"),
+    NamedInterval::ji(21, 16, "septimal narrow fourth"),
+    NamedInterval::ji(4, 3, "perfect fourth"),
+    NamedInterval::ji(27, 20, "acute fourth"),
+    NamedInterval::ji(11, 8, "undecimal superfourth"),
+    NamedInterval::ji(7, 5, "septimal tritone"),
+    NamedInterval::ji(45, 32, "augmented fourth"),
+    NamedInterval::ji(64, 45, "diminished fifth"),
+    NamedInterval::ji(729, 512, "Pythagorean tritone"),
+    NamedInterval::ji(10, 7, "greater septimal tritone"),
+    NamedInterval::ji(16, 11, "undecimal subfifth"),
+    NamedInterval::ji(40, 27, "grave fifth"),
+    NamedInterval::ji(3, 2, "perfect fifth"),
+    NamedInterval::ji(32, 21, "septimal wide fifth"),
+    NamedInterval::ji(14, 9, "septimal minor sixth"),
+    NamedInterval::ji(11, 7, "undecimal augmented fifth"),
+    NamedInterval::ji(128, 81, "Pythagorean minor sixth"),
+    NamedInterval::ji(8, 5, "minor sixth"),
+    NamedInterval::ji(13, 8, "tridecimal neutral sixth"),
+    NamedInterval::ji(18, 11, "undecimal neutral sixth"),
+    NamedInterval::ji(5, 3, "major sixth"),
+    NamedInterval::ji(27, 16, "Pythagorean major sixth"),
+    NamedInterval::ji(12, 7, "septimal major sixth"),
+    NamedInterval::ji(7, 4, "septimal minor seventh"),
+    NamedInterval::ji(16, 9, "Pythagorean minor seventh"),
+    NamedInterval::ji(9, 5, "just minor seventh"),
+    NamedInterval::ji(20, 11, "undecimal large minor seventh"),
+    NamedInterval::ji(11, 6, "undecimal neutral seventh"),
+    NamedInterval::ji(15, 8, "major seventh"),
+    NamedInterval::ji(243, 128, "Pythagorean major seventh"),
+    NamedInterval::ji(27, 14, "septimal major seventh"),
+    NamedInterval::ji(2, 1, "octave"),
+];
+
+/// Curated list of named equal temperament steps: the 12-EDO intervals followed by common microtonal steps.
+pub const EDO_STEPS: &[NamedInterval] = &[
+    NamedInterval::edo(0, 12, "unison"),
+    NamedInterval::edo(1, 12, "semitone"),
+    NamedInterval::edo(2, 12, "whole tone"),
+    NamedInterval::edo(3, 12, "minor third"),
+    NamedInterval::edo(4, 12, "major third"),
+    NamedInterval::edo(5, 12, "perfect fourth"),
+    NamedInterval::edo(6, 12, "tritone"),
+    NamedInterval::edo(7, 12, "perfect fifth"),
+    NamedInterval::edo(8, 12, "minor sixth"),
+    NamedInterval::edo(9, 12, "major sixth"),
+    NamedInterval::edo(10, 12, "minor seventh"),
+    NamedInterval::edo(11, 12, "major seventh"),
+    NamedInterval::edo(12, 12, "octave"),
+    NamedInterval::edo(1, 1200, "cent"),
+    NamedInterval::edo(1, 53, "Holdrian comma"),
+    NamedInterval::edo(1, 72, "twelfth tone"),
+    NamedInterval::edo(1, 36, "sixth tone"),
+    NamedInterval::edo(1, 24, "quarter tone"),
+    NamedInterval::edo(1, 18, "third tone"),
+    NamedInterval::edo(3, 24, "neutral second"),
+    NamedInterval::edo(7, 24, "neutral third"),
+    NamedInterval::edo(17, 24, "neutral sixth"),
+    NamedInterval::edo(21, 24, "neutral seventh"),
+];
+
+/// Finds the named just intonation interval closest to `ratio` that deviates by at most `tolerance`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::interval;
+/// # use tune::ratio::Ratio;
+/// let tolerance = Ratio::from_cents(5.0);
+///
+/// let name_of = |cents| {
+///     interval::find_ji_interval(Ratio::from_cents(cents), tolerance).map(|interval| interval.name)
+/// };
+///
+/// assert_eq!(name_of(386.0), Some("major third"));
+/// assert_eq!(name_of(969.0), Some("septimal minor seventh"));
+/// assert_eq!(name_of(350.0), Some("undecimal neutral third"));
+/// assert_eq!(name_of(21.0), Some("syntonic comma"));
+/// assert_eq!(name_of(450.0), None);
+/// ```
+pub fn find_ji_interval(ratio: Ratio, tolerance: Ratio) -> Option<&'static NamedInterval> {
+    find_closest(JI_INTERVALS, ratio, tolerance)
+}
+
+/// Finds the named just intonation interval with exactly the given (unsimplified) numerator and denominator.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::interval;
+/// assert_eq!(interval::find_ji_fraction(7, 4).unwrap().name, "septimal minor seventh");
+/// assert_eq!(interval::find_ji_fraction(10, 8).unwrap().name, "major third");
+/// assert!(interval::find_ji_fraction(17, 16).is_none());
+/// ```
+pub fn find_ji_fraction(numer: u32, denom: u32) -> Option<&'static NamedInterval> {
+    JI_INTERVALS.iter().find(|interval| match interval.value {
+        IntervalValue::Just {
+            numer: interval_numer,
+            denom: interval_denom,
+        } => {
+            u64::from(numer) * u64::from(interval_denom)
+                == u64::from(denom) * u64::from(interval_numer)
+        }
+        IntervalValue::EdoSteps { .. } => false,
+    })
+}
+
+/// Finds the named equal temperament step closest to `ratio` that deviates by at most `tolerance`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::interval;
+/// # use tune::ratio::Ratio;
+/// let tolerance = Ratio::from_cents(1.0);
+///
+/// let fifth = interval::find_edo_step(Ratio::from_cents(700.0), tolerance).unwrap();
+/// assert_eq!(fifth.to_string(), "7\\12 perfect fifth");
+///
+/// let neutral_third = interval::find_edo_step(Ratio::from_cents(350.0), tolerance).unwrap();
+/// assert_eq!(neutral_third.to_string(), "7\\24 neutral third");
+///
+/// assert!(interval::find_edo_step(Ratio::from_cents(386.0), tolerance).is_none());
+/// ```
+pub fn find_edo_step(ratio: Ratio, tolerance: Ratio) -> Option<&'static NamedInterval> {
+    find_closest(EDO_STEPS, ratio, tolerance)
+}
+
+fn find_closest(
+    intervals: &'static [NamedInterval],
+    ratio: Ratio,
+    tolerance: Ratio,
+) -> Option<&'static NamedInterval> {
+    let deviation_of =
+        |interval: &NamedInterval| ratio.deviation_from(interval.ratio()).as_cents().abs();
+
+    intervals
+        .iter()
+        .map(|interval| (interval, deviation_of(interval)))
+        .filter(|&(_, deviation)| deviation <= tolerance.as_cents().abs())
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(interval, _)| interval)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math;
+
+    #[test]
+    fn ji_intervals_are_simplified_and_sorted() {
+        for interval in JI_INTERVALS {
+            if let IntervalValue::Just { numer, denom } = interval.value {
+                assert_eq!(
+                    math::gcd_u64(numer.into(), denom.into()),
+                    1,
+                    "{} is not simplified",
+                    interval
+                );
+            }
+        }
+
+        for window in JI_INTERVALS.windows(2) {
+            assert!(
+                window[0].ratio().as_float() < window[1].ratio().as_float(),
+                "{} should be smaller than {}",
+                window[0],
+                window[1]
+            );
+        }
+    }
+}
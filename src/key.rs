@@ -29,6 +29,11 @@ impl PianoKey {
     }
 }
 
+/// An isomorphic keyboard mapping 2D coordinates to [`PianoKey`]s.
+///
+/// Moving one key to the right adds `primary_step`. Moving one row up subtracts `secondary_step`.
+/// On a hexagonal grid where every row is shifted by half a key to the right of the row above, moving one row up means moving to the upper left neighbor.
+/// See [`LayoutPreset`](crate::layout::LayoutPreset) for well-known layouts.
 #[derive(Debug, Clone)]
 pub struct Keyboard {
     root_key: PianoKey,
@@ -0,0 +1,936 @@
+//! Isomorphic keyboard layouts, their export to hardware controllers and the search for playable layouts.
+
+use crate::{
+    key::{Keyboard, PianoKey},
+    pitch::{Pitch, Pitched},
+    ratio::Ratio,
+    temperament::EqualTemperament,
+    tuning::{Approximation, Tuning},
+};
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+/// Well-known isomorphic layouts.
+///
+/// The steps of each layout are derived from the primary step (whole tone), the secondary step (limma) and the fifth of an [`EqualTemperament`].
+/// Rows are horizontal and every row is shifted by half a key to the right of the row above, as on hexagonal controllers.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum LayoutPreset {
+    /// Whole tones to the right, fifths to the upper right and fourths to the upper left.
+    WickiHayden,
+
+    /// Whole tones to the right and diatonic semitones (limmas) to the upper right.
+    Bosanquet,
+
+    /// Whole tones to the right and chromatic semitones to the upper right.
+    Janko,
+
+    /// Fifths to the right, major thirds to the upper right and minor thirds to the lower right.
+    HarmonicTable,
+}
+
+impl LayoutPreset {
+    pub const ALL: [LayoutPreset; 4] = [
+        LayoutPreset::WickiHayden,
+        LayoutPreset::Bosanquet,
+        LayoutPreset::Janko,
+        LayoutPreset::HarmonicTable,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LayoutPreset::WickiHayden => "wicki-hayden",
+            LayoutPreset::Bosanquet => "bosanquet",
+            LayoutPreset::Janko => "janko",
+            LayoutPreset::HarmonicTable => "harmonic-table",
+        }
+    }
+
+    /// Creates a [`Keyboard`] that realizes the preset in the given temperament.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::key::PianoKey;
+    /// # use tune::layout::LayoutPreset;
+    /// # use tune::temperament::EqualTemperament;
+    /// let temperament = EqualTemperament::find().by_edo(12);
+    /// let keyboard = LayoutPreset::WickiHayden.keyboard(PianoKey::from_midi_number(60), &temperament);
+    ///
+    /// let right = keyboard.get_key(1, 0).midi_number();
+    /// let upper_left = keyboard.get_key(0, 1).midi_number();
+    /// let upper_right = keyboard.get_key(1, 1).midi_number();
+    /// assert_eq!((right, upper_right, upper_left), (62, 67, 65));
+    ///
+    /// let temperament = EqualTemperament::find().by_edo(31);
+    /// let keyboard = LayoutPreset::Bosanquet.keyboard(PianoKey::from_midi_number(0), &temperament);
+    /// assert_eq!((keyboard.primary_step(), keyboard.secondary_step()), (5, 2));
+    /// ```
+    pub fn keyboard(self, root_key: PianoKey, temperament: &EqualTemperament) -> Keyboard {
+        let whole_tone = temperament.primary_step();
+        let limma = temperament.secondary_step();
+        let fifth = temperament.num_steps_per_fifth() as i16;
+
+        let (right, upper_right) = match self {
+            LayoutPreset::WickiHayden => (whole_tone, fifth),
+            LayoutPreset::Bosanquet => (whole_tone, limma),
+            LayoutPreset::Janko => (whole_tone, whole_tone - limma),
+            LayoutPreset::HarmonicTable => (fifth, 2 * whole_tone),
+        };
+
+        Keyboard::root_at(root_key).with_steps(right, right - upper_right)
+    }
+}
+
+/// Parses the names `wicki-hayden`, `bosanquet`, `janko` and `harmonic-table` into a [`LayoutPreset`].
+///
+/// # Examples
+///
+/// ```
+/// # use tune::layout::LayoutPreset;
+/// assert_eq!("janko".parse(), Ok(LayoutPreset::Janko));
+/// assert_eq!(
+///     "piano".parse::<LayoutPreset>(),
+///     Err("Unknown layout 'piano'. Use wicki-hayden, bosanquet, janko or harmonic-table".to_owned())
+/// );
+/// ```
+impl FromStr for LayoutPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LayoutPreset::ALL
+            .iter()
+            .copied()
+            .find(|preset| preset.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown layout '{}'. Use wicki-hayden, bosanquet, janko or harmonic-table",
+                    s
+                )
+            })
+    }
+}
+
+/// A key color in the 24-bit RGB format. Displayed as lowercase hex digits, e.g. `ffd700`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Rgb {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    fn from_hsv(hue: f64, saturation: f64, value: f64) -> Self {
+        let sector = (hue / 60.0).rem_euclid(6.0);
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (red, green, blue) = match sector as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let to_u8 = |component: f64| ((component + value - chroma) * 255.0).round() as u8;
+        Self::new(to_u8(red), to_u8(green), to_u8(blue))
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+pub(crate) const ROOT_COLOR: Rgb = Rgb::new(0xff, 0xd7, 0x00);
+pub(crate) const NATURAL_COLOR: Rgb = Rgb::new(0xff, 0xff, 0xff);
+const SHARP_COLOR: Rgb = Rgb::new(0x5b, 0x9b, 0xd5);
+const DOUBLE_SHARP_COLOR: Rgb = Rgb::new(0x1f, 0x4e, 0x79);
+const FLAT_COLOR: Rgb = Rgb::new(0x70, 0xad, 0x47);
+const DOUBLE_FLAT_COLOR: Rgb = Rgb::new(0x37, 0x56, 0x23);
+const OTHER_COLOR: Rgb = Rgb::new(0xa5, 0xa5, 0xa5);
+
+/// Strategy for coloring the keys of a layout based on their distance in steps from the root key.
+#[derive(Clone, Debug)]
+pub enum KeyColoring {
+    /// Every degree of a scale with the given number of notes per period gets its own hue. The root is highlighted in gold.
+    ScaleDegree(u16),
+
+    /// Natural notes are white, sharpened notes blue and flattened notes green according to the heptatonic note names of the temperament.
+    NoteName(EqualTemperament),
+}
+
+impl KeyColoring {
+    /// Determines the color of the key `degree` steps above the root key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::layout::KeyColoring;
+    /// # use tune::temperament::EqualTemperament;
+    /// let by_degree = KeyColoring::ScaleDegree(12);
+    /// assert_eq!(by_degree.color_of(0).to_string(), "ffd700");
+    /// assert_eq!(by_degree.color_of(12).to_string(), "ffd700");
+    /// assert_eq!(by_degree.color_of(6).to_string(), "5ce5e5");
+    ///
+    /// // Degree 0 is D
+    /// let by_name = KeyColoring::NoteName(EqualTemperament::find().by_edo(31));
+    /// assert_eq!(by_name.color_of(0).to_string(), "ffffff"); // D
+    /// assert_eq!(by_name.color_of(2).to_string(), "5b9bd5"); // D#
+    /// assert_eq!(by_name.color_of(1).to_string(), "375623"); // Ebb
+    /// ```
+    pub fn color_of(&self, degree: i32) -> Rgb {
+        match self {
+            KeyColoring::ScaleDegree(num_notes) => {
+                let num_notes = i32::from((*num_notes).max(1));
+                match degree.rem_euclid(num_notes) {
+                    0 => ROOT_COLOR,
+                    degree => {
+                        Rgb::from_hsv(f64::from(degree) / f64::from(num_notes) * 360.0, 0.6, 0.9)
+                    }
+                }
+            }
+            KeyColoring::NoteName(temperament) => {
+                let name = temperament.get_heptatonic_name(degree);
+                let name = name.split(" / ").next().unwrap_or_default();
+                let num_raised = name.chars().filter(|&c| c == '#' || c == '+').count();
+                let num_lowered = name.chars().filter(|&c| c == 'b' || c == '-').count();
+                let num_letters = name.chars().filter(char::is_ascii_uppercase).count();
+                match (num_raised, num_lowered) {
+                    (0, 0) if name.chars().count() == num_letters => NATURAL_COLOR,
+                    (1, 0) => SHARP_COLOR,
+                    (_, 0) => DOUBLE_SHARP_COLOR,
+                    (0, 1) => FLAT_COLOR,
+                    (0, _) => DOUBLE_FLAT_COLOR,
+                    _ => OTHER_COLOR,
+                }
+            }
+        }
+    }
+}
+
+const LUMATONE_NUM_BOARDS: i16 = 5;
+const LUMATONE_BOARD_OFFSET: (i16, i16) = (6, 2);
+/// First column and number of keys of each row of a Lumatone board, from top to bottom.
+const LUMATONE_BOARD_ROWS: [(i16, i16); 11] = [
+    (0, 2),
+    (0, 5),
+    (0, 6),
+    (0, 6),
+    (0, 6),
+    (0, 6),
+    (0, 6),
+    (0, 6),
+    (0, 6),
+    (1, 5),
+    (4, 2),
+];
+/// The root key is located at the center of the middle board (board 2, row 5, column 2).
+const LUMATONE_ROOT_LOCATION: (i16, i16) = (2 * 6 + 2, 2 * 2 + 5);
+
+/// Creates a Lumatone key mapping (.ltn file) for a [`Keyboard`].
+///
+/// The root key of the keyboard is placed at the center of the middle board.
+/// Keys are mapped to note numbers and channels by splitting the linear key range into chunks of `notes_per_channel` notes, starting at channel 1.
+/// Keys outside of the 16 available channels are disabled.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::key::{Keyboard, PianoKey};
+/// # use tune::layout::{KeyColoring, LumatoneExport};
+/// let keyboard = Keyboard::root_at(PianoKey::from_midi_number(60)).with_steps(5, 3);
+/// let coloring = KeyColoring::ScaleDegree(31);
+/// let export = LumatoneExport::new(&keyboard, &coloring).to_string();
+///
+/// assert!(export.contains("[Board2]\nKey_0=35\nChan_0=1\n"));
+/// // The root key
+/// assert!(export.contains("Key_27=60\nChan_27=1\nCol_27=ffd700\nKTyp_27=1\n"));
+/// assert_eq!(export.matches("Key_").count(), 280);
+///
+/// // Keys below note 0 are disabled
+/// assert!(export.starts_with("[Board0]\nKey_0=0\nChan_0=1\nCol_0=000000\nKTyp_0=0\n"));
+/// ```
+pub struct LumatoneExport<'a> {
+    keyboard: &'a Keyboard,
+    coloring: &'a KeyColoring,
+    notes_per_channel: u16,
+}
+
+impl<'a> LumatoneExport<'a> {
+    pub fn new(keyboard: &'a Keyboard, coloring: &'a KeyColoring) -> Self {
+        Self {
+            keyboard,
+            coloring,
+            notes_per_channel: 128,
+        }
+    }
+
+    /// Sets the number of notes per MIDI channel, e.g. the size of the scale for channel-per-period mappings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `notes_per_channel` is not in the range 1..=128.
+    pub fn with_notes_per_channel(mut self, notes_per_channel: u16) -> Self {
+        assert!(
+            (1..=128).contains(&notes_per_channel),
+            "Number of notes per channel must be between 1 and 128"
+        );
+        self.notes_per_channel = notes_per_channel;
+        self
+    }
+
+    pub fn notes_per_channel(&self) -> u16 {
+        self.notes_per_channel
+    }
+
+    /// The mappings of all keys, board by board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::key::{Keyboard, PianoKey};
+    /// # use tune::layout::{KeyColoring, LumatoneExport};
+    /// let keyboard = Keyboard::root_at(PianoKey::from_midi_number(60)).with_steps(5, 3);
+    /// let coloring = KeyColoring::ScaleDegree(31);
+    /// let keys = LumatoneExport::new(&keyboard, &coloring).keys();
+    ///
+    /// assert_eq!(keys.len(), 280);
+    /// assert_eq!((keys[139].board, keys[139].index), (2, 27));
+    /// assert_eq!(keys[139].note_and_channel, Some((60, 1)));
+    /// assert_eq!(keys[139].color.to_string(), "ffd700");
+    /// assert_eq!(keys[0].note_and_channel, None);
+    /// ```
+    pub fn keys(&self) -> Vec<LumatoneKey> {
+        let root_key = self.keyboard.get_key(0, 0);
+        let notes_per_channel = i32::from(self.notes_per_channel);
+
+        (0..LUMATONE_NUM_BOARDS)
+            .flat_map(|board| {
+                LUMATONE_BOARD_ROWS
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(row, &(first_column, num_keys))| {
+                        (first_column..first_column + num_keys)
+                            .map(move |column| (column, row as i16))
+                    })
+                    .enumerate()
+                    .map(move |(index, (column, row))| {
+                        let x = board * LUMATONE_BOARD_OFFSET.0 + column - LUMATONE_ROOT_LOCATION.0;
+                        let y = LUMATONE_ROOT_LOCATION.1 - board * LUMATONE_BOARD_OFFSET.1 - row;
+                        let key = self.keyboard.get_key(x, y);
+
+                        let channel = key.midi_number().div_euclid(notes_per_channel);
+                        let (note_and_channel, color) = if (0..16).contains(&channel) {
+                            let note = key.midi_number().rem_euclid(notes_per_channel);
+                            let color = self.coloring.color_of(root_key.num_keys_before(key));
+                            (Some((note, channel + 1)), color)
+                        } else {
+                            (None, Rgb::new(0x00, 0x00, 0x00))
+                        };
+
+                        LumatoneKey {
+                            board,
+                            index,
+                            note_and_channel,
+                            color,
+                        }
+                    })
+            })
+            .collect()
+    }
+}
+
+/// The mapping of a single key of a [`LumatoneExport`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LumatoneKey {
+    pub board: i16,
+    /// Index of the key within its board, counted row by row from the top.
+    pub index: usize,
+    /// The note number and the MIDI channel (1-16) of the key or [`None`] if the key is disabled since it lies outside of the 16 available channels.
+    pub note_and_channel: Option<(i32, i32)>,
+    pub color: Rgb,
+}
+
+impl Display for LumatoneExport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for key in self.keys() {
+            if key.index == 0 {
+                writeln!(f, "[Board{}]", key.board)?;
+            }
+            let index = key.index;
+            match key.note_and_channel {
+                Some((note, channel)) => {
+                    writeln!(f, "Key_{}={}", index, note)?;
+                    writeln!(f, "Chan_{}={}", index, channel)?;
+                    writeln!(f, "Col_{}={}", index, key.color)?;
+                    writeln!(f, "KTyp_{}=1", index)?;
+                }
+                None => {
+                    writeln!(f, "Key_{}=0", index)?;
+                    writeln!(f, "Chan_{}=1", index)?;
+                    writeln!(f, "Col_{}={}", index, key.color)?;
+                    writeln!(f, "KTyp_{}=0", index)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+const LINNSTRUMENT_NUM_ROWS: i16 = 8;
+
+/// Describes the settings and the resulting note grid of a LinnStrument realizing a [`Keyboard`].
+///
+/// The LinnStrument is a rectangular grid with the root key in the lower left corner.
+/// Moving one pad to the right adds the column offset (the primary step), moving one row up adds the row offset (the inverse of the secondary step).
+///
+/// # Examples
+///
+/// ```
+/// # use tune::key::{Keyboard, PianoKey};
+/// # use tune::layout::LinnStrumentExport;
+/// let keyboard = Keyboard::root_at(PianoKey::from_midi_number(30)).with_steps(1, -5);
+/// let export = LinnStrumentExport::new(&keyboard).with_num_columns(16);
+///
+/// assert_eq!(export.column_offset(), 1);
+/// assert_eq!(export.row_offset(), 5);
+/// assert_eq!(export.lowest_note(), 30);
+/// assert_eq!(export.notes()[0][..3], [65, 66, 67]);
+/// assert_eq!(export.notes()[7][..3], [30, 31, 32]);
+/// ```
+pub struct LinnStrumentExport<'a> {
+    keyboard: &'a Keyboard,
+    num_columns: i16,
+}
+
+impl<'a> LinnStrumentExport<'a> {
+    /// Creates an export for a LinnStrument with 25 columns. Use [`LinnStrumentExport::with_num_columns`] for the LinnStrument 128.
+    pub fn new(keyboard: &'a Keyboard) -> Self {
+        Self {
+            keyboard,
+            num_columns: 25,
+        }
+    }
+
+    pub fn with_num_columns(mut self, num_columns: i16) -> Self {
+        self.num_columns = num_columns;
+        self
+    }
+
+    pub fn column_offset(&self) -> i32 {
+        i32::from(self.keyboard.primary_step())
+    }
+
+    pub fn row_offset(&self) -> i32 {
+        -i32::from(self.keyboard.secondary_step())
+    }
+
+    pub fn lowest_note(&self) -> i32 {
+        self.keyboard.get_key(0, 0).midi_number()
+    }
+
+    /// Note numbers of all pads, from the top row to the bottom row.
+    pub fn notes(&self) -> Vec<Vec<i32>> {
+        (0..LINNSTRUMENT_NUM_ROWS)
+            .rev()
+            .map(|row| {
+                (0..self.num_columns)
+                    .map(|column| self.keyboard.get_key(column, row).midi_number())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Display for LinnStrumentExport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Column offset: {}", self.column_offset())?;
+        writeln!(f, "Row offset: {}", self.row_offset())?;
+        writeln!(f, "Lowest note: {}", self.lowest_note())?;
+        writeln!(f)?;
+        for (row, notes) in self.notes().iter().enumerate() {
+            write!(f, "Row {} |", LINNSTRUMENT_NUM_ROWS as usize - row)?;
+            for &note in notes {
+                if (0..128).contains(&note) {
+                    write!(f, "{:>4}", note)?;
+                } else {
+                    write!(f, "{:>4}", "--")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+const COVERAGE_WEIGHT: f64 = 10.0;
+const DUPLICATES_WEIGHT: f64 = 10.0;
+const OCTAVE_DIRECTION_WEIGHT: f64 = 2.0;
+
+/// Rates all isomorphic layouts of an EDO that fit into a rectangular grid of keys.
+///
+/// Every pair of a primary step in `1..=num_steps_per_octave` and a secondary step in `-num_steps_per_octave..=num_steps_per_octave` is a candidate.
+/// The rating of a candidate is the sum of the following penalties, lower being better:
+///
+/// - 10 × the fraction of scale degrees that are missing in the grid
+/// - 10 × the fraction of keys in the grid that duplicate the pitch of another key
+/// - 2 if the closest octave does not point to the right and upwards
+/// - the average number of keys to move from one key to another to play each target interval
+///
+/// # Examples
+///
+/// ```
+/// # use tune::layout::LayoutSearch;
+/// # use tune::ratio::Ratio;
+/// let ratings = LayoutSearch::for_edo(12)
+///     .with_target(Ratio::from_float(3.0 / 2.0))
+///     .with_target(Ratio::from_float(5.0 / 4.0))
+///     .with_target(Ratio::from_float(6.0 / 5.0))
+///     .rank();
+///
+/// let best = &ratings[0];
+/// assert_eq!((best.primary_step(), best.secondary_step()), (7, 3));
+/// assert_eq!(best.coverage(), 1.0);
+/// assert_eq!(best.duplicate_fraction(), 0.0);
+/// assert_eq!(best.octave_location(), Some((3, 3)));
+/// assert_eq!(best.target_distances(), [Some(1), Some(1), Some(1)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LayoutSearch {
+    num_steps_per_octave: u16,
+    scale_degrees: Vec<i32>,
+    targets: Vec<Ratio>,
+    num_columns: i16,
+    num_rows: i16,
+}
+
+impl LayoutSearch {
+    /// Creates a search for all degrees of the given EDO in a grid of 12 × 5 keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_steps_per_octave == 0`.
+    pub fn for_edo(num_steps_per_octave: u16) -> Self {
+        assert!(num_steps_per_octave > 0, "EDO must have at least one step");
+        Self {
+            num_steps_per_octave,
+            scale_degrees: (0..i32::from(num_steps_per_octave)).collect(),
+            targets: Vec::new(),
+            num_columns: 12,
+            num_rows: 5,
+        }
+    }
+
+    /// Only requires the given EDO degrees, e.g. the degrees of a scale, to be covered by the grid.
+    pub fn with_scale_degrees(mut self, scale_degrees: impl IntoIterator<Item = i32>) -> Self {
+        self.scale_degrees = scale_degrees.into_iter().collect();
+        self
+    }
+
+    /// Adds an interval that should be playable with as few keys as possible between the two notes.
+    pub fn with_target(mut self, target: Ratio) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    pub fn with_grid_size(mut self, num_columns: i16, num_rows: i16) -> Self {
+        self.num_columns = num_columns;
+        self.num_rows = num_rows;
+        self
+    }
+
+    /// Rates every candidate layout and returns the ratings, best first.
+    pub fn rank(&self) -> Vec<LayoutRating> {
+        let num_steps = i16::try_from(self.num_steps_per_octave).unwrap_or(i16::MAX);
+
+        let mut ratings = (1..=num_steps)
+            .flat_map(|primary_step| {
+                (-num_steps..=num_steps)
+                    .map(move |secondary_step| self.rate(primary_step, secondary_step))
+            })
+            .collect::<Vec<_>>();
+
+        ratings.sort_by(|a, b| a.score.partial_cmp(&b.score).expect("Comparison failed"));
+
+        ratings
+    }
+
+    fn rate(&self, primary_step: i16, secondary_step: i16) -> LayoutRating {
+        let keyboard = Keyboard::root_at(PianoKey::from_midi_number(0))
+            .with_steps(primary_step, secondary_step);
+        let num_steps_per_octave = i32::from(self.num_steps_per_octave);
+
+        let keys = (0..self.num_rows)
+            .flat_map(|y| (0..self.num_columns).map(move |x| (x, y)))
+            .map(|(x, y)| keyboard.get_key(x, y).midi_number())
+            .collect::<Vec<_>>();
+        let distinct_keys = keys.iter().copied().collect::<HashSet<_>>();
+
+        let pitch_classes = distinct_keys
+            .iter()
+            .map(|key| key.rem_euclid(num_steps_per_octave))
+            .collect::<HashSet<_>>();
+        let scale_degrees = self
+            .scale_degrees
+            .iter()
+            .map(|degree| degree.rem_euclid(num_steps_per_octave))
+            .collect::<HashSet<_>>();
+        let coverage = if scale_degrees.is_empty() {
+            1.0
+        } else {
+            scale_degrees.intersection(&pitch_classes).count() as f64 / scale_degrees.len() as f64
+        };
+
+        let duplicate_fraction = if keys.is_empty() {
+            0.0
+        } else {
+            (keys.len() - distinct_keys.len()) as f64 / keys.len() as f64
+        };
+
+        let octave_location = self.closest_location(&keyboard, num_steps_per_octave);
+        let octave_points_up = match octave_location {
+            Some((x, y)) => y >= 0 && 2 * x >= y,
+            None => false,
+        };
+
+        let target_distances = self
+            .targets
+            .iter()
+            .map(|target| {
+                let num_steps = (target.as_octaves() * f64::from(num_steps_per_octave)).round();
+                self.closest_location(&keyboard, num_steps as i32)
+                    .map(|(x, y)| hex_distance(x, y))
+            })
+            .collect::<Vec<_>>();
+        let unreachable_distance = (self.num_columns + self.num_rows) as u16;
+        let average_distance = if target_distances.is_empty() {
+            0.0
+        } else {
+            target_distances
+                .iter()
+                .map(|distance| f64::from(distance.unwrap_or(unreachable_distance)))
+                .sum::<f64>()
+                / target_distances.len() as f64
+        };
+
+        let score = COVERAGE_WEIGHT * (1.0 - coverage)
+            + DUPLICATES_WEIGHT * duplicate_fraction
+            + if octave_points_up {
+                0.0
+            } else {
+                OCTAVE_DIRECTION_WEIGHT
+            }
+            + average_distance;
+
+        LayoutRating {
+            primary_step,
+            secondary_step,
+            coverage,
+            duplicate_fraction,
+            octave_location,
+            target_distances,
+            score,
+        }
+    }
+
+    /// Finds the shortest move within the grid that covers `num_steps` steps, preferring moves to the right and upwards.
+    fn closest_location(&self, keyboard: &Keyboard, num_steps: i32) -> Option<(i16, i16)> {
+        let origin = keyboard.get_key(0, 0);
+        (1 - self.num_rows..self.num_rows)
+            .rev()
+            .flat_map(|y| {
+                (1 - self.num_columns..self.num_columns)
+                    .rev()
+                    .map(move |x| (x, y))
+            })
+            .filter(|&(x, y)| origin.num_keys_before(keyboard.get_key(x, y)) == num_steps)
+            .min_by_key(|&(x, y)| hex_distance(x, y))
+    }
+}
+
+/// The number of keys to move on a hexagonal grid where the upper left neighbor of (x, y) is (x, y + 1).
+fn hex_distance(x: i16, y: i16) -> u16 {
+    if x.signum() * y.signum() >= 0 {
+        x.unsigned_abs().max(y.unsigned_abs())
+    } else {
+        x.unsigned_abs() + y.unsigned_abs()
+    }
+}
+
+/// The result of rating a single layout in a [`LayoutSearch`].
+#[derive(Clone, Debug)]
+pub struct LayoutRating {
+    primary_step: i16,
+    secondary_step: i16,
+    coverage: f64,
+    duplicate_fraction: f64,
+    octave_location: Option<(i16, i16)>,
+    target_distances: Vec<Option<u16>>,
+    score: f64,
+}
+
+impl LayoutRating {
+    pub fn primary_step(&self) -> i16 {
+        self.primary_step
+    }
+
+    pub fn secondary_step(&self) -> i16 {
+        self.secondary_step
+    }
+
+    /// Creates a [`Keyboard`] with the steps of the rated layout.
+    pub fn keyboard(&self, root_key: PianoKey) -> Keyboard {
+        Keyboard::root_at(root_key).with_steps(self.primary_step, self.secondary_step)
+    }
+
+    /// The fraction of scale degrees that can be found in the grid.
+    pub fn coverage(&self) -> f64 {
+        self.coverage
+    }
+
+    /// The fraction of keys in the grid that have the same pitch as another key.
+    pub fn duplicate_fraction(&self) -> f64 {
+        self.duplicate_fraction
+    }
+
+    /// The location (x, y) of the octave relative to the root key, if it can be reached within the grid.
+    pub fn octave_location(&self) -> Option<(i16, i16)> {
+        self.octave_location
+    }
+
+    /// The number of keys to move to play each target, in the order the targets were added, if it can be reached within the grid.
+    pub fn target_distances(&self) -> &[Option<u16>] {
+        &self.target_distances
+    }
+
+    /// The total penalty of the layout. Lower is better.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+}
+
+/// A two-dimensional lattice of pitches where each axis is a generator, e.g. 3/2 and 5/4 for a 5-limit just intonation keyboard.
+///
+/// In contrast to [`Keyboard`], lattice addresses (x, y) are not collapsed into a linear [`PianoKey`], s.t. distinct lattice points stay distinct even if their pitches coincide within an octave.
+/// Moving one key to the right (x + 1) multiplies the pitch by the primary generator, moving one row up (y + 1) multiplies it by the secondary generator.
+/// Note that this is the opposite vertical direction of [`Keyboard::get_key`] where moving one row up subtracts the secondary step.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::layout::Lattice;
+/// # use tune::pitch::Pitch;
+/// # use tune::ratio::Ratio;
+/// use tune::tuning::Tuning;
+///
+/// let lattice = Lattice::new(
+///     Pitch::from_hz(200.0),
+///     Ratio::from_float(3.0 / 2.0),
+///     Ratio::from_float(5.0 / 4.0),
+/// );
+///
+/// assert_approx_eq!(lattice.pitch_of((1, 0)).as_hz(), 300.0);
+/// assert_approx_eq!(lattice.pitch_of((1, 1)).as_hz(), 375.0);
+/// assert_approx_eq!(lattice.pitch_of((-1, 2)).as_hz(), 208.333333);
+///
+/// let approximation = lattice.find_by_pitch(Pitch::from_hz(376.0));
+/// assert_eq!(approximation.approx_value, (1, 1));
+/// assert_approx_eq!(approximation.deviation.as_cents(), 4.610480);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Lattice {
+    root_pitch: Pitch,
+    primary_generator: Ratio,
+    secondary_generator: Ratio,
+    x_range: RangeInclusive<i32>,
+    y_range: RangeInclusive<i32>,
+}
+
+impl Lattice {
+    /// Creates a lattice with the root at (0, 0) whose search range is limited to -6..=6 in both directions.
+    pub fn new(
+        root_pitch: impl Pitched,
+        primary_generator: Ratio,
+        secondary_generator: Ratio,
+    ) -> Self {
+        Self {
+            root_pitch: root_pitch.pitch(),
+            primary_generator,
+            secondary_generator,
+            x_range: -6..=6,
+            y_range: -6..=6,
+        }
+    }
+
+    /// Limits the lattice points considered by [`Tuning::find_by_pitch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::layout::Lattice;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::ratio::Ratio;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let lattice = Lattice::new(
+    ///     Pitch::from_hz(200.0),
+    ///     Ratio::from_float(3.0 / 2.0),
+    ///     Ratio::from_float(5.0 / 4.0),
+    /// );
+    /// let four_fifths = Pitch::from_hz(200.0 * 81.0 / 16.0);
+    /// assert_eq!(lattice.find_by_pitch(four_fifths).approx_value, (4, 0));
+    ///
+    /// let restricted = lattice.with_bounds(-3..=3, -3..=3);
+    /// assert_eq!(restricted.find_by_pitch(four_fifths).approx_value, (3, 2));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x_range` or `y_range` is empty.
+    pub fn with_bounds(
+        mut self,
+        x_range: RangeInclusive<i32>,
+        y_range: RangeInclusive<i32>,
+    ) -> Self {
+        assert!(
+            !x_range.is_empty() && !y_range.is_empty(),
+            "Lattice bounds must not be empty"
+        );
+        self.x_range = x_range;
+        self.y_range = y_range;
+        self
+    }
+
+    pub fn root_pitch(&self) -> Pitch {
+        self.root_pitch
+    }
+
+    pub fn primary_generator(&self) -> Ratio {
+        self.primary_generator
+    }
+
+    pub fn secondary_generator(&self) -> Ratio {
+        self.secondary_generator
+    }
+}
+
+impl Tuning<(i32, i32)> for Lattice {
+    fn pitch_of(&self, (x, y): (i32, i32)) -> Pitch {
+        self.root_pitch * self.primary_generator.repeated(x) * self.secondary_generator.repeated(y)
+    }
+
+    /// Finds the lattice point within bounds whose pitch is closest to `pitch`. Ties are resolved in favor of the point closer to the root.
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<(i32, i32)> {
+        let (approx_value, deviation) = self
+            .y_range
+            .clone()
+            .flat_map(|y| self.x_range.clone().map(move |x| (x, y)))
+            .map(|address| {
+                let deviation = Ratio::between_pitches(self.pitch_of(address), pitch);
+                (address, deviation)
+            })
+            .min_by(|(a, a_deviation), (b, b_deviation)| {
+                let (a_cents, b_cents) =
+                    (a_deviation.as_cents().abs(), b_deviation.as_cents().abs());
+                // Pitches reached via different paths may differ by rounding errors only
+                let deviation_ordering = if (a_cents - b_cents).abs() < 1e-6 {
+                    Ordering::Equal
+                } else {
+                    a_cents.partial_cmp(&b_cents).expect("Comparison failed")
+                };
+                deviation_ordering
+                    .then_with(|| (a.0.abs() + a.1.abs()).cmp(&(b.0.abs() + b.1.abs())))
+            })
+            .expect("Lattice bounds are not empty");
+
+        Approximation {
+            approx_value,
+            deviation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn octave_lattice() -> Lattice {
+        Lattice::new(
+            Pitch::from_hz(100.0),
+            Ratio::from_float(2.0),
+            Ratio::from_float(4.0),
+        )
+    }
+
+    #[test]
+    fn find_by_pitch_prefers_point_closer_to_root_on_ties() {
+        let lattice = octave_lattice();
+
+        assert_eq!(
+            lattice.find_by_pitch(Pitch::from_hz(100.0)).approx_value,
+            (0, 0)
+        );
+        assert_eq!(
+            lattice.find_by_pitch(Pitch::from_hz(400.0)).approx_value,
+            (0, 1)
+        );
+        assert_eq!(
+            lattice.find_by_pitch(Pitch::from_hz(800.0)).approx_value,
+            (1, 1)
+        );
+        assert_eq!(
+            lattice.find_by_pitch(Pitch::from_hz(25.0)).approx_value,
+            (0, -1)
+        );
+
+        let fifths = Lattice::new(
+            Pitch::from_hz(100.0),
+            Ratio::from_float(3.0 / 2.0),
+            Ratio::from_float(9.0 / 4.0),
+        );
+        assert_eq!(
+            fifths.find_by_pitch(Pitch::from_hz(225.0)).approx_value,
+            (0, 1)
+        );
+        assert_eq!(
+            fifths.find_by_pitch(Pitch::from_hz(337.5)).approx_value,
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn find_by_pitch_stays_within_bounds() {
+        let lattice = octave_lattice().with_bounds(1..=2, -1..=0);
+
+        let approximation = lattice.find_by_pitch(Pitch::from_hz(100.0));
+        assert_eq!(approximation.approx_value, (2, -1));
+        assert!(approximation.deviation.as_cents().abs() < 1e-6);
+
+        let approximation = lattice.find_by_pitch(Pitch::from_hz(1600.0));
+        assert_eq!(approximation.approx_value, (2, 0));
+        assert!((approximation.deviation.as_cents() - 2400.0).abs() < 1e-6);
+
+        let approximation = lattice.find_by_pitch(Pitch::from_hz(10.0));
+        assert_eq!(approximation.approx_value, (1, -1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Lattice bounds must not be empty")]
+    fn reject_empty_bounds() {
+        #[allow(clippy::reversed_empty_ranges)]
+        octave_lattice().with_bounds(1..=0, -6..=6);
+    }
+}
@@ -1,14 +1,25 @@
-mod generators;
 mod parse;
+#[cfg(feature = "serde")]
+mod serialization;
 
+pub mod archive;
+pub mod chord;
+pub mod consonance;
+pub mod generators;
+pub mod interval;
 pub mod key;
+pub mod layout;
 pub mod math;
 pub mod midi;
 pub mod mts;
+pub mod notation;
 pub mod note;
+pub mod pcset;
 pub mod pitch;
+pub mod presets;
 pub mod ratio;
 pub mod scala;
+pub mod svg;
 pub mod temperament;
 pub mod tuner;
 pub mod tuning;
@@ -142,6 +142,64 @@ pub fn gcd_u16(mut x: u16, mut y: u16) -> u16 {
     x
 }
 
+/// Determines the greatest common divisor of two `u64`s.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::math;
+/// assert_eq!(math::gcd_u64(35, 20), 5);
+/// assert_eq!(math::gcd_u64(1 << 40, 6), 2);
+/// assert_eq!(math::gcd_u64(35, 0), 35);
+/// assert_eq!(math::gcd_u64(0, 0), 0);
+/// ```
+pub fn gcd_u64(mut x: u64, mut y: u64) -> u64 {
+    while y != 0 {
+        let t = y;
+        y = x % y;
+        x = t;
+    }
+    x
+}
+
+/// Decomposes a `u64` into its prime factors and their multiplicities, in ascending order.
+///
+/// The factorization uses trial division, i.e. the number of steps grows with the square root of the second-largest prime factor.
+/// Products of small primes, e.g. the numerators and denominators of just intonation intervals, are decomposed quickly.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::math;
+/// assert_eq!(math::prime_factors_u64(0), []);
+/// assert_eq!(math::prime_factors_u64(1), []);
+/// assert_eq!(math::prime_factors_u64(360), [(2, 3), (3, 2), (5, 1)]);
+/// assert_eq!(math::prime_factors_u64(4_294_967_291), [(4_294_967_291, 1)]);
+/// assert_eq!(math::prime_factors_u64(3_u64.pow(40)), [(3, 40)]);
+/// ```
+pub fn prime_factors_u64(mut number: u64) -> Vec<(u64, u32)> {
+    let mut prime_factors = Vec::new();
+    if number < 2 {
+        return prime_factors;
+    }
+    let mut factor = 2;
+    while factor <= number / factor {
+        let mut multiplicity = 0;
+        while number.is_multiple_of(factor) {
+            number /= factor;
+            multiplicity += 1;
+        }
+        if multiplicity > 0 {
+            prime_factors.push((factor, multiplicity));
+        }
+        factor += 1;
+    }
+    if number > 1 {
+        prime_factors.push((number, 1));
+    }
+    prime_factors
+}
+
 /// Removes all powers of two from a `u16`.
 ///
 /// # Examples
@@ -164,3 +222,24 @@ pub fn odd_factors_u16(mut number: u16) -> u16 {
     }
     number
 }
+
+/// Determines the largest prime factor of a `u16`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::math;
+/// assert_eq!(math::largest_prime_factor_u16(0), 0);
+/// assert_eq!(math::largest_prime_factor_u16(1), 1);
+/// assert_eq!(math::largest_prime_factor_u16(2), 2);
+/// assert_eq!(math::largest_prime_factor_u16(12), 3);
+/// assert_eq!(math::largest_prime_factor_u16(35), 7);
+/// assert_eq!(math::largest_prime_factor_u16(169), 13);
+/// assert_eq!(math::largest_prime_factor_u16(65521), 65521);
+/// ```
+pub fn largest_prime_factor_u16(number: u16) -> u16 {
+    prime_factors_u64(number.into())
+        .last()
+        .map(|&(prime, _)| prime as u16)
+        .unwrap_or(number)
+}
@@ -7,7 +7,8 @@
 use crate::ratio::Ratio;
 use crate::{
     key::PianoKey,
-    note::NoteLetter,
+    note::{Note, NoteLetter},
+    pitch::{Pitch, Pitched},
     tuning::{ConcertPitch, Tuning},
 };
 use core::ops::Range;
@@ -130,6 +131,59 @@ impl SingleNoteTuningChangeMessage {
         Ok(())
     }
 
+    /// Decodes a Real Time Single Note Tuning Change message, e.g. the content of a .syx file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::mts::{DeviceId, SingleNoteTuningChange, SingleNoteTuningChangeMessage};
+    /// # use tune::ratio::Ratio;
+    /// let tuning_changes = vec![
+    ///     SingleNoteTuningChange::new(60, 61, Ratio::from_cents(10.0)),
+    ///     SingleNoteTuningChange::new(62, 63, Ratio::from_cents(-10.0)),
+    /// ];
+    /// let message =
+    ///     SingleNoteTuningChangeMessage::from_tuning_changes(tuning_changes, DeviceId::broadcast(), 0)
+    ///         .unwrap();
+    ///
+    /// let decoded = SingleNoteTuningChangeMessage::from_sysex_bytes(message.sysex_bytes()).unwrap();
+    /// assert_eq!(decoded.sysex_bytes(), message.sysex_bytes());
+    /// assert_eq!(decoded.retuned_notes()[0].source_note(), 60);
+    /// assert_approx_eq!(decoded.retuned_notes()[0].target_pitch().as_hz(), 278.788, 1e-3);
+    /// assert_eq!(decoded.retuned_notes()[1].source_note(), 62);
+    /// assert_approx_eq!(decoded.retuned_notes()[1].target_pitch().as_hz(), 309.335, 1e-3);
+    ///
+    /// assert!(SingleNoteTuningChangeMessage::from_sysex_bytes(&[0xf0, 0x7e, 0x7f, 0x08, 0x08]).is_err());
+    /// ```
+    pub fn from_sysex_bytes(bytes: &[u8]) -> Result<Self, SysExParseError> {
+        let (number_of_notes, payload) = match bytes {
+            [SYSEX_START, SYSEX_RT, _device_id, MIDI_TUNING_STANDARD, SINGLE_NOTE_TUNING_CHANGE, _tuning_program, number_of_notes, payload @ .., SYSEX_END] => {
+                (usize::from(*number_of_notes), payload)
+            }
+            [SYSEX_START, .., SYSEX_END] => return Err(SysExParseError::UnsupportedMessageType),
+            _ => return Err(SysExParseError::MalformedMessage),
+        };
+        if payload.len() != 4 * number_of_notes {
+            return Err(SysExParseError::InconsistentNumberOfNotes(number_of_notes));
+        }
+
+        let retuned_notes = payload
+            .chunks(4)
+            .map(|chunk| SingleNoteTuningChange {
+                source_note: chunk[0],
+                target_note: i32::from(chunk[1]),
+                detune_as_14_bits: (i32::from(chunk[2]) << 7) | i32::from(chunk[3]),
+            })
+            .collect();
+
+        Ok(SingleNoteTuningChangeMessage {
+            sysex_call: bytes.to_vec(),
+            retuned_notes,
+            out_of_range_notes: Vec::new(),
+        })
+    }
+
     pub fn sysex_bytes(&self) -> &[u8] {
         &self.sysex_call
     }
@@ -168,6 +222,16 @@ impl SingleNoteTuningChange {
         }
     }
 
+    pub fn source_note(&self) -> u8 {
+        self.source_note
+    }
+
+    /// The pitch of the target note at the default [`ConcertPitch`] including the detuning.
+    pub fn target_pitch(&self) -> Pitch {
+        Note::from_midi_number(self.target_note).pitch()
+            * Ratio::from_semitones(f64::from(self.detune_as_14_bits) / MAX_VALUE_14_BITS)
+    }
+
     fn normalized(self) -> Self {
         SingleNoteTuningChange {
             target_note: self.target_note + (self.detune_as_14_bits >> 14),
@@ -253,6 +317,7 @@ fn encode_channels(
 }
 
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScaleOctaveTuning {
     pub c: Ratio,
     pub csh: Ratio,
@@ -319,6 +384,13 @@ pub enum TuningError {
     TuningProgramNumberOutOfRange(u8),
 }
 
+#[derive(Copy, Clone, Debug)]
+pub enum SysExParseError {
+    MalformedMessage,
+    UnsupportedMessageType,
+    InconsistentNumberOfNotes(usize),
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct DeviceId(u8);
 
@@ -0,0 +1,451 @@
+//! Notations for naming and parsing the steps of equal temperaments.
+
+use crate::{pitch::Pitch, ratio::Ratio, temperament::EqualTemperament, tuning::ConcertPitch};
+use std::str::FromStr;
+
+/// A bidirectional mapping between the steps of a tuning and note names including the octave number.
+///
+/// Steps are counted from C4, i.e. step 0 is always named C4 (or an enharmonic equivalent).
+pub trait Notation {
+    /// Formats the given step as a note name, e.g. `^Eb4`.
+    fn format_step(&self, step: i32) -> String;
+
+    /// Parses a note name, e.g. `^Eb4`, into the step it denotes.
+    fn parse_step(&self, note_name: &str) -> Result<i32, String>;
+}
+
+/// The accidentals an [`EdoNotation`] uses in addition to the Pythagorean accidentals # and b.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum NotationStyle {
+    /// Raises (`^`) or lowers (`v`) a note by single EDO steps, e.g. `^Eb4` or `vvC#4`.
+    UpsAndDowns,
+
+    /// Mixed Sagittal notation using the ASCII representation of the 5-comma (`/|`, `\!`), the 7-comma (`|)`, `!)`) and the 11-M-diesis (`/|\`, `\!/`), e.g. `Eb/|4`.
+    SagittalAscii,
+
+    /// Helmholtz–Ellis notation using syntonic comma arrows (`↑`, `↓`), septimal comma signs (`>`, `<`) and undecimal quarter tones (`t`, `d`), e.g. `Eb↓4`.
+    HelmholtzEllis,
+}
+
+/// Parses the short names `ups`, `sagittal` and `heji` into a [`NotationStyle`].
+///
+/// # Examples
+///
+/// ```
+/// # use tune::notation::NotationStyle;
+/// assert_eq!("ups".parse(), Ok(NotationStyle::UpsAndDowns));
+/// assert_eq!("sagittal".parse(), Ok(NotationStyle::SagittalAscii));
+/// assert_eq!("heji".parse(), Ok(NotationStyle::HelmholtzEllis));
+/// assert_eq!(
+///     "staff".parse::<NotationStyle>(),
+///     Err("Unknown notation 'staff'. Use ups, sagittal or heji".to_owned())
+/// );
+/// ```
+impl FromStr for NotationStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ups" => Ok(NotationStyle::UpsAndDowns),
+            "sagittal" => Ok(NotationStyle::SagittalAscii),
+            "heji" => Ok(NotationStyle::HelmholtzEllis),
+            _ => Err(format!(
+                "Unknown notation '{}'. Use ups, sagittal or heji",
+                s
+            )),
+        }
+    }
+}
+
+/// A [`Notation`] for an equal temperament based on the chain of fifths.
+///
+/// The natural notes C D E F G A B and the accidentals # and b are derived from the fifth of the temperament.
+/// The remaining steps are notated using the accidentals of the given [`NotationStyle`] whose sizes are determined by the patent val of the EDO.
+/// If a step cannot be reached using the accidentals of the style, ups and downs are used as a fallback.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::notation::EdoNotation;
+/// # use tune::notation::Notation;
+/// # use tune::notation::NotationStyle;
+/// # use tune::temperament::EqualTemperament;
+/// # use tune::tuning::ConcertPitch;
+/// let twenty_two_edo = EqualTemperament::find().by_edo(22);
+/// let ups_and_downs = EdoNotation::new(&twenty_two_edo, NotationStyle::UpsAndDowns);
+///
+/// assert_eq!(ups_and_downs.format_step(0), "C4");
+/// assert_eq!(ups_and_downs.format_step(1), "^C4");
+/// assert_eq!(ups_and_downs.format_step(7), "vE4");
+/// assert_eq!(ups_and_downs.format_step(-1), "B3");
+/// assert_eq!(ups_and_downs.parse_step("^Eb4"), Ok(6));
+/// assert_eq!(ups_and_downs.parse_step("C#-1"), Ok(-107));
+///
+/// let pitch = ups_and_downs.parse_pitch("A4", ConcertPitch::default()).unwrap();
+/// assert_approx_eq!(pitch.as_hz(), 440.0);
+///
+/// let sagittal = EdoNotation::new(&twenty_two_edo, NotationStyle::SagittalAscii);
+/// assert_eq!(sagittal.format_step(7), "E\\!4");
+/// assert_eq!(sagittal.parse_step("Eb/|4"), Ok(6));
+/// ```
+#[derive(Clone, Debug)]
+pub struct EdoNotation {
+    style: NotationStyle,
+    num_steps_per_octave: i32,
+    num_steps_per_fifth: i32,
+    size_of_octave: Ratio,
+    accidentals: Vec<CommaAccidental>,
+}
+
+#[derive(Clone, Debug)]
+struct CommaAccidental {
+    raise: &'static str,
+    lower: &'static str,
+    num_steps: i32,
+}
+
+#[derive(Clone, Debug)]
+struct Spelling {
+    letter: usize,
+    octave: i32,
+    num_ups: i32,
+    num_sharps: i32,
+    comma_counts: Vec<i32>,
+}
+
+/// Fallback ups, symbols, alteration, ups, commas, sharps, flat
+type SpellingRating = (i32, i32, i32, i32, i32, i32, bool);
+
+const LETTERS: [char; 7] = ['C', 'D', 'E', 'F', 'G', 'A', 'B'];
+const FIFTHS_ABOVE_C: [i32; 7] = [0, 2, 4, -1, 1, 3, 5];
+const MAX_NUM_SHARPS: i32 = 2;
+
+impl EdoNotation {
+    pub fn new(temperament: &EqualTemperament, style: NotationStyle) -> Self {
+        let num_steps_per_octave = i32::from(temperament.num_steps_per_octave());
+        let num_steps_per_fifth = i32::from(temperament.num_steps_per_fifth());
+
+        let patent_val =
+            |prime: f64| (f64::from(num_steps_per_octave) * prime.log2()).round() as i32;
+        let v2 = num_steps_per_octave;
+        let v3 = num_steps_per_octave + num_steps_per_fifth;
+        let v5 = patent_val(5.0);
+        let v7 = patent_val(7.0);
+        let v11 = patent_val(11.0);
+
+        let syntonic_comma = 4 * v3 - 4 * v2 - v5;
+        let septimal_comma = 6 * v2 - 2 * v3 - v7;
+        let undecimal_diesis = v3 + v11 - 5 * v2;
+
+        let symbols: &[(&str, &str, i32)] = match style {
+            NotationStyle::UpsAndDowns => &[],
+            NotationStyle::SagittalAscii => &[
+                ("/|", "\\!", syntonic_comma),
+                ("|)", "!)", septimal_comma),
+                ("/|\\", "\\!/", undecimal_diesis),
+            ],
+            NotationStyle::HelmholtzEllis => &[
+                ("↑", "↓", syntonic_comma),
+                (">", "<", septimal_comma),
+                ("t", "d", undecimal_diesis),
+            ],
+        };
+
+        let mut accidentals: Vec<CommaAccidental> = Vec::new();
+        for &(raise, lower, num_steps) in symbols {
+            let (raise, lower, num_steps) = if num_steps < 0 {
+                (lower, raise, -num_steps)
+            } else {
+                (raise, lower, num_steps)
+            };
+            if num_steps != 0 && accidentals.iter().all(|a| a.num_steps != num_steps) {
+                accidentals.push(CommaAccidental {
+                    raise,
+                    lower,
+                    num_steps,
+                });
+            }
+        }
+
+        Self {
+            style,
+            num_steps_per_octave,
+            num_steps_per_fifth,
+            size_of_octave: temperament.size_of_octave(),
+            accidentals,
+        }
+    }
+
+    pub fn style(&self) -> NotationStyle {
+        self.style
+    }
+
+    /// Returns the pitch of the given step assuming A4 sounds at the pitch given by `concert_pitch`.
+    pub fn pitch_of_step(&self, step: i32, concert_pitch: ConcertPitch) -> Pitch {
+        let num_steps_above_a4 = step - self.location_of_letter(5);
+        concert_pitch.a4_pitch()
+            * self
+                .size_of_octave
+                .divided_into_equal_steps(self.num_steps_per_octave)
+                .repeated(num_steps_above_a4)
+    }
+
+    /// Parses a note name into a [`Pitch`] assuming A4 sounds at the pitch given by `concert_pitch`.
+    pub fn parse_pitch(
+        &self,
+        note_name: &str,
+        concert_pitch: ConcertPitch,
+    ) -> Result<Pitch, String> {
+        self.parse_step(note_name)
+            .map(|step| self.pitch_of_step(step, concert_pitch))
+    }
+
+    fn size_of_sharp(&self) -> i32 {
+        7 * self.num_steps_per_fifth - 4 * self.num_steps_per_octave
+    }
+
+    fn location_of_letter(&self, letter: usize) -> i32 {
+        let num_fifths = FIFTHS_ABOVE_C[letter];
+        let num_octaves = (num_fifths * 7).div_euclid(12);
+        num_fifths * self.num_steps_per_fifth - num_octaves * self.num_steps_per_octave
+    }
+
+    fn spell(&self, step: i32) -> Spelling {
+        let comma_combinations = self.comma_combinations();
+        let mut best: Option<(SpellingRating, Spelling)> = None;
+
+        for letter in 0..LETTERS.len() {
+            let location = self.location_of_letter(letter);
+            let rounded_octave =
+                (f64::from(step - location) / f64::from(self.num_steps_per_octave)).round() as i32;
+
+            for octave in rounded_octave - 1..=rounded_octave + 1 {
+                let deviation = step - location - octave * self.num_steps_per_octave;
+                for num_sharps in -MAX_NUM_SHARPS..=MAX_NUM_SHARPS {
+                    let remainder = deviation - num_sharps * self.size_of_sharp();
+                    for comma_counts in &comma_combinations {
+                        let num_ups = remainder
+                            - comma_counts
+                                .iter()
+                                .zip(&self.accidentals)
+                                .map(|(count, accidental)| count * accidental.num_steps)
+                                .sum::<i32>();
+                        let num_fallback_ups = match self.style {
+                            NotationStyle::UpsAndDowns => 0,
+                            _ => num_ups.abs(),
+                        };
+                        let num_commas = comma_counts.iter().map(|count| count.abs()).sum::<i32>();
+                        let num_symbols = num_ups.abs() + num_sharps.abs() + num_commas;
+                        let rating = (
+                            num_fallback_ups,
+                            num_symbols,
+                            deviation.abs(),
+                            num_ups.abs(),
+                            num_commas,
+                            num_sharps.abs(),
+                            num_sharps < 0,
+                        );
+
+                        let is_better = match &best {
+                            Some((best_rating, _)) => rating < *best_rating,
+                            None => true,
+                        };
+                        if is_better {
+                            best = Some((
+                                rating,
+                                Spelling {
+                                    letter,
+                                    octave,
+                                    num_ups,
+                                    num_sharps,
+                                    comma_counts: comma_counts.clone(),
+                                },
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        best.unwrap().1
+    }
+
+    fn comma_combinations(&self) -> Vec<Vec<i32>> {
+        let mut combinations = vec![Vec::new()];
+        for _ in &self.accidentals {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    (-1..=1).map(move |count| {
+                        let mut combination = combination.clone();
+                        combination.push(count);
+                        combination
+                    })
+                })
+                .collect();
+        }
+
+        match self.style {
+            // Sagittal uses at most one symbol per note
+            NotationStyle::SagittalAscii => combinations
+                .into_iter()
+                .filter(|combination| combination.iter().filter(|&&c| c != 0).count() <= 1)
+                .collect(),
+            NotationStyle::UpsAndDowns | NotationStyle::HelmholtzEllis => combinations,
+        }
+    }
+}
+
+impl Notation for EdoNotation {
+    fn format_step(&self, step: i32) -> String {
+        let spelling = self.spell(step);
+
+        let mut formatted = String::new();
+        let ups = if spelling.num_ups > 0 { "^" } else { "v" };
+        formatted.push_str(&ups.repeat(spelling.num_ups.unsigned_abs() as usize));
+        formatted.push(LETTERS[spelling.letter]);
+        let sharps = if spelling.num_sharps > 0 { "#" } else { "b" };
+        formatted.push_str(&sharps.repeat(spelling.num_sharps.unsigned_abs() as usize));
+        for (&count, accidental) in spelling.comma_counts.iter().zip(&self.accidentals) {
+            match count {
+                1 => formatted.push_str(accidental.raise),
+                -1 => formatted.push_str(accidental.lower),
+                _ => {}
+            }
+        }
+        formatted.push_str(&(spelling.octave + 4).to_string());
+
+        formatted
+    }
+
+    fn parse_step(&self, note_name: &str) -> Result<i32, String> {
+        let octave_start = note_name
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !c.is_ascii_digit())
+            .map_or(0, |(index, c)| index + c.len_utf8());
+        let octave_start = match note_name[..octave_start].strip_suffix('-') {
+            Some(prefix) if octave_start < note_name.len() => prefix.len(),
+            _ => octave_start,
+        };
+        let (note, octave) = note_name.split_at(octave_start);
+        let octave = octave
+            .parse::<i32>()
+            .map_err(|_| format!("Missing octave number in '{}'", note_name))?;
+
+        let num_ups = note.chars().take_while(|&c| c == '^').count() as i32;
+        let num_downs = note.chars().take_while(|&c| c == 'v').count() as i32;
+        let note = &note[(num_ups + num_downs) as usize..];
+
+        let mut chars = note.chars();
+        let letter = chars
+            .next()
+            .and_then(|letter| LETTERS.iter().position(|&l| l == letter))
+            .ok_or_else(|| format!("Missing note letter (C-B) in '{}'", note_name))?;
+
+        let mut step =
+            self.location_of_letter(letter) + (octave - 4) * self.num_steps_per_octave + num_ups
+                - num_downs;
+
+        let mut accidentals = chars.as_str();
+        while !accidentals.is_empty() {
+            let (num_steps, symbol_len) = self
+                .accidental_symbols()
+                .into_iter()
+                .filter(|(symbol, _)| accidentals.starts_with(symbol))
+                .map(|(symbol, num_steps)| (num_steps, symbol.len()))
+                .max_by_key(|&(_, symbol_len)| symbol_len)
+                .ok_or_else(|| {
+                    format!("Invalid accidental '{}' in '{}'", accidentals, note_name)
+                })?;
+            step += num_steps;
+            accidentals = &accidentals[symbol_len..];
+        }
+
+        Ok(step)
+    }
+}
+
+impl EdoNotation {
+    fn accidental_symbols(&self) -> Vec<(&'static str, i32)> {
+        let size_of_sharp = self.size_of_sharp();
+        let mut symbols = vec![
+            ("#", size_of_sharp),
+            ("x", 2 * size_of_sharp),
+            ("b", -size_of_sharp),
+        ];
+        for accidental in &self.accidentals {
+            symbols.push((accidental.raise, accidental.num_steps));
+            symbols.push((accidental.lower, -accidental.num_steps));
+        }
+        symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_and_parse_roundtrip() {
+        for &style in &[
+            NotationStyle::UpsAndDowns,
+            NotationStyle::SagittalAscii,
+            NotationStyle::HelmholtzEllis,
+        ] {
+            for num_steps_per_octave in 5u16..=72 {
+                let temperament = EqualTemperament::find().by_edo(num_steps_per_octave);
+                let notation = EdoNotation::new(&temperament, style);
+                for step in -30..30 {
+                    let note_name = notation.format_step(step);
+                    assert_eq!(
+                        notation.parse_step(&note_name),
+                        Ok(step),
+                        "{}-EDO {:?}: {}",
+                        num_steps_per_octave,
+                        style,
+                        note_name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn twelve_edo_uses_standard_notation() {
+        let temperament = EqualTemperament::find().by_edo(12);
+        for &style in &[
+            NotationStyle::UpsAndDowns,
+            NotationStyle::SagittalAscii,
+            NotationStyle::HelmholtzEllis,
+        ] {
+            let notation = EdoNotation::new(&temperament, style);
+            let note_names: Vec<_> = (0..12).map(|step| notation.format_step(step)).collect();
+            assert_eq!(
+                note_names,
+                ["C4", "C#4", "D4", "D#4", "E4", "F4", "F#4", "G4", "G#4", "A4", "A#4", "B4"]
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let notation = EdoNotation::new(
+            &EqualTemperament::find().by_edo(31),
+            NotationStyle::UpsAndDowns,
+        );
+        assert_eq!(
+            notation.parse_step("C"),
+            Err("Missing octave number in 'C'".to_owned())
+        );
+        assert_eq!(
+            notation.parse_step("H4"),
+            Err("Missing note letter (C-B) in 'H4'".to_owned())
+        );
+        assert_eq!(
+            notation.parse_step("C/|4"),
+            Err("Invalid accidental '/|' in 'C/|4'".to_owned())
+        );
+    }
+}
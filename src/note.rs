@@ -7,6 +7,7 @@ use crate::{key::PianoKey, ratio::Ratio};
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::str::FromStr;
 
 /// A musical note encapsulating a clearly defined pitch.
 ///
@@ -191,6 +192,104 @@ impl Display for Note {
     }
 }
 
+/// Parses a [`Note`] given in scientific or in Helmholtz pitch notation.
+///
+/// Any number of sharps (`#`), double sharps (`x`) and flats (`b`) can follow the note letter.
+/// In Helmholtz notation, lowercase letters denote the small octave which is raised by each `'` while uppercase letters denote the great octave which is lowered by each `,`.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::note::Note;
+/// assert_eq!("A4".parse(), Ok(Note::from_midi_number(69)));
+/// assert_eq!("C#4".parse(), Ok(Note::from_midi_number(61)));
+/// assert_eq!("Bb-1".parse(), Ok(Note::from_midi_number(10)));
+/// assert_eq!("Cb4".parse(), Ok(Note::from_midi_number(59)));
+/// assert_eq!("Fx4".parse(), Ok(Note::from_midi_number(67)));
+/// assert_eq!("A 4".parse(), Ok(Note::from_midi_number(69)));
+///
+/// // Helmholtz pitch notation
+/// assert_eq!("c'".parse(), Ok(Note::from_midi_number(60)));
+/// assert_eq!("a''".parse(), Ok(Note::from_midi_number(81)));
+/// assert_eq!("g".parse(), Ok(Note::from_midi_number(55)));
+/// assert_eq!("C".parse(), Ok(Note::from_midi_number(36)));
+/// assert_eq!("F#,".parse(), Ok(Note::from_midi_number(30)));
+///
+/// assert_eq!(
+///     "H4".parse::<Note>(),
+///     Err("Invalid note 'H4': Must start with a note letter (A-G or a-g)".to_owned())
+/// );
+/// assert_eq!(
+///     "c4".parse::<Note>(),
+///     Err("Invalid note 'c4': Lowercase note letters must be followed by ' (Helmholtz notation)".to_owned())
+/// );
+/// assert_eq!(
+///     "C2000000000".parse::<Note>(),
+///     Err("Invalid note 'C2000000000': Octave number out of range".to_owned())
+/// );
+/// ```
+impl FromStr for Note {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let letter = chars.next().ok_or_else(|| "Empty note name".to_owned())?;
+        let semitone = match letter.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' => 11,
+            _ => {
+                return Err(format!(
+                    "Invalid note '{}': Must start with a note letter (A-G or a-g)",
+                    s
+                ))
+            }
+        };
+
+        let rest = chars.as_str();
+        let octave_start = rest
+            .find(|c| !matches!(c, '#' | 'b' | 'x'))
+            .unwrap_or(rest.len());
+        let (accidentals, octave) = rest.split_at(octave_start);
+        let alteration = accidentals
+            .chars()
+            .map(|accidental| match accidental {
+                '#' => 1,
+                'x' => 2,
+                _ => -1,
+            })
+            .sum::<i32>();
+
+        let octave_number = if letter.is_ascii_lowercase() {
+            if !octave.chars().all(|c| c == '\'') {
+                return Err(format!(
+                    "Invalid note '{}': Lowercase note letters must be followed by ' (Helmholtz notation)",
+                    s
+                ));
+            }
+            3 + octave.chars().count() as i32
+        } else if octave.chars().all(|c| c == ',') {
+            2 - octave.chars().count() as i32
+        } else {
+            octave
+                .trim_start()
+                .parse()
+                .map_err(|_| format!("Invalid note '{}': Must end with an octave number", s))?
+        };
+
+        octave_number
+            .checked_add(1)
+            .and_then(|octave_number| octave_number.checked_mul(12))
+            .and_then(|midi_number| midi_number.checked_add(semitone + alteration))
+            .map(Note::from_midi_number)
+            .ok_or_else(|| format!("Invalid note '{}': Octave number out of range", s))
+    }
+}
+
 /// The speaking name of a note within its octave.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum NoteLetter {
@@ -0,0 +1,520 @@
+//! Pitch-class set theory for arbitrary EDOs.
+
+use crate::{
+    ratio::Ratio,
+    scala::{Scl, SclBuildError},
+    temperament::EqualTemperament,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A set of pitch classes, i.e. octave-equivalent steps, of an EDO.
+///
+/// The pitch classes are reduced to the range `0..num_steps_per_octave` and sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::pcset::PitchClassSet;
+/// let major_triad = PitchClassSet::new(12, [7, 0, 4, 16, -5].iter().copied());
+///
+/// assert_eq!(major_triad.pitch_classes(), [0, 4, 7]);
+/// assert_eq!(major_triad.to_string(), "[0, 4, 7]");
+/// assert_eq!(major_triad.cardinality(), 3);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PitchClassSet {
+    num_steps_per_octave: u16,
+    pitch_classes: Vec<u16>,
+}
+
+impl PitchClassSet {
+    /// Creates a set of the given pitch classes of an EDO.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_steps_per_octave` is 0.
+    pub fn new(num_steps_per_octave: u16, pitch_classes: impl IntoIterator<Item = i32>) -> Self {
+        assert!(
+            num_steps_per_octave > 0,
+            "Number of steps per octave must be greater than 0"
+        );
+        let mut pitch_classes = pitch_classes
+            .into_iter()
+            .map(|pitch_class| pitch_class.rem_euclid(i32::from(num_steps_per_octave)) as u16)
+            .collect::<Vec<_>>();
+        pitch_classes.sort_unstable();
+        pitch_classes.dedup();
+        Self {
+            num_steps_per_octave,
+            pitch_classes,
+        }
+    }
+
+    /// Maps every degree within the first period of a [`Scl`] to the nearest step of an EDO.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// # use tune::scala::Scl;
+    /// let just_major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let pcset = PitchClassSet::from_scl(&just_major, 31);
+    /// assert_eq!(pcset.pitch_classes(), [0, 5, 10, 13, 18, 23, 28]);
+    /// ```
+    pub fn from_scl(scl: &Scl, num_steps_per_octave: u16) -> Self {
+        let steps = (0..scl.size() as i32).map(|degree| {
+            let octaves = scl.relative_pitch_of(degree).as_octaves();
+            (octaves * f64::from(num_steps_per_octave)).round() as i32
+        });
+        Self::new(num_steps_per_octave, steps)
+    }
+
+    pub fn num_steps_per_octave(&self) -> u16 {
+        self.num_steps_per_octave
+    }
+
+    pub fn pitch_classes(&self) -> &[u16] {
+        &self.pitch_classes
+    }
+
+    pub fn cardinality(&self) -> usize {
+        self.pitch_classes.len()
+    }
+
+    pub fn contains(&self, pitch_class: i32) -> bool {
+        let pitch_class = pitch_class.rem_euclid(i32::from(self.num_steps_per_octave)) as u16;
+        self.pitch_classes.binary_search(&pitch_class).is_ok()
+    }
+
+    /// Transposes the set by `num_steps` (T<sub>n</sub>).
+    pub fn transposed(&self, num_steps: i32) -> Self {
+        Self::new(
+            self.num_steps_per_octave,
+            self.pitch_classes.iter().map(|&pitch_class| {
+                i32::from(pitch_class) + num_steps.rem_euclid(i32::from(self.num_steps_per_octave))
+            }),
+        )
+    }
+
+    /// Inverts the set around pitch class 0 (T<sub>0</sub>I).
+    pub fn inverted(&self) -> Self {
+        Self::new(
+            self.num_steps_per_octave,
+            self.pitch_classes
+                .iter()
+                .map(|&pitch_class| -i32::from(pitch_class)),
+        )
+    }
+
+    /// Returns all pitch classes of the EDO that are not in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// let whole_tone = PitchClassSet::new(12, vec![0, 2, 4, 6, 8, 10]);
+    /// assert_eq!(whole_tone.complement().pitch_classes(), [1, 3, 5, 7, 9, 11]);
+    /// ```
+    pub fn complement(&self) -> Self {
+        Self::new(
+            self.num_steps_per_octave,
+            (0..i32::from(self.num_steps_per_octave))
+                .filter(|&pitch_class| !self.contains(pitch_class)),
+        )
+    }
+
+    /// Returns the most compact rotation of the set according to Rahn's algorithm.
+    ///
+    /// The rotation with the smallest span from the first to the last pitch class is chosen.
+    /// Ties are broken by the span to the second-to-last pitch class, then to the third-to-last pitch class etc. and finally by the smallest first pitch class.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// let dominant_seventh = PitchClassSet::new(12, vec![7, 11, 2, 5]);
+    /// assert_eq!(dominant_seventh.normal_form(), [11, 2, 5, 7]);
+    ///
+    /// let neutral_triad = PitchClassSet::new(24, vec![0, 7, 14]);
+    /// assert_eq!(neutral_triad.normal_form(), [0, 7, 14]);
+    /// ```
+    pub fn normal_form(&self) -> Vec<u16> {
+        (0..self.cardinality())
+            .min_by(|&a, &b| {
+                self.packing(a)
+                    .cmp(self.packing(b))
+                    .then(self.pitch_classes[a].cmp(&self.pitch_classes[b]))
+            })
+            .map(|start| self.rotation(start))
+            .unwrap_or_default()
+    }
+
+    /// Returns the most compact form of the set and its inversion, transposed to start at 0, according to Rahn's algorithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// let major_triad = PitchClassSet::new(12, vec![0, 4, 7]);
+    /// assert_eq!(major_triad.prime_form(), [0, 3, 7]);
+    ///
+    /// // Rahn and Forte disagree about 5-20
+    /// let set_5_20 = PitchClassSet::new(12, vec![0, 1, 5, 6, 8]);
+    /// assert_eq!(set_5_20.prime_form(), [0, 1, 5, 6, 8]);
+    ///
+    /// let septimal_tetrad = PitchClassSet::new(31, vec![0, 10, 18, 25]);
+    /// assert_eq!(septimal_tetrad.prime_form(), [0, 6, 13, 21]);
+    ///
+    /// let wrapping_dyad = PitchClassSet::new(40000, vec![0, 39999]);
+    /// assert_eq!(wrapping_dyad.prime_form(), [0, 1]);
+    /// assert_eq!(wrapping_dyad.transposed(i32::MAX).pitch_classes(), [3646, 3647]);
+    /// ```
+    pub fn prime_form(&self) -> Vec<u16> {
+        let zero_based = |normal_form: Vec<u16>| {
+            let first = normal_form.first().copied().unwrap_or_default();
+            normal_form
+                .iter()
+                .map(|&pitch_class| self.interval(first, pitch_class))
+                .collect::<Vec<_>>()
+        };
+        let original = zero_based(self.normal_form());
+        let inverted = zero_based(self.inverted().normal_form());
+        // Zero-based forms start at 0, s.t. their packing is given by the pitch classes themselves
+        if inverted
+            .iter()
+            .skip(1)
+            .rev()
+            .lt(original.iter().skip(1).rev())
+        {
+            inverted
+        } else {
+            original
+        }
+    }
+
+    /// Counts the occurrences of each interval class, from 1 to `num_steps_per_octave / 2`, between any two pitch classes of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// let major_triad = PitchClassSet::new(12, vec![0, 4, 7]);
+    /// assert_eq!(major_triad.interval_class_vector(), [0, 0, 1, 1, 1, 0]);
+    ///
+    /// let diatonic = PitchClassSet::new(19, vec![0, 3, 6, 8, 11, 14, 17]);
+    /// assert_eq!(diatonic.interval_class_vector(), [0, 2, 5, 0, 4, 3, 0, 6, 1]);
+    /// ```
+    pub fn interval_class_vector(&self) -> Vec<u16> {
+        let mut vector = vec![0; usize::from(self.num_steps_per_octave / 2)];
+        for (index, &a) in self.pitch_classes.iter().enumerate() {
+            for &b in &self.pitch_classes[index + 1..] {
+                let interval = b - a;
+                let interval_class = interval.min(self.num_steps_per_octave - interval);
+                vector[usize::from(interval_class) - 1] += 1;
+            }
+        }
+        vector
+    }
+
+    /// Checks whether `other` is a transposition (T<sub>n</sub>) of `self`.
+    pub fn is_transposition_of(&self, other: &PitchClassSet) -> bool {
+        self.num_steps_per_octave == other.num_steps_per_octave
+            && self.cardinality() == other.cardinality()
+            && (0..i32::from(self.num_steps_per_octave))
+                .any(|num_steps| &self.transposed(num_steps) == other)
+    }
+
+    /// Checks whether `other` is a transposition or inversion (T<sub>n</sub>I) of `self`, i.e. whether both sets belong to the same set class.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// let major_triad = PitchClassSet::new(12, vec![0, 4, 7]);
+    /// let minor_triad = PitchClassSet::new(12, vec![2, 5, 9]);
+    ///
+    /// assert!(!major_triad.is_transposition_of(&minor_triad));
+    /// assert!(major_triad.is_equivalent_to(&minor_triad));
+    /// assert!(major_triad.is_transposition_of(&major_triad.transposed(5)));
+    /// ```
+    pub fn is_equivalent_to(&self, other: &PitchClassSet) -> bool {
+        self.num_steps_per_octave == other.num_steps_per_octave
+            && self.prime_form() == other.prime_form()
+    }
+
+    /// Checks whether `self` and `other` share the same interval-class vector without belonging to the same set class.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// let all_interval_tetrad = PitchClassSet::new(12, vec![0, 1, 4, 6]);
+    /// let other_all_interval_tetrad = PitchClassSet::new(12, vec![0, 1, 3, 7]);
+    ///
+    /// assert!(all_interval_tetrad.is_z_related_to(&other_all_interval_tetrad));
+    /// assert!(!all_interval_tetrad.is_z_related_to(&all_interval_tetrad.inverted()));
+    /// ```
+    pub fn is_z_related_to(&self, other: &PitchClassSet) -> bool {
+        self.num_steps_per_octave == other.num_steps_per_octave
+            && self.interval_class_vector() == other.interval_class_vector()
+            && !self.is_equivalent_to(other)
+    }
+
+    /// Generates the prime forms of all set classes of the given cardinality, in lexicographical order.
+    ///
+    /// Every set class is generated exactly once by only extending sets that can still become a prime form.
+    /// Nevertheless, the number of set classes grows combinatorially, roughly as `binomial(num_steps_per_octave, cardinality) / (2 * num_steps_per_octave)`.
+    /// E.g. 12-EDO has 50 hexachord classes whereas 31-EDO has more than 2 million set classes with 12 pitch classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// let trichords = PitchClassSet::all_set_classes(12, 3);
+    /// assert_eq!(trichords.len(), 12);
+    /// assert_eq!(trichords[0].pitch_classes(), [0, 1, 2]);
+    /// assert_eq!(trichords[11].pitch_classes(), [0, 4, 8]);
+    ///
+    /// assert_eq!(PitchClassSet::all_set_classes(12, 6).len(), 50);
+    /// assert_eq!(PitchClassSet::all_set_classes(19, 3).len(), 30);
+    /// ```
+    pub fn all_set_classes(num_steps_per_octave: u16, cardinality: usize) -> Vec<PitchClassSet> {
+        SetClassSearch::new(num_steps_per_octave, cardinality, None).run()
+    }
+
+    /// Finds the prime forms of all set classes that are Z-related to `self`, in lexicographical order.
+    ///
+    /// In contrast to filtering [`PitchClassSet::all_set_classes`], only sets whose interval-class vector stays within the interval-class vector of `self` are considered.
+    /// This keeps the search fast even for large sets of large EDOs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// let all_interval_tetrad = PitchClassSet::new(12, vec![0, 1, 4, 6]);
+    /// let z_related = all_interval_tetrad.z_related_set_classes();
+    /// assert_eq!(z_related.len(), 1);
+    /// assert_eq!(z_related[0].pitch_classes(), [0, 1, 3, 7]);
+    ///
+    /// let major_triad = PitchClassSet::new(12, vec![0, 4, 7]);
+    /// assert!(major_triad.z_related_set_classes().is_empty());
+    ///
+    /// let large_set = PitchClassSet::new(31, vec![0, 1, 2, 5, 6, 10, 12, 14, 15, 23, 25, 26]);
+    /// let z_related = large_set.z_related_set_classes();
+    /// assert_eq!(z_related.len(), 1);
+    /// assert_eq!(z_related[0].pitch_classes(), [0, 1, 3, 9, 11, 13, 14, 18, 19, 20, 23, 24]);
+    /// assert!(z_related[0].is_z_related_to(&large_set));
+    /// ```
+    pub fn z_related_set_classes(&self) -> Vec<PitchClassSet> {
+        let prime_form = self.prime_form();
+        SetClassSearch::new(
+            self.num_steps_per_octave,
+            self.cardinality(),
+            Some(self.interval_class_vector()),
+        )
+        .run()
+        .into_iter()
+        .filter(|set_class| set_class.pitch_classes != prime_form)
+        .collect()
+    }
+
+    /// Names each pitch class using the heptatonic note names of the given temperament.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pcset::PitchClassSet;
+    /// # use tune::temperament::EqualTemperament;
+    /// let temperament = EqualTemperament::find().by_edo(31);
+    /// let pcset = PitchClassSet::new(31, vec![0, 10, 18, 25]);
+    ///
+    /// assert_eq!(pcset.note_names(&temperament), ["D", "F#", "A", "B#"]);
+    /// ```
+    pub fn note_names(&self, temperament: &EqualTemperament) -> Vec<String> {
+        self.pitch_classes
+            .iter()
+            .map(|&pitch_class| {
+                let name = temperament.get_heptatonic_name(i32::from(pitch_class));
+                name.split(" / ").next().unwrap_or_default().to_owned()
+            })
+            .collect()
+    }
+
+    /// Creates a scale of the EDO subset starting at the first pitch class of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::pcset::PitchClassSet;
+    /// let pentatonic = PitchClassSet::new(12, vec![1, 3, 6, 8, 10]);
+    /// let scl = pentatonic.to_scl().unwrap();
+    ///
+    /// assert_eq!(scl.size(), 5);
+    /// assert_approx_eq!(scl.relative_pitch_of(1).as_cents(), 200.0);
+    /// assert_approx_eq!(scl.relative_pitch_of(2).as_cents(), 500.0);
+    /// assert_approx_eq!(scl.relative_pitch_of(5).as_cents(), 1200.0);
+    /// ```
+    pub fn to_scl(&self) -> Result<Scl, SclBuildError> {
+        let first = self.pitch_classes.first().copied().unwrap_or_default();
+        let step_size = Ratio::octave().divided_into_equal_steps(self.num_steps_per_octave);
+
+        let mut builder = Scl::builder();
+        for &pitch_class in self.pitch_classes.iter().skip(1) {
+            builder = builder.push_ratio(step_size.repeated(self.interval(first, pitch_class)));
+        }
+        builder = builder.push_int(2);
+        builder.build_with_description(format!(
+            "Pitch-class set {} of {}-EDO",
+            self, self.num_steps_per_octave
+        ))
+    }
+
+    fn rotation(&self, start: usize) -> Vec<u16> {
+        self.pitch_classes[start..]
+            .iter()
+            .chain(&self.pitch_classes[..start])
+            .copied()
+            .collect()
+    }
+
+    /// The intervals from the first to the last, second-to-last, ..., second pitch class of a rotation. Smaller is more compact.
+    ///
+    /// The intervals are computed lazily since most rotations can be discarded after comparing their spans.
+    fn packing(&self, start: usize) -> impl Iterator<Item = u16> + '_ {
+        let len = self.cardinality();
+        let first = self.pitch_classes[start];
+        (1..len)
+            .rev()
+            .map(move |offset| self.interval(first, self.pitch_classes[(start + offset) % len]))
+    }
+
+    fn interval(&self, from: u16, to: u16) -> u16 {
+        (i32::from(to) - i32::from(from)).rem_euclid(i32::from(self.num_steps_per_octave)) as u16
+    }
+}
+
+/// Depth-first search for prime forms, optionally restricted to a given interval-class vector.
+struct SetClassSearch {
+    num_steps_per_octave: u16,
+    cardinality: usize,
+    target_vector: Option<Vec<u16>>,
+    current: Vec<u16>,
+    vector: Vec<u16>,
+    set_classes: Vec<PitchClassSet>,
+}
+
+impl SetClassSearch {
+    fn new(num_steps_per_octave: u16, cardinality: usize, target_vector: Option<Vec<u16>>) -> Self {
+        Self {
+            num_steps_per_octave,
+            cardinality,
+            target_vector,
+            current: Vec::with_capacity(cardinality),
+            vector: vec![0; usize::from(num_steps_per_octave / 2)],
+            set_classes: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Vec<PitchClassSet> {
+        if self.cardinality == 0 {
+            self.set_classes
+                .push(PitchClassSet::new(self.num_steps_per_octave, Vec::new()));
+        } else if self.cardinality <= usize::from(self.num_steps_per_octave) {
+            self.current.push(0);
+            self.extend();
+        }
+        self.set_classes
+    }
+
+    fn extend(&mut self) {
+        let num_steps_per_octave = self.num_steps_per_octave;
+        if self.current.len() == self.cardinality {
+            let pcset = PitchClassSet::new(
+                num_steps_per_octave,
+                self.current.iter().copied().map(i32::from),
+            );
+            let matches_target = match &self.target_vector {
+                Some(target_vector) => &self.vector == target_vector,
+                None => true,
+            };
+            // Sets that are not in prime form belong to a set class that is generated elsewhere
+            if matches_target && pcset.prime_form() == self.current {
+                self.set_classes.push(pcset);
+            }
+            return;
+        }
+
+        let last = self.current.last().copied().unwrap_or_default();
+        let largest_gap = self
+            .current
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .max()
+            .unwrap_or_default();
+        let num_missing = (self.cardinality - self.current.len()) as u16;
+        for pitch_class in last + 1..=num_steps_per_octave - num_missing {
+            // A prime form is the rotation whose gap from the last back to the first pitch class is the largest one
+            let lowest_possible_last = pitch_class + num_missing - 1;
+            if largest_gap.max(pitch_class - last) > num_steps_per_octave - lowest_possible_last {
+                break;
+            }
+
+            if self.add_intervals(pitch_class) {
+                self.current.push(pitch_class);
+                self.extend();
+                self.current.pop();
+            }
+            self.remove_intervals(pitch_class);
+        }
+    }
+
+    /// Returns `false` if the interval-class vector exceeds the target vector.
+    fn add_intervals(&mut self, pitch_class: u16) -> bool {
+        let mut within_target = true;
+        for index in 0..self.current.len() {
+            let interval_class = self.interval_class_index(self.current[index], pitch_class);
+            self.vector[interval_class] += 1;
+            if let Some(target_vector) = &self.target_vector {
+                within_target &= self.vector[interval_class] <= target_vector[interval_class];
+            }
+        }
+        within_target
+    }
+
+    fn remove_intervals(&mut self, pitch_class: u16) {
+        for index in 0..self.current.len() {
+            let interval_class = self.interval_class_index(self.current[index], pitch_class);
+            self.vector[interval_class] -= 1;
+        }
+    }
+
+    fn interval_class_index(&self, lower: u16, upper: u16) -> usize {
+        let interval = upper - lower;
+        usize::from(interval.min(self.num_steps_per_octave - interval)) - 1
+    }
+}
+
+/// Formats the pitch classes as a comma-separated list in brackets, e.g. `[0, 4, 7]`.
+impl Display for PitchClassSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (index, pitch_class) in self.pitch_classes.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", pitch_class)?;
+        }
+        write!(f, "]")
+    }
+}
@@ -2,6 +2,7 @@ use crate::note;
 use crate::note::Note;
 use crate::parse;
 use crate::{
+    interval::{self, IntervalValue, NamedInterval},
     key::PianoKey,
     ratio::Ratio,
     tuning::{Approximation, Tuning},
@@ -64,18 +65,133 @@ impl Pitch {
     }
 }
 
+/// Parses a [`Pitch`] expression.
+///
+/// A pitch expression consists of a frequency (e.g. `440Hz`) or a [`Note`] sounding at standard 440&nbsp;Hz tuning (e.g. `A4`), optionally followed by an operator (`+`, `-`, `*` or `/`) and a [`Ratio`] expression.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::pitch::Pitch;
+/// let parse = |s: &str| s.parse::<Pitch>().unwrap().as_hz();
+///
+/// assert_approx_eq!(parse("440Hz"), 440.0);
+/// assert_approx_eq!(parse("A4"), 440.0);
+/// assert_approx_eq!(parse("A4+12c"), 443.060442);
+/// assert_approx_eq!(parse("A4-1200c"), 220.0);
+/// assert_approx_eq!(parse("C4*3/2"), 392.438347);
+/// assert_approx_eq!(parse("440Hz/7:12:2"), 293.664767);
+/// assert_approx_eq!(parse("1000/3Hz"), 333.333333);
+/// assert_approx_eq!(parse("1000/3Hz*3/2"), 500.0);
+/// assert_approx_eq!(parse("(1000/3)Hz-1200c"), 166.666667);
+/// assert_approx_eq!(parse("Bb-1+100c"), 15.433853);
+///
+/// assert_eq!(
+///     "440".parse::<Pitch>().unwrap_err(),
+///     "Invalid pitch '440': Must be a frequency (e.g. 440Hz) or a note (e.g. A4)"
+/// );
+/// assert_eq!(
+///     "A4+x".parse::<Pitch>().unwrap_err(),
+///     "Invalid ratio 'x': Invalid expression 'x': Unexpected 'x' at position 1: \
+///      Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), \
+///      cents value (e.g. 702c), EDO step (e.g. 7\\12) or constant (e.g. octave)"
+/// );
+/// ```
 impl FromStr for Pitch {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.ends_with("Hz") || s.ends_with("hz") {
-            let freq = &s[..s.len() - 2];
+        let (base, operation) = split_pitch_expression(s);
+
+        let base_pitch = if base.ends_with("Hz") || base.ends_with("hz") {
+            let freq = &base[..base.len() - 2];
             let freq = freq
                 .parse::<Ratio>()
                 .map_err(|e| format!("Invalid frequency: '{}': {}", freq, e))?;
-            Ok(Pitch::from_hz(freq.as_float()))
+            Pitch::from_hz(freq.as_float())
         } else {
-            Err("Must end with Hz or hz".to_string())
+            base.parse::<Note>()
+                .map_err(|_| {
+                    format!(
+                        "Invalid pitch '{}': Must be a frequency (e.g. 440Hz) or a note (e.g. A4)",
+                        base
+                    )
+                })?
+                .pitch()
+        };
+
+        apply_operation(base_pitch, operation)
+    }
+}
+
+/// Splits a pitch expression at its first top-level operator.
+///
+/// A frequency (e.g. `1000/3Hz`) is matched as a whole before any operator is considered.
+/// A `-` directly following a note letter (e.g. `Bb-1`) is considered to be part of the octave number.
+fn split_pitch_expression(s: &str) -> (&str, Option<(char, &str)>) {
+    if let Some(end_of_freq) = find_end_of_frequency(s) {
+        let (base, rest) = s.split_at(end_of_freq);
+        let mut rest = rest.trim().chars();
+        match rest.next() {
+            None => return (base.trim(), None),
+            Some(c @ '+') | Some(c @ '*') | Some(c @ '/') | Some(c @ '-') => {
+                return (base.trim(), Some((c, rest.as_str().trim())))
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut num_parens = 0;
+    for (index, c) in s.char_indices() {
+        match c {
+            '(' => num_parens += 1,
+            ')' => num_parens -= 1,
+            '+' | '*' | '/' | '-' if num_parens == 0 => {
+                let (base, rest) = s.split_at(index);
+                if c == '-' && is_note_without_octave(base.trim()) {
+                    continue;
+                }
+                return (base.trim(), Some((c, rest[1..].trim())));
+            }
+            _ => {}
+        }
+    }
+    (s.trim(), None)
+}
+
+fn find_end_of_frequency(s: &str) -> Option<usize> {
+    let mut num_parens = 0;
+    for (index, c) in s.char_indices() {
+        match c {
+            '(' => num_parens += 1,
+            ')' => num_parens -= 1,
+            'H' | 'h' if num_parens == 0 && s[index + 1..].starts_with('z') => {
+                return Some(index + 2)
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_note_without_octave(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some('A'..='G') | Some('a'..='g'))
+        && chars.all(|c| matches!(c, '#' | 'b' | 'x'))
+}
+
+fn apply_operation(pitch: Pitch, operation: Option<(char, &str)>) -> Result<Pitch, String> {
+    match operation {
+        None => Ok(pitch),
+        Some((operator, ratio)) => {
+            let ratio = ratio
+                .parse::<Ratio>()
+                .map_err(|e| format!("Invalid ratio '{}': {}", ratio, e))?;
+            Ok(match operator {
+                '+' | '*' => pitch * ratio,
+                _ => pitch / ratio,
+            })
         }
     }
 }
@@ -147,6 +263,34 @@ pub struct Description<N> {
     pub deviation: Ratio,
 }
 
+impl<N> Description<N> {
+    /// Finds the named just intonation interval (e.g. a comma) matching the absolute deviation within 0.1c.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Pitch;
+    /// # use tune::ratio::Ratio;
+    /// # use tune::tuning::ConcertPitch;
+    /// let just_major_third = Pitch::from_hz(440.0) * Ratio::from_float(5.0 / 4.0);
+    /// let description = just_major_third.describe(ConcertPitch::default());
+    /// assert!(description.deviation_name().is_none());
+    ///
+    /// let a4_plus_syntonic_comma = Pitch::from_hz(440.0) * Ratio::from_float(81.0 / 80.0);
+    /// let description = a4_plus_syntonic_comma.describe(ConcertPitch::default());
+    /// assert_eq!(description.deviation_name().unwrap().name, "syntonic comma");
+    /// assert_eq!(
+    ///     description.to_string(),
+    ///     "445.500 Hz | A 4 | +21.506c (81/80 syntonic comma)"
+    /// );
+    /// ```
+    pub fn deviation_name(&self) -> Option<&'static NamedInterval> {
+        let abs_deviation = Ratio::from_cents(self.deviation.as_cents().abs());
+        interval::find_ji_interval(abs_deviation, Ratio::from_cents(0.1))
+            .filter(|interval| interval.value != IntervalValue::Just { numer: 1, denom: 1 })
+    }
+}
+
 impl<N: Display> Display for Description<N> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "{:.3} Hz | {}", self.freq_in_hz, self.approx_value,)?;
@@ -154,6 +298,9 @@ impl<N: Display> Display for Description<N> {
         let deviation_in_cents = self.deviation.as_cents();
         if deviation_in_cents.abs() >= 0.001 {
             write!(f, " | {:+.3}c", deviation_in_cents)?;
+            if let Some(deviation_name) = self.deviation_name() {
+                write!(f, " ({})", deviation_name)?;
+            }
         }
 
         Ok(())
@@ -187,48 +334,64 @@ impl ReferencePitch {
     }
 }
 
+/// Parses a [`ReferencePitch`] given as a reference note and an optional pitch.
+///
+/// The reference note can be given as a MIDI number or as a [`Note`].
+/// The pitch can either be specified explicitly using `@` followed by a [`Pitch`] expression or relative to the reference note using `+`, `-`, `*` or `/` followed by a [`Ratio`] expression.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::pitch::ReferencePitch;
+/// let parse = |s: &str| {
+///     let ref_pitch = s.parse::<ReferencePitch>().unwrap();
+///     (ref_pitch.key().midi_number(), ref_pitch.pitch().as_hz())
+/// };
+///
+/// assert_eq!(parse("69").0, 69);
+/// assert_approx_eq!(parse("69").1, 440.0);
+/// assert_approx_eq!(parse("69@432Hz").1, 432.0);
+/// assert_approx_eq!(parse("60+100c").1, 277.182631);
+///
+/// assert_eq!(parse("A4@432Hz").0, 69);
+/// assert_approx_eq!(parse("A4@432Hz").1, 432.0);
+/// assert_approx_eq!(parse("C4@A4/5/3").1, 264.0);
+/// assert_eq!(parse("Bb-1-1200c").0, 10);
+/// assert_approx_eq!(parse("Bb-1-1200c").1, 7.283809);
+///
+/// assert_eq!(
+///     "H4".parse::<ReferencePitch>().unwrap_err(),
+///     "Invalid note 'H4': Must be a MIDI number (e.g. 69) or a note (e.g. A4)"
+/// );
+/// ```
 impl FromStr for ReferencePitch {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if let [note, pitch] = parse::split_balanced(s, '@').as_slice() {
-            let note_number = note
-                .parse()
-                .map_err(|_| format!("Invalid note '{}': Must be an integer", note))?;
+            let key = parse_key(note)?;
             let pitch: Pitch = pitch
                 .parse()
                 .map_err(|e| format!("Invalid pitch '{}': {}", pitch, e))?;
-            Ok(ReferencePitch::from_key_and_pitch(
-                PianoKey::from_midi_number(note_number),
-                pitch,
-            ))
-        } else if let [note, delta] = parse::split_balanced(s, '+').as_slice() {
-            let note_number = note
-                .parse()
-                .map_err(|_| format!("Invalid note '{}': Must be an integer", note))?;
-            let delta = delta
-                .parse()
-                .map_err(|e| format!("Invalid delta '{}': {}", delta, e))?;
-            Ok(ReferencePitch::from_note(
-                Note::from_midi_number(note_number).alter_pitch_by(delta),
-            ))
-        } else if let [note, delta] = parse::split_balanced(s, '-').as_slice() {
-            let note_number = note
-                .parse()
-                .map_err(|_| format!("Invalid note '{}': Must be an integer", note))?;
-            let delta = delta
-                .parse::<Ratio>()
-                .map_err(|e| format!("Invalid delta '{}': {}", delta, e))?;
-            Ok(ReferencePitch::from_note(
-                Note::from_midi_number(note_number).alter_pitch_by(delta.inv()),
-            ))
+            Ok(ReferencePitch::from_key_and_pitch(key, pitch))
         } else {
-            let note_number = s
-                .parse()
-                .map_err(|_| "Must be an expression of type 69, 69@440Hz or 69+100c".to_string())?;
-            Ok(ReferencePitch::from_note(Note::from_midi_number(
-                note_number,
-            )))
+            let (note, operation) = split_pitch_expression(s);
+            let key = parse_key(note)?;
+            let pitch = apply_operation(Note::from_piano_key(key).pitch(), operation)?;
+            Ok(ReferencePitch::from_key_and_pitch(key, pitch))
         }
     }
 }
+
+fn parse_key(note: &str) -> Result<PianoKey, String> {
+    if let Ok(midi_number) = note.parse() {
+        return Ok(PianoKey::from_midi_number(midi_number));
+    }
+    note.parse::<Note>().map(Note::as_piano_key).map_err(|_| {
+        format!(
+            "Invalid note '{}': Must be a MIDI number (e.g. 69) or a note (e.g. A4)",
+            note
+        )
+    })
+}
@@ -0,0 +1,351 @@
+//! Catalog of historical temperaments and just intonation scales.
+
+use crate::{
+    math,
+    ratio::Ratio,
+    scala::{Scl, SclBuildError},
+};
+use std::convert::TryFrom;
+
+/// A scale with a name that early-music players would use to talk about it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScalePreset {
+    /// The identifier of the preset, e.g. `werckmeister-3`.
+    pub name: &'static str,
+    pub description: &'static str,
+    pub definition: PresetDefinition,
+}
+
+/// The exact definition of a [`ScalePreset`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PresetDefinition {
+    /// A chain of 12 notes connected by 11 fifths, starting `num_fifths_below_root` fifths below the root, e.g. Eb–Bb–F–C–G–D–A–E–B–F#–C#–G# for 3 fifths below C.
+    ///
+    /// `num_fifths_below_root` must not exceed 11 since the root needs to be part of the chain.
+    FifthsChain {
+        num_fifths_below_root: u16,
+        fifths: [TemperedFifth; 11],
+    },
+    /// Just intonation fractions in ascending order, excluding the unison and including the period.
+    Just(&'static [(u32, u32)]),
+}
+
+/// A 3/2 fifth widened or, if `numer` is negative, narrowed by `numer/denom` of a [`Comma`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TemperedFifth {
+    pub numer: i32,
+    pub denom: u32,
+    pub comma: Comma,
+}
+
+/// The commas used to temper the fifths of a [`PresetDefinition::FifthsChain`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Comma {
+    /// 81/80
+    Syntonic,
+    /// 531441/524288
+    Pythagorean,
+    /// 32805/32768
+    Schisma,
+}
+
+impl Comma {
+    pub fn fraction(self) -> (u32, u32) {
+        match self {
+            Comma::Syntonic => (81, 80),
+            Comma::Pythagorean => (531_441, 524_288),
+            Comma::Schisma => (32805, 32768),
+        }
+    }
+
+    pub fn ratio(self) -> Ratio {
+        let (numer, denom) = self.fraction();
+        Ratio::from_float(f64::from(numer) / f64::from(denom))
+    }
+}
+
+impl TemperedFifth {
+    const fn pure() -> Self {
+        Self {
+            numer: 0,
+            denom: 1,
+            comma: Comma::Syntonic,
+        }
+    }
+
+    const fn narrowed(denom: u32, comma: Comma) -> Self {
+        Self {
+            numer: -1,
+            denom,
+            comma,
+        }
+    }
+
+    pub fn is_pure(self) -> bool {
+        self.numer == 0
+    }
+
+    /// Returns the [`Ratio`] of the tempered fifth.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::presets::{Comma, TemperedFifth};
+    /// let quarter_comma_fifth = TemperedFifth { numer: -1, denom: 4, comma: Comma::Syntonic };
+    /// assert_approx_eq!(quarter_comma_fifth.ratio().as_cents(), 696.578428);
+    /// ```
+    pub fn ratio(self) -> Ratio {
+        let fraction_of_comma = f64::from(self.numer) / f64::from(self.denom);
+        let pure_fifth = Ratio::from_float(3.0 / 2.0);
+        Ratio::from_octaves(
+            pure_fifth.as_octaves() + self.comma.ratio().as_octaves() * fraction_of_comma,
+        )
+    }
+}
+
+impl ScalePreset {
+    const fn fifths_chain(
+        name: &'static str,
+        description: &'static str,
+        num_fifths_below_root: u16,
+        fifths: [TemperedFifth; 11],
+    ) -> Self {
+        Self {
+            name,
+            description,
+            definition: PresetDefinition::FifthsChain {
+                num_fifths_below_root,
+                fifths,
+            },
+        }
+    }
+
+    const fn just(
+        name: &'static str,
+        description: &'static str,
+        fractions: &'static [(u32, u32)],
+    ) -> Self {
+        Self {
+            name,
+            description,
+            definition: PresetDefinition::Just(fractions),
+        }
+    }
+
+    /// Creates an [`Scl`] of the preset.
+    ///
+    /// Notes whose fifths are tempered by whole commas in total, e.g. the pure major thirds of Kirnberger III, are exported as exact fractions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::presets;
+    /// let werckmeister = presets::find_preset("werckmeister-3").unwrap().to_scl().unwrap();
+    ///
+    /// assert_eq!(werckmeister.size(), 12);
+    /// assert_approx_eq!(werckmeister.relative_pitch_of(1).as_cents(), 90.224996);
+    /// assert_approx_eq!(werckmeister.relative_pitch_of(4).as_cents(), 390.224996);
+    /// assert_approx_eq!(werckmeister.relative_pitch_of(7).as_cents(), 696.089998);
+    /// assert_eq!(werckmeister.fraction_of(1), Some((256, 243)));
+    /// assert_eq!(werckmeister.fraction_of(4), None);
+    /// assert_eq!(werckmeister.fraction_of(12), Some((2, 1)));
+    ///
+    /// let kirnberger = presets::find_preset("kirnberger-3").unwrap().to_scl().unwrap();
+    /// assert_eq!(kirnberger.fraction_of(4), Some((5, 4)));
+    /// assert_eq!(kirnberger.fraction_of(11), Some((15, 8)));
+    ///
+    /// let ptolemaic = presets::find_preset("ptolemaic").unwrap().to_scl().unwrap();
+    /// assert_eq!(ptolemaic.size(), 7);
+    /// assert_eq!(ptolemaic.fraction_of(2), Some((5, 4)));
+    ///
+    /// // The root must be part of the chain of fifths
+    /// # use tune::presets::PresetDefinition;
+    /// # use tune::scala::SclBuildError;
+    /// let mut detached_root = *presets::find_preset("pythagorean").unwrap();
+    /// if let PresetDefinition::FifthsChain { num_fifths_below_root, .. } = &mut detached_root.definition {
+    ///     *num_fifths_below_root = 12;
+    /// }
+    /// assert!(matches!(detached_root.to_scl(), Err(SclBuildError::RootOutOfRange)));
+    /// ```
+    pub fn to_scl(&self) -> Result<Scl, SclBuildError> {
+        let mut builder = Scl::builder();
+        match self.definition {
+            PresetDefinition::FifthsChain {
+                num_fifths_below_root,
+                fifths,
+            } => {
+                let root_index = usize::from(num_fifths_below_root);
+                if root_index > fifths.len() {
+                    return Err(SclBuildError::RootOutOfRange);
+                }
+                let mut notes = (0..=fifths.len())
+                    .filter(|&index| index != root_index)
+                    .map(|index| {
+                        let (path, direction) = if index > root_index {
+                            (&fifths[root_index..index], 1)
+                        } else {
+                            (&fifths[index..root_index], -1)
+                        };
+                        let octaves = path
+                            .iter()
+                            .map(|fifth| fifth.ratio().as_octaves())
+                            .sum::<f64>()
+                            * f64::from(direction);
+                        let fraction = exact_fraction(path, direction);
+                        (octaves.rem_euclid(1.0), fraction)
+                    })
+                    .collect::<Vec<_>>();
+                notes.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("Comparison failed"));
+
+                for (octaves, fraction) in notes {
+                    builder = match fraction {
+                        Some((numer, denom)) => builder.push_fraction(numer, denom),
+                        None => builder.push_ratio(Ratio::from_octaves(octaves)),
+                    };
+                }
+                builder = builder.push_int(2);
+            }
+            PresetDefinition::Just(fractions) => {
+                for &(numer, denom) in fractions {
+                    builder = builder.push_fraction(numer, denom);
+                }
+            }
+        }
+        builder.build_with_description(self.description)
+    }
+}
+
+/// The octave-reduced fraction of a chain of fifths, if the fifths are tempered by whole commas in total.
+fn exact_fraction(path: &[TemperedFifth], direction: i32) -> Option<(u32, u32)> {
+    let mut exponent_of_3 = path.len() as i32;
+    let mut exponent_of_5 = 0;
+    for &comma in &[Comma::Syntonic, Comma::Pythagorean, Comma::Schisma] {
+        let (numer, denom) = path
+            .iter()
+            .filter(|fifth| fifth.comma == comma && !fifth.is_pure())
+            .fold((0, 1), |(numer, denom), fifth| {
+                let denom_of_fifth = fifth.denom as i32;
+                let numer = numer * denom_of_fifth + fifth.numer * denom;
+                let denom = denom * denom_of_fifth;
+                let gcd = math::gcd_u64(numer.unsigned_abs().into(), denom.unsigned_abs().into());
+                let gcd = gcd.max(1) as i32;
+                (numer / gcd, denom / gcd)
+            });
+        if denom != 1 {
+            return None;
+        }
+        let (comma_exponent_of_3, comma_exponent_of_5) = match comma {
+            Comma::Syntonic => (4, -1),
+            Comma::Pythagorean => (12, 0),
+            Comma::Schisma => (8, 1),
+        };
+        exponent_of_3 += numer * comma_exponent_of_3;
+        exponent_of_5 += numer * comma_exponent_of_5;
+    }
+
+    let power = |base: u64, exponent: i32| base.checked_pow(exponent.unsigned_abs());
+    let (exponent_of_3, exponent_of_5) = (exponent_of_3 * direction, exponent_of_5 * direction);
+    let mut numer = power(3, exponent_of_3.max(0))?.checked_mul(power(5, exponent_of_5.max(0))?)?;
+    let mut denom =
+        power(3, -exponent_of_3.min(0))?.checked_mul(power(5, -exponent_of_5.min(0))?)?;
+    while numer < denom {
+        numer = numer.checked_mul(2)?;
+    }
+    while numer >= 2 * denom {
+        denom = denom.checked_mul(2)?;
+    }
+    Some((u32::try_from(numer).ok()?, u32::try_from(denom).ok()?))
+}
+
+const P: TemperedFifth = TemperedFifth::pure();
+const QSC: TemperedFifth = TemperedFifth::narrowed(4, Comma::Syntonic);
+const SSC: TemperedFifth = TemperedFifth::narrowed(6, Comma::Syntonic);
+const QPC: TemperedFifth = TemperedFifth::narrowed(4, Comma::Pythagorean);
+const SPC: TemperedFifth = TemperedFifth::narrowed(6, Comma::Pythagorean);
+const SCH: TemperedFifth = TemperedFifth::narrowed(1, Comma::Schisma);
+
+/// Curated list of historical temperaments and just intonation scales.
+///
+/// The fifths of each chain are listed from Eb–Bb to C#–G#.
+pub const SCALE_PRESETS: &[ScalePreset] = &[
+    ScalePreset::fifths_chain(
+        "pythagorean",
+        "Pythagorean tuning, all fifths pure (Eb-G#)",
+        3,
+        [P, P, P, P, P, P, P, P, P, P, P],
+    ),
+    ScalePreset::fifths_chain(
+        "meantone-1-4",
+        "Quarter-comma meantone, all fifths narrowed by 1/4 syntonic comma (Eb-G#)",
+        3,
+        [QSC, QSC, QSC, QSC, QSC, QSC, QSC, QSC, QSC, QSC, QSC],
+    ),
+    ScalePreset::fifths_chain(
+        "meantone-1-6",
+        "Sixth-comma meantone, all fifths narrowed by 1/6 syntonic comma (Eb-G#)",
+        3,
+        [SSC, SSC, SSC, SSC, SSC, SSC, SSC, SSC, SSC, SSC, SSC],
+    ),
+    ScalePreset::fifths_chain(
+        "werckmeister-3",
+        "Werckmeister III (1691), C-G-D-A and B-F# narrowed by 1/4 Pythagorean comma",
+        3,
+        [P, P, P, QPC, QPC, QPC, P, P, QPC, P, P],
+    ),
+    ScalePreset::fifths_chain(
+        "kirnberger-3",
+        "Kirnberger III (1779), C-G-D-A-E narrowed by 1/4 syntonic comma, F#-C# narrowed by a schisma",
+        3,
+        [P, P, P, QSC, QSC, QSC, QSC, P, P, SCH, P],
+    ),
+    ScalePreset::fifths_chain(
+        "vallotti",
+        "Vallotti (ca. 1750), F-C-G-D-A-E-B narrowed by 1/6 Pythagorean comma",
+        3,
+        [P, P, SPC, SPC, SPC, SPC, SPC, SPC, P, P, P],
+    ),
+    ScalePreset::fifths_chain(
+        "young",
+        "Young No. 2 (1799), C-G-D-A-E-B-F# narrowed by 1/6 Pythagorean comma",
+        3,
+        [P, P, P, SPC, SPC, SPC, SPC, SPC, SPC, P, P],
+    ),
+    ScalePreset::just(
+        "ptolemaic",
+        "Ptolemy's intense diatonic, 5-limit just major scale",
+        &[(9, 8), (5, 4), (4, 3), (3, 2), (5, 3), (15, 8), (2, 1)],
+    ),
+    ScalePreset::just(
+        "just-chromatic",
+        "5-limit just chromatic scale",
+        &[
+            (16, 15),
+            (9, 8),
+            (6, 5),
+            (5, 4),
+            (4, 3),
+            (45, 32),
+            (3, 2),
+            (8, 5),
+            (5, 3),
+            (9, 5),
+            (15, 8),
+            (2, 1),
+        ],
+    ),
+];
+
+/// Finds the [`ScalePreset`] with the given name.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::presets;
+/// assert_eq!(presets::find_preset("vallotti").unwrap().name, "vallotti");
+/// assert!(presets::find_preset("equal").is_none());
+/// ```
+pub fn find_preset(name: &str) -> Option<&'static ScalePreset> {
+    SCALE_PRESETS.iter().find(|preset| preset.name == name)
+}
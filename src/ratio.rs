@@ -1,7 +1,8 @@
 //! Linear and logarithmic operations on frequency ratios.
 
 use crate::math;
-use crate::{parse, pitch::Pitched};
+use crate::pitch::Pitched;
+use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -239,6 +240,226 @@ impl Ratio {
     pub fn nearest_fraction(self, limit: u16) -> NearestFraction {
         NearestFraction::for_float_with_limit(self.as_float(), limit)
     }
+
+    /// Finds the most accurate rational number approximation of the current [Ratio] instance satisfying the given [`FractionConstraints`].
+    ///
+    /// This is the last element of [`Ratio::best_approximations`]. If no fraction satisfies the constraints [`None`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::ratio::FractionConstraints;
+    /// # use tune::ratio::Ratio;
+    /// let major_third = Ratio::from_semitones(4);
+    ///
+    /// let f = major_third
+    ///     .nearest_fraction_with(&FractionConstraints::odd_limit(11))
+    ///     .unwrap();
+    /// assert_eq!((f.numer, f.denom), (5, 4));
+    /// assert_approx_eq!(f.deviation.as_cents(), 13.686286);
+    ///
+    /// let harmonic_seventh = Ratio::from_cents(970.0);
+    ///
+    /// let f = harmonic_seventh
+    ///     .nearest_fraction_with(&FractionConstraints::odd_limit(81))
+    ///     .unwrap();
+    /// assert_eq!((f.numer, f.denom), (7, 4));
+    /// assert_approx_eq!(f.deviation.as_cents(), 1.174094);
+    ///
+    /// let five_limit = FractionConstraints::odd_limit(81).with_prime_limit(5);
+    /// let f = harmonic_seventh.nearest_fraction_with(&five_limit).unwrap();
+    /// assert_eq!((f.numer, f.denom), (16, 9));
+    /// assert_approx_eq!(f.deviation.as_cents(), -26.089998);
+    ///
+    /// let within_5_cents = five_limit.with_max_error_in_cents(5.0);
+    /// assert!(harmonic_seventh.nearest_fraction_with(&within_5_cents).is_none());
+    /// ```
+    pub fn nearest_fraction_with(
+        self,
+        constraints: &FractionConstraints,
+    ) -> Option<NearestFraction> {
+        self.best_approximations(constraints).pop()
+    }
+
+    /// Lists the best rational number approximations of the current [Ratio] instance satisfying the given [`FractionConstraints`].
+    ///
+    /// The candidates are all octave-reduced fractions that pass the given constraints.
+    /// The approximations are ordered by increasing [Tenney height](https://en.xen.wiki/w/Tenney_height) `log2(numer*denom)`.
+    /// Each approximation is strictly more accurate than all of its simpler predecessors, s.t. the absolute value of [`NearestFraction::deviation`] is an upper error bound for all subsequent approximations.
+    /// Like in [`Ratio::nearest_fraction`], the approximations are normalized to values within an octave.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ratio::FractionConstraints;
+    /// # use tune::ratio::Ratio;
+    /// let major_third = Ratio::from_semitones(4);
+    /// let approximations = major_third.best_approximations(&FractionConstraints::odd_limit(11));
+    ///
+    /// let as_strings = approximations
+    ///     .iter()
+    ///     .map(ToString::to_string)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     as_strings,
+    ///     [
+    ///         "1/1 [+400c] (+0o)",
+    ///         "3/2 [-302c] (+0o)",
+    ///         "4/3 [-98c] (+0o)",
+    ///         "5/4 [+14c] (+0o)"
+    ///     ]
+    /// );
+    /// ```
+    pub fn best_approximations(self, constraints: &FractionConstraints) -> Vec<NearestFraction> {
+        let num_octaves = self.as_octaves().floor();
+        let normalized_ratio = self.as_float() / num_octaves.exp2();
+
+        let odd_numbers = (1..=constraints.odd_limit)
+            .step_by(2)
+            .filter(|&odd_number| match constraints.prime_limit {
+                Some(prime_limit) => math::largest_prime_factor_u16(odd_number) <= prime_limit,
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
+        let mut fractions = Vec::new();
+        for &numer in &odd_numbers {
+            for &denom in &odd_numbers {
+                if math::gcd_u16(numer, denom) == 1 {
+                    fractions.extend(octave_reduced(numer, denom));
+                }
+            }
+        }
+        // Ratios slightly below the octave are approximated best by 2/1
+        fractions.push((2, 1));
+
+        let mut candidates = Vec::new();
+        for (numer, denom) in fractions {
+            if !constraints.admits(numer, denom) {
+                continue;
+            }
+            let deviation =
+                Ratio::from_float(normalized_ratio * f64::from(denom) / f64::from(numer));
+            if matches!(constraints.max_error_in_cents, Some(max) if deviation.as_cents().abs() > max)
+            {
+                continue;
+            }
+            candidates.push((u32::from(numer) * u32::from(denom), numer, denom, deviation));
+        }
+        candidates.sort_by_key(|&(numer_times_denom, numer, ..)| (numer_times_denom, numer));
+
+        let mut best_approximations: Vec<NearestFraction> = Vec::new();
+        for (_, numer, denom, deviation) in candidates {
+            let is_better = match best_approximations.last() {
+                Some(best) => {
+                    deviation.as_cents().abs()
+                        < best.deviation.as_cents().abs() - ACCURACY_TOLERANCE_IN_CENTS
+                }
+                None => true,
+            };
+            if is_better {
+                best_approximations.push(NearestFraction {
+                    numer,
+                    denom,
+                    deviation,
+                    num_octaves: num_octaves as i32,
+                });
+            }
+        }
+        best_approximations
+    }
+}
+
+/// Multiplies the smaller of the two odd numbers by a power of two s.t. the fraction lies within [1, 2). Returns [`None`] if the result does not fit into a [`u16`].
+fn octave_reduced(odd_numer: u16, odd_denom: u16) -> Option<(u16, u16)> {
+    let (mut numer, mut denom) = (u32::from(odd_numer), u32::from(odd_denom));
+    while numer < denom {
+        numer *= 2;
+    }
+    while denom * 2 <= numer {
+        denom *= 2;
+    }
+    Some((u16::try_from(numer).ok()?, u16::try_from(denom).ok()?))
+}
+
+/// Deviations are compared with a small tolerance s.t. exact ties (e.g. 9/8 and 10/9 in quarter-comma meantone) resolve to the simpler fraction.
+const ACCURACY_TOLERANCE_IN_CENTS: f64 = 1e-9;
+
+/// Constraints for [`Ratio::best_approximations`] and [`Ratio::nearest_fraction_with`].
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ratio::FractionConstraints;
+/// let constraints = FractionConstraints::odd_limit(255)
+///     .with_prime_limit(13)
+///     .with_max_tenney_height(16.0)
+///     .with_max_error_in_cents(10.0);
+///
+/// assert_eq!(constraints.odd_limit, 255);
+/// assert_eq!(constraints.prime_limit, Some(13));
+/// assert_eq!(constraints.max_tenney_height, Some(16.0));
+/// assert_eq!(constraints.max_error_in_cents, Some(10.0));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct FractionConstraints {
+    /// Largest acceptable numerator or denominator (ignoring powers of two).
+    ///
+    /// The computation time grows quadratically with the number of odd numbers within the odd limit (and the prime limit).
+    pub odd_limit: u16,
+
+    /// Largest acceptable prime factor of the numerator or denominator.
+    pub prime_limit: Option<u16>,
+
+    /// Largest acceptable Tenney height `log2(numer*denom)` of the octave-reduced fraction.
+    pub max_tenney_height: Option<f64>,
+
+    /// Largest acceptable deviation from the approximated [`Ratio`] in either direction.
+    pub max_error_in_cents: Option<f64>,
+}
+
+impl FractionConstraints {
+    pub fn odd_limit(odd_limit: u16) -> Self {
+        Self {
+            odd_limit,
+            prime_limit: None,
+            max_tenney_height: None,
+            max_error_in_cents: None,
+        }
+    }
+
+    pub fn with_prime_limit(mut self, prime_limit: u16) -> Self {
+        self.prime_limit = Some(prime_limit);
+        self
+    }
+
+    pub fn with_max_tenney_height(mut self, max_tenney_height: f64) -> Self {
+        self.max_tenney_height = Some(max_tenney_height);
+        self
+    }
+
+    pub fn with_max_error_in_cents(mut self, max_error_in_cents: f64) -> Self {
+        self.max_error_in_cents = Some(max_error_in_cents);
+        self
+    }
+
+    /// Checks the odd limit, prime limit and Tenney height of an octave-reduced fraction.
+    fn admits(&self, numer: u16, denom: u16) -> bool {
+        let (odd_numer, odd_denom) = (math::odd_factors_u16(numer), math::odd_factors_u16(denom));
+        if odd_numer > self.odd_limit || odd_denom > self.odd_limit {
+            return false;
+        }
+        if let Some(prime_limit) = self.prime_limit {
+            if math::largest_prime_factor_u16(odd_numer) > prime_limit
+                || math::largest_prime_factor_u16(odd_denom) > prime_limit
+            {
+                return false;
+            }
+        }
+        let tenney_height = (f64::from(numer) * f64::from(denom)).log2();
+        !matches!(self.max_tenney_height, Some(max) if tenney_height > max)
+    }
 }
 
 /// The default [`Ratio`] is the ratio that respresents equivalence of two frequencies, i.e. no distance at all.
@@ -306,7 +527,19 @@ impl Display for Ratio {
 /// assert_approx_eq!("3/2".parse::<Ratio>().unwrap().as_float(), 1.5);
 /// assert_approx_eq!("7:12:2".parse::<Ratio>().unwrap().as_semitones(), 7.0);
 /// assert_approx_eq!("702c".parse::<Ratio>().unwrap().as_cents(), 702.0);
-/// assert_eq!("foo".parse::<Ratio>().unwrap_err(), "Invalid expression \'foo\': Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2) or cents value (e.g. 702c)");
+/// assert_approx_eq!("3/2*5/4".parse::<Ratio>().unwrap().as_float(), 1.875);
+/// assert_approx_eq!("(3/2)^4".parse::<Ratio>().unwrap().as_float(), 5.0625);
+/// assert_approx_eq!("700c+2c".parse::<Ratio>().unwrap().as_cents(), 702.0);
+/// assert_approx_eq!("7\\12".parse::<Ratio>().unwrap().as_semitones(), 7.0);
+/// assert_approx_eq!("13\\13<3>".parse::<Ratio>().unwrap().as_float(), 3.0);
+/// assert_approx_eq!("tritave/octave".parse::<Ratio>().unwrap().as_float(), 1.5);
+/// assert_eq!(
+///     "3/2*foo".parse::<Ratio>().unwrap_err(),
+///     "Invalid expression '3/2*foo': Unexpected 'foo' at position 5: \
+///      Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), \
+///      cents value (e.g. 702c), EDO step (e.g. 7\\12) or constant (e.g. octave)"
+/// );
+/// ```
 impl FromStr for Ratio {
     type Err = String;
 
@@ -350,6 +583,21 @@ impl FromStr for RatioExpression {
 }
 
 /// Type used to distinguish which particular outer expression was given as string input bevor parsing.
+///
+/// Sums of cents values (e.g. `700c+2c`) are recorded as a single [`RatioExpressionVariant::Cents`] value.
+/// Chains of products and quotients (e.g. `3/2*5/4`) are recorded as a single [`RatioExpressionVariant::Product`] value whose `numer` and `denom` collect the numerators and denominators of all factors.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::ratio::RatioExpression;
+/// # use tune::ratio::RatioExpressionVariant;
+/// let product = "3/2*5/4".parse::<RatioExpression>().unwrap();
+/// assert!(matches!(
+///     product.variant(),
+///     RatioExpressionVariant::Product { numer, denom } if numer == 15.0 && denom == 8.0
+/// ));
+/// ```
 #[derive(Copy, Clone, Debug)]
 pub enum RatioExpressionVariant {
     Float {
@@ -359,14 +607,31 @@ pub enum RatioExpressionVariant {
         numer: f64,
         denom: f64,
     },
+    Product {
+        numer: f64,
+        denom: f64,
+    },
+    Power {
+        base: f64,
+        exponent: f64,
+    },
     IntervalFraction {
         numer: f64,
         denom: f64,
         interval: f64,
     },
+    EdoSteps {
+        num_steps: f64,
+        num_steps_per_interval: f64,
+        interval: f64,
+    },
     Cents {
         cents_value: f64,
     },
+    Constant {
+        name: &'static str,
+        float_value: f64,
+    },
 }
 
 impl RatioExpressionVariant {
@@ -386,12 +651,20 @@ impl RatioExpressionVariant {
         let as_float = match self {
             Self::Float { float_value } => float_value,
             Self::Fraction { numer, denom } => numer / denom,
+            Self::Product { numer, denom } => numer / denom,
+            Self::Power { base, exponent } => base.powf(exponent),
             Self::IntervalFraction {
                 numer,
                 denom,
                 interval,
             } => interval.powf(numer / denom),
+            Self::EdoSteps {
+                num_steps,
+                num_steps_per_interval,
+                interval,
+            } => interval.powf(num_steps / num_steps_per_interval),
             Self::Cents { cents_value } => Ratio::from_cents(cents_value).as_float(),
+            Self::Constant { float_value, .. } => float_value,
         };
         if as_float.is_finite() {
             Ok(as_float)
@@ -401,40 +674,388 @@ impl RatioExpressionVariant {
     }
 }
 
+const EXPECTED_OPERAND: &str = "Must be a float (e.g. 1.5), fraction (e.g. 3/2), \
+                                interval fraction (e.g. 7:12:2), cents value (e.g. 702c), \
+                                EDO step (e.g. 7\\12) or constant (e.g. octave)";
+
+const EXPECTED_OPERATOR: &str =
+    "Must be an operator (+, -, *, /, ^, :, \\) or the end of the expression";
+
 fn parse_ratio(s: &str) -> Result<RatioExpressionVariant, String> {
-    let s = s.trim();
-    if let [numer, denom, interval] = parse::split_balanced(&s, ':').as_slice() {
-        Ok(RatioExpressionVariant::IntervalFraction {
-            numer: parse_ratio_as_float(numer, "interval numerator")?,
-            denom: parse_ratio_as_float(denom, "interval denominator")?,
-            interval: parse_ratio_as_float(interval, "interval")?,
+    let mut parser = Parser {
+        input: s,
+        tokens: tokenize(s)?,
+        index: 0,
+    };
+    let parsed = parser.parse_expression()?;
+    if parser.peek() != Token::End {
+        return Err(parser.unexpected(EXPECTED_OPERATOR));
+    }
+    Ok(parsed.variant)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Token<'a> {
+    Number(f64),
+    Word(&'a str),
+    Symbol(char),
+    End,
+}
+
+/// A token together with its byte range in the input.
+type Spanned<'a> = (usize, usize, Token<'a>);
+
+fn tokenize(input: &str) -> Result<Vec<Spanned<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while let Some(c) = input[start..].chars().next() {
+        let rest = &input[start..];
+        let len = if c.is_whitespace() {
+            c.len_utf8()
+        } else if c.is_ascii_digit() || c == '.' {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let len = len + exponent_len(&rest[len..]);
+            let number = rest[..len]
+                .parse()
+                .map_err(|_| unexpected(input, start, start + len, "Must be a valid number"))?;
+            tokens.push((start, start + len, Token::Number(number)));
+            len
+        } else if c.is_alphabetic() {
+            let len = rest
+                .find(|c: char| !c.is_alphabetic())
+                .unwrap_or(rest.len());
+            tokens.push((start, start + len, Token::Word(&rest[..len])));
+            len
+        } else if "()+-*/^:\\<>".contains(c) {
+            tokens.push((start, start + 1, Token::Symbol(c)));
+            1
+        } else {
+            return Err(unexpected(
+                input,
+                start,
+                start + c.len_utf8(),
+                EXPECTED_OPERAND,
+            ));
+        };
+        start += len;
+    }
+    tokens.push((input.len(), input.len(), Token::End));
+    Ok(tokens)
+}
+
+/// Determines the length of an exponent (e.g. `e-3`) at the start of `s` or returns 0 if there is none.
+fn exponent_len(s: &str) -> usize {
+    let mut chars = s.char_indices().peekable();
+    if !matches!(chars.next(), Some((_, 'e')) | Some((_, 'E'))) {
+        return 0;
+    }
+    if matches!(chars.peek(), Some((_, '+')) | Some((_, '-'))) {
+        chars.next();
+    }
+    let digits_start = chars.peek().map(|&(index, _)| index).unwrap_or(s.len());
+    let digits_end = s[digits_start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map(|len| digits_start + len)
+        .unwrap_or(s.len());
+    if digits_end > digits_start {
+        digits_end
+    } else {
+        0
+    }
+}
+
+fn unexpected(input: &str, start: usize, end: usize, expected: &str) -> String {
+    let position = input[..start].chars().count() + 1;
+    if start == input.len() {
+        format!(
+            "Unexpected end of expression at position {}: {}",
+            position, expected
+        )
+    } else {
+        format!(
+            "Unexpected '{}' at position {}: {}",
+            &input[start..end],
+            position,
+            expected
+        )
+    }
+}
+
+/// A parsed subexpression together with its byte range in the input.
+struct Parsed {
+    variant: RatioExpressionVariant,
+    start: usize,
+    end: usize,
+}
+
+/// Recursive descent parser. Ordered by increasing precedence, the grammar rules are:
+///
+/// - Interval fraction: `<sum>:<sum>:<sum>`
+/// - Sum: `<product>+<product>`, `<product>-<product>`
+/// - Product: `<power>*<power>`, `<power>/<power>`
+/// - Power: `<postfix>^<power>`
+/// - Postfix: `<unary>c`, `<unary>\<unary>`, `<unary>\<unary><<expr>>`
+/// - Unary: `-<unary>`
+/// - Atom: number, constant, `(<expr>)`
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Spanned<'a>>,
+    index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse_expression(&mut self) -> Result<Parsed, String> {
+        let numer = self.parse_sum()?;
+        if !self.eat(':') {
+            return Ok(numer);
+        }
+        let denom = self.parse_sum()?;
+        self.expect(':')?;
+        let interval = self.parse_sum()?;
+        Ok(Parsed {
+            variant: RatioExpressionVariant::IntervalFraction {
+                numer: self.operand(&numer, "interval numerator")?,
+                denom: self.operand(&denom, "interval denominator")?,
+                interval: self.operand(&interval, "interval")?,
+            },
+            start: numer.start,
+            end: interval.end,
         })
-    } else if let [numer, denom] = parse::split_balanced(&s, '/').as_slice() {
-        Ok(RatioExpressionVariant::Fraction {
-            numer: parse_ratio_as_float(numer, "numerator")?,
-            denom: parse_ratio_as_float(denom, "denominator")?,
+    }
+
+    fn parse_sum(&mut self) -> Result<Parsed, String> {
+        let mut sum = self.parse_product()?;
+        loop {
+            let (start, end, token) = self.tokens[self.index];
+            let sign = match token {
+                Token::Symbol('+') => 1.0,
+                Token::Symbol('-') => -1.0,
+                _ => return Ok(sum),
+            };
+            self.index += 1;
+            let summand = self.parse_product()?;
+            let left = self.operand(&sum, "summand")?;
+            let right = self.operand(&summand, "summand")?;
+            let variant = match (sum.variant, summand.variant) {
+                (
+                    RatioExpressionVariant::Cents { cents_value: left },
+                    RatioExpressionVariant::Cents { cents_value: right },
+                ) => RatioExpressionVariant::Cents {
+                    cents_value: left + sign * right,
+                },
+                (RatioExpressionVariant::Cents { .. }, _)
+                | (_, RatioExpressionVariant::Cents { .. }) => {
+                    return Err(unexpected(
+                        self.input,
+                        start,
+                        end,
+                        "Cents values can only be added to cents values (e.g. 700c+2c)",
+                    ))
+                }
+                _ => RatioExpressionVariant::Float {
+                    float_value: left + sign * right,
+                },
+            };
+            sum = Parsed {
+                variant,
+                start: sum.start,
+                end: summand.end,
+            };
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Parsed, String> {
+        let mut product = self.parse_power()?;
+        let mut num_operators = 0;
+        loop {
+            let is_quotient = match self.peek() {
+                Token::Symbol('*') => false,
+                Token::Symbol('/') => true,
+                _ => return Ok(product),
+            };
+            self.index += 1;
+            num_operators += 1;
+            let factor = self.parse_power()?;
+            let variant = if is_quotient && num_operators == 1 {
+                RatioExpressionVariant::Fraction {
+                    numer: self.operand(&product, "numerator")?,
+                    denom: self.operand(&factor, "denominator")?,
+                }
+            } else {
+                let (left_numer, left_denom) = self.factor(&product)?;
+                let (right_numer, right_denom) = self.factor(&factor)?;
+                if is_quotient {
+                    RatioExpressionVariant::Product {
+                        numer: left_numer * right_denom,
+                        denom: left_denom * right_numer,
+                    }
+                } else {
+                    RatioExpressionVariant::Product {
+                        numer: left_numer * right_numer,
+                        denom: left_denom * right_denom,
+                    }
+                }
+            };
+            product = Parsed {
+                variant,
+                start: product.start,
+                end: factor.end,
+            };
+        }
+    }
+
+    /// Splits a factor of a product into its numerator and denominator.
+    fn factor(&self, factor: &Parsed) -> Result<(f64, f64), String> {
+        match factor.variant {
+            RatioExpressionVariant::Fraction { numer, denom }
+            | RatioExpressionVariant::Product { numer, denom } => {
+                self.operand(factor, "factor")?;
+                Ok((numer, denom))
+            }
+            _ => Ok((self.operand(factor, "factor")?, 1.0)),
+        }
+    }
+
+    fn parse_power(&mut self) -> Result<Parsed, String> {
+        let base = self.parse_postfix()?;
+        if !self.eat('^') {
+            return Ok(base);
+        }
+        let exponent = self.parse_power()?;
+        Ok(Parsed {
+            variant: RatioExpressionVariant::Power {
+                base: self.operand(&base, "base")?,
+                exponent: self.operand(&exponent, "exponent")?,
+            },
+            start: base.start,
+            end: exponent.end,
+        })
+    }
+
+    fn parse_postfix(&mut self) -> Result<Parsed, String> {
+        let operand = self.parse_unary()?;
+        let (_, end, token) = self.tokens[self.index];
+        match token {
+            Token::Word("c") => {
+                self.index += 1;
+                Ok(Parsed {
+                    variant: RatioExpressionVariant::Cents {
+                        cents_value: self.operand(&operand, "cents value")?,
+                    },
+                    start: operand.start,
+                    end,
+                })
+            }
+            Token::Symbol('\\') => {
+                self.index += 1;
+                let num_steps_per_interval = self.parse_unary()?;
+                let (interval, end) = if self.eat('<') {
+                    let interval = self.parse_expression()?;
+                    let end = self.expect('>')?;
+                    (self.operand(&interval, "interval")?, end)
+                } else {
+                    (2.0, num_steps_per_interval.end)
+                };
+                Ok(Parsed {
+                    variant: RatioExpressionVariant::EdoSteps {
+                        num_steps: self.operand(&operand, "number of steps")?,
+                        num_steps_per_interval: self
+                            .operand(&num_steps_per_interval, "number of steps per interval")?,
+                        interval,
+                    },
+                    start: operand.start,
+                    end,
+                })
+            }
+            _ => Ok(operand),
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Parsed, String> {
+        let start = self.tokens[self.index].0;
+        if !self.eat('-') {
+            return self.parse_atom();
+        }
+        let operand = self.parse_unary()?;
+        Ok(Parsed {
+            variant: RatioExpressionVariant::Float {
+                float_value: -self.operand(&operand, "operand")?,
+            },
+            start,
+            end: operand.end,
         })
-    } else if let [cents_value, ""] = parse::split_balanced(&s, 'c').as_slice() {
-        Ok(RatioExpressionVariant::Cents {
-            cents_value: parse_ratio_as_float(cents_value, "cents value")?,
+    }
+
+    fn parse_atom(&mut self) -> Result<Parsed, String> {
+        let (start, end, token) = self.tokens[self.index];
+        let variant = match token {
+            Token::Number(float_value) => RatioExpressionVariant::Float { float_value },
+            Token::Word("octave") => RatioExpressionVariant::Constant {
+                name: "octave",
+                float_value: 2.0,
+            },
+            Token::Word("tritave") => RatioExpressionVariant::Constant {
+                name: "tritave",
+                float_value: 3.0,
+            },
+            Token::Symbol('(') => {
+                self.index += 1;
+                let inner = self.parse_expression()?;
+                let end = self.expect(')')?;
+                return Ok(Parsed {
+                    variant: inner.variant,
+                    start,
+                    end,
+                });
+            }
+            _ => return Err(self.unexpected(EXPECTED_OPERAND)),
+        };
+        self.index += 1;
+        Ok(Parsed {
+            variant,
+            start,
+            end,
         })
-    } else if s.starts_with('(') && s.ends_with(')') {
-        parse_ratio(&s[1..s.len() - 1])
-    } else {
-        Ok(RatioExpressionVariant::Float {
-            float_value: s.parse().map_err(|_| {
-                "Must be a float (e.g. 1.5), fraction (e.g. 3/2), \
-                 interval fraction (e.g. 7:12:2) or cents value (e.g. 702c)"
-                    .to_string()
-            })?,
+    }
+
+    fn operand(&self, parsed: &Parsed, name: &str) -> Result<f64, String> {
+        parsed.variant.as_float().map_err(|e| {
+            format!(
+                "Invalid {} '{}': {}",
+                name,
+                &self.input[parsed.start..parsed.end],
+                e
+            )
         })
     }
-}
 
-fn parse_ratio_as_float(s: &str, name: &str) -> Result<f64, String> {
-    parse_ratio(s)
-        .and_then(RatioExpressionVariant::as_float)
-        .map_err(|e| format!("Invalid {} '{}': {}", name, s, e))
+    fn peek(&self) -> Token<'a> {
+        self.tokens[self.index].2
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let matches = self.peek() == Token::Symbol(symbol);
+        if matches {
+            self.index += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<usize, String> {
+        let end = self.tokens[self.index].1;
+        if self.eat(symbol) {
+            Ok(end)
+        } else {
+            Err(self.unexpected(&format!("Must be '{}'", symbol)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        let (start, end, _) = self.tokens[self.index];
+        unexpected(self.input, start, end, expected)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -508,12 +1129,18 @@ impl Display for NearestFraction {
 #[cfg(test)]
 mod test {
     use super::*;
+    use assert_approx_eq::assert_approx_eq;
 
     #[test]
     fn parses_successfully() {
         let test_cases = [
             ("1", 1.0000),
             ("99.9", 99.9000),
+            ("1e3", 1000.0000),
+            ("1.5E-1", 0.1500),
+            ("2.5e+1", 25.0000),
+            ("1e3/1e2", 10.0000),
+            ("(1e2)c", 1.0595),
             ("(1.25)", 1.2500),
             ("(1.25)", 1.2500),
             ("10/3", 3.3333),
@@ -540,6 +1167,28 @@ mod test {
             ("702c/3", 0.5000),    // 2^(702/1200)/3 - 702 cents divided by 3
             ("3/702c", 2.0000),    // 3/2^(702/1200) - 3 divided by 702 cents
             ("(1404/2)c", 1.5000), // 2^(702/1200) - 1402/2 cents
+            ("3/2*5/4", 1.8750),
+            ("3/2*5/4/2", 0.9375),
+            ("3/2^2", 0.7500),
+            ("(3/2)^4", 5.0625),
+            ("2^1/2", 1.0000),
+            ("2^-1", 0.5000),
+            ("2^3^2", 512.0000), // 2^(3^2) - right-associative
+            ("700c+2c", 1.5000),
+            ("1200c-498c", 1.5000),
+            ("(700c+2c)/3", 0.5000),
+            ("(700+2)c", 1.5000),
+            ("1+1/2", 1.5000),
+            ("7\\12", 1.4983),
+            ("12\\12", 2.0000),
+            ("-7\\12", 0.6674),
+            ("13\\13<3>", 3.0000),
+            ("13\\19<3>", 2.1206),
+            ("1\\2<3/2*2>", 1.7320),
+            ("octave", 2.0000),
+            ("tritave", 3.0000),
+            ("tritave/octave", 1.5000),
+            ("1:12:octave", 1.0595),
         ];
 
         for (input, expected) in test_cases.iter() {
@@ -572,13 +1221,54 @@ mod test {
             ),
             (
                 "(1/x)c",
-                "Invalid expression '(1/x)c': Invalid cents value '(1/x)': Invalid denominator 'x': \
-                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2) or cents value (e.g. 702c)",
+                "Invalid expression '(1/x)c': Unexpected 'x' at position 4: \
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), \
+                 cents value (e.g. 702c), EDO step (e.g. 7\\12) or constant (e.g. octave)",
             ),
             (
                 "   (1   /x )c ",
-                "Invalid expression '(1   /x )c': Invalid cents value '(1   /x )': Invalid denominator 'x': \
-                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2) or cents value (e.g. 702c)",
+                "Invalid expression '(1   /x )c': Unexpected 'x' at position 7: \
+                 Must be a float (e.g. 1.5), fraction (e.g. 3/2), interval fraction (e.g. 7:12:2), \
+                 cents value (e.g. 702c), EDO step (e.g. 7\\12) or constant (e.g. octave)",
+            ),
+            (
+                "3/2 5/4",
+                "Invalid expression '3/2 5/4': Unexpected '5' at position 5: \
+                 Must be an operator (+, -, *, /, ^, :, \\) or the end of the expression",
+            ),
+            (
+                "(3/2",
+                "Invalid expression '(3/2': Unexpected end of expression at position 5: Must be ')'",
+            ),
+            (
+                "7:12",
+                "Invalid expression '7:12': Unexpected end of expression at position 5: Must be ':'",
+            ),
+            (
+                "7\\12<3",
+                "Invalid expression '7\\12<3': Unexpected end of expression at position 7: Must be '>'",
+            ),
+            (
+                "700c+2",
+                "Invalid expression '700c+2': Unexpected '+' at position 5: \
+                 Cents values can only be added to cents values (e.g. 700c+2c)",
+            ),
+            (
+                "1.2.3",
+                "Invalid expression '1.2.3': Unexpected '1.2.3' at position 1: Must be a valid number",
+            ),
+            (
+                "1e",
+                "Invalid expression '1e': Unexpected 'e' at position 2: \
+                 Must be an operator (+, -, *, /, ^, :, \\) or the end of the expression",
+            ),
+            (
+                "7\\0",
+                "Invalid expression '7\\0': Evaluates to inf",
+            ),
+            (
+                "(7\\0)^2",
+                "Invalid expression '(7\\0)^2': Invalid base '(7\\0)': Evaluates to inf",
             ),
         ];
 
@@ -610,6 +1300,41 @@ mod test {
             "(0/3)c".parse::<RatioExpression>().unwrap().variant(),
             RatioExpressionVariant::Cents { .. }
         ));
+        assert!(matches!(
+            "(3/2)*(5/4)".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Product { numer, denom } if numer == 15.0 && denom == 8.0
+        ));
+        assert!(matches!(
+            "3/2*5/4".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Product { numer, denom } if numer == 15.0 && denom == 8.0
+        ));
+        assert!(matches!(
+            "3*5/2".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Product { numer, denom } if numer == 15.0 && denom == 2.0
+        ));
+        assert!(matches!(
+            "9/4/(3/2)".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Product { numer, denom } if numer == 18.0 && denom == 12.0
+        ));
+        assert!(matches!(
+            "(3/2)^4".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Power { .. }
+        ));
+        assert!(matches!(
+            "700c+2c".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Cents { .. }
+        ));
+        assert!(matches!(
+            "13\\19<3>".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::EdoSteps { .. }
+        ));
+        assert!(matches!(
+            "tritave".parse::<RatioExpression>().unwrap().variant(),
+            RatioExpressionVariant::Constant {
+                name: "tritave",
+                ..
+            }
+        ));
     }
 
     #[test]
@@ -638,6 +1363,79 @@ mod test {
         }
     }
 
+    #[test]
+    fn best_approximations_are_consistent_with_nearest_fraction() {
+        for semitones in -24..=24 {
+            for &offset in [0.0, 0.1, 0.25, 0.5].iter() {
+                let ratio = Ratio::from_semitones(f64::from(semitones) + offset);
+                let expected = ratio.nearest_fraction(11);
+                let actual = ratio
+                    .nearest_fraction_with(&FractionConstraints::odd_limit(11))
+                    .unwrap();
+                assert_eq!(
+                    (actual.numer, actual.denom, actual.num_octaves),
+                    (expected.numer, expected.denom, expected.num_octaves)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn best_approximations_with_prime_limit() {
+        let constraints = FractionConstraints::odd_limit(255).with_prime_limit(13);
+        for approximation in Ratio::from_cents(551.3).best_approximations(&constraints) {
+            assert!(math::largest_prime_factor_u16(approximation.numer) <= 13);
+            assert!(math::largest_prime_factor_u16(approximation.denom) <= 13);
+        }
+        let f = Ratio::from_cents(551.3)
+            .nearest_fraction_with(&constraints)
+            .unwrap();
+        assert_eq!((f.numer, f.denom), (11, 8));
+
+        let tenney_limited = FractionConstraints::odd_limit(255).with_max_tenney_height(3.0);
+        let f = Ratio::from_cents(551.3)
+            .nearest_fraction_with(&tenney_limited)
+            .unwrap();
+        assert_eq!((f.numer, f.denom), (3, 2));
+    }
+
+    #[test]
+    fn nearest_fraction_with_prime_limit_matches_brute_force() {
+        let test_cases = [
+            (400.0, 3, (81, 64)),
+            (300.0, 5, (32, 27)),
+            (350.0, 13, (175, 143)),
+        ];
+        for &(cents, prime_limit, expected) in test_cases.iter() {
+            let constraints = FractionConstraints::odd_limit(255).with_prime_limit(prime_limit);
+            let f = Ratio::from_cents(cents)
+                .nearest_fraction_with(&constraints)
+                .unwrap();
+            assert_eq!((f.numer, f.denom), expected);
+        }
+
+        for &prime_limit in [3, 5, 7, 13].iter() {
+            let constraints = FractionConstraints::odd_limit(255).with_prime_limit(prime_limit);
+            for cents in (0..24).map(|index| f64::from(index) * 50.0 + 10.0) {
+                let ratio = Ratio::from_cents(cents);
+                let mut expected_error = f64::INFINITY;
+                for denom in 1..=255 {
+                    for numer in denom..=2 * denom {
+                        if math::gcd_u16(numer, denom) == 1 && constraints.admits(numer, denom) {
+                            let error = (ratio.as_cents()
+                                - Ratio::from_float(f64::from(numer) / f64::from(denom))
+                                    .as_cents())
+                            .abs();
+                            expected_error = expected_error.min(error);
+                        }
+                    }
+                }
+                let f = ratio.nearest_fraction_with(&constraints).unwrap();
+                assert_approx_eq!(f.deviation.as_cents().abs(), expected_error);
+            }
+        }
+    }
+
     #[test]
     fn render_ratio_of_irrational_numbers() {
         let test_cases = [
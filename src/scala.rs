@@ -4,8 +4,9 @@ use crate::math;
 use crate::pitch::{Pitch, ReferencePitch};
 use crate::{
     key::PianoKey,
-    note::PitchedNote,
+    note::{Note, NoteLetter, PitchedNote},
     ratio::Ratio,
+    temperament::EqualTemperament,
     tuning::{Approximation, Scale, Tuning},
 };
 use io::{BufReader, Read};
@@ -14,6 +15,7 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 use std::{
     borrow::Borrow,
+    convert::TryFrom,
     io::{self, BufRead},
     ops::Neg,
 };
@@ -49,6 +51,7 @@ pub struct Scl {
     period: Ratio,
     pitch_values: Vec<PitchValue>,
     pitch_value_ordering: Vec<usize>,
+    format: Option<Box<SclFormat>>,
 }
 
 impl Scl {
@@ -141,6 +144,58 @@ impl Scl {
             .stretched_by(ratio_within_period)
     }
 
+    /// Retrieves the exact fraction of a degree if the degree and the period are given as fractions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_fraction(5, 4)
+    ///     .push_cents(700.0)
+    ///     .push_int(2)
+    ///     .build().unwrap();
+    ///
+    /// assert_eq!(scl.fraction_of(0), Some((1, 1)));
+    /// assert_eq!(scl.fraction_of(1), Some((5, 4)));
+    /// assert_eq!(scl.fraction_of(2), None);
+    /// assert_eq!(scl.fraction_of(3), Some((2, 1)));
+    /// assert_eq!(scl.fraction_of(4), Some((5, 2)));
+    /// assert_eq!(scl.fraction_of(-2), Some((5, 8)));
+    ///
+    /// // A period given in cents keeps the fractions of the first period
+    /// let scl_with_cents_period = Scl::builder()
+    ///     .push_fraction(5, 4)
+    ///     .push_cents(700.0)
+    ///     .push_cents(1200.0)
+    ///     .build().unwrap();
+    ///
+    /// assert_eq!(scl_with_cents_period.fraction_of(1), Some((5, 4)));
+    /// assert_eq!(scl_with_cents_period.fraction_of(4), None);
+    /// ```
+    pub fn fraction_of(&self, degree: i32) -> Option<(u64, u64)> {
+        let (num_periods, degree_within_period) = math::i32_dr_u32(degree, self.size() as u32);
+        let (numer, denom) = if degree_within_period == 0 {
+            (1, 1)
+        } else {
+            self.pitch_values[(degree_within_period - 1) as usize].as_fraction()?
+        };
+        if num_periods == 0 {
+            return Some((numer, denom));
+        }
+        let (period_numer, period_denom) = self.pitch_values.last()?.as_fraction()?;
+        let (period_numer, period_denom) = if num_periods < 0 {
+            (period_denom, period_numer)
+        } else {
+            (period_numer, period_denom)
+        };
+        let exponent = num_periods.unsigned_abs();
+        let numer = period_numer.checked_pow(exponent)?.checked_mul(numer)?;
+        let denom = period_denom.checked_pow(exponent)?.checked_mul(denom)?;
+        let gcd = math::gcd_u64(numer, denom);
+        Some((numer / gcd, denom / gcd))
+    }
+
     /// Finds the approximate degree of a relative pitch without requiring any [`Kbm`] reference.
     ///
     /// # Examples
@@ -267,6 +322,505 @@ impl Scl {
         }
     }
 
+    /// Rotates the scale to the mode starting at the given (sorted) degree.
+    ///
+    /// Fractions are preserved where possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     major.mode(5).unwrap().export().to_string().lines().skip(2).collect::<Vec<_>>(),
+    ///     ["9/8", "6/5", "27/20", "3/2", "8/5", "9/5", "2"]
+    /// );
+    /// ```
+    pub fn mode(&self, degree: i32) -> Result<Scl, SclBuildError> {
+        let root = self.sorted_pitch_value_of(degree);
+        self.transformed(
+            format!("{} (mode {})", self.description, degree),
+            (1..=self.size() as i32).map(|offset| {
+                self.sorted_pitch_value_of(degree + offset)
+                    .deviation_from(root)
+            }),
+        )
+    }
+
+    /// Mirrors the scale s.t. the steps appear in reverse order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(3, 2)
+    ///     .push_int(2)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     scl.inverted().unwrap().export().to_string().lines().skip(2).collect::<Vec<_>>(),
+    ///     ["4/3", "8/5", "2"]
+    /// );
+    /// ```
+    pub fn inverted(&self) -> Result<Scl, SclBuildError> {
+        let period = self.sorted_pitch_value_of(self.size() as i32);
+        self.transformed(
+            format!("{} (inverted)", self.description),
+            (1..=self.size() as i32).map(|degree| {
+                period.deviation_from(self.sorted_pitch_value_of(self.size() as i32 - degree))
+            }),
+        )
+    }
+
+    /// Creates a scale from the given (sorted) degrees. The last degree becomes the new period.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::ratio::Ratio;
+    /// # use tune::scala::Scl;
+    /// let edo_12 = Scl::builder().push_cents(100.0).build().unwrap();
+    /// let major = edo_12.subset(&[2, 4, 5, 7, 9, 11, 12]).unwrap();
+    ///
+    /// assert_eq!(major.size(), 7);
+    /// assert_approx_eq!(major.relative_pitch_of(3).as_cents(), 500.0);
+    /// assert_approx_eq!(major.period().as_cents(), 1200.0);
+    /// ```
+    pub fn subset(&self, degrees: &[i32]) -> Result<Scl, SclBuildError> {
+        self.transformed(
+            format!("{} (subset)", self.description),
+            degrees
+                .iter()
+                .map(|&degree| self.sorted_pitch_value_of(degree)),
+        )
+    }
+
+    /// Merges the pitches of `other` into the period of `self`. Duplicate pitches are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let fifths = Scl::builder().push_fraction(3, 2).push_int(2).build().unwrap();
+    /// let thirds = Scl::builder().push_fraction(5, 4).push_fraction(3, 2).push_int(2).build().unwrap();
+    ///
+    /// assert_eq!(
+    ///     fifths.merged(&thirds).unwrap().export().to_string().lines().skip(2).collect::<Vec<_>>(),
+    ///     ["5/4", "3/2", "2"]
+    /// );
+    /// ```
+    pub fn merged(&self, other: &Scl) -> Result<Scl, SclBuildError> {
+        let period = self.sorted_pitch_value_of(self.size() as i32);
+        let mut pitch_values = (0..self.size() as i32)
+            .map(|degree| self.sorted_pitch_value_of(degree))
+            .collect::<Vec<_>>();
+
+        // The builder only accepts ascending periods, s.t. `other` covers `period` after a bounded number of its own periods.
+        let num_periods_of_other = (period.as_ratio().as_octaves() / other.period().as_octaves())
+            .ceil()
+            .max(1.0) as usize;
+        let lowest_degree_of_other = other
+            .find_by_relative_pitch_sorted(Ratio::default())
+            .approx_value;
+        pitch_values.extend(
+            (lowest_degree_of_other..)
+                .take(other.size() * (num_periods_of_other + 1))
+                .map(|degree| other.sorted_pitch_value_of(degree))
+                .take_while(|pitch_value| pitch_value.as_ratio() < period.as_ratio()),
+        );
+
+        pitch_values.sort_by(|a, b| {
+            a.as_ratio()
+                .partial_cmp(&b.as_ratio())
+                .expect("Comparison failed")
+        });
+        pitch_values.dedup_by(|a, b| a.as_ratio().deviation_from(b.as_ratio()).as_cents() < 1e-6);
+        pitch_values.push(period);
+
+        self.transformed(
+            format!("{} (merged with {})", self.description, other.description),
+            pitch_values
+                .into_iter()
+                .filter(|pitch_value| pitch_value.as_ratio().as_cents() > 1e-6),
+        )
+    }
+
+    /// Stretches or compresses the scale s.t. its period becomes `new_period`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::ratio::Ratio;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder().push_cents(400.0).push_cents(1200.0).build().unwrap();
+    /// let stretched = scl.with_period(Ratio::from_cents(1212.0)).unwrap();
+    ///
+    /// assert_approx_eq!(stretched.relative_pitch_of(1).as_cents(), 404.0);
+    /// assert_approx_eq!(stretched.relative_pitch_of(2).as_cents(), 1212.0);
+    /// ```
+    pub fn with_period(&self, new_period: Ratio) -> Result<Scl, SclBuildError> {
+        let stretch_factor = new_period.as_octaves() / self.period.as_octaves();
+        self.transformed(
+            format!("{} (period {:#})", self.description, new_period),
+            self.pitch_values.iter().map(|pitch_value| {
+                PitchValue::Cents(pitch_value.as_ratio().as_cents() * stretch_factor)
+            }),
+        )
+        .map(|scl| self.with_format_transferred_to(scl))
+    }
+
+    /// Tempers all pitches to the nearest steps of the given EDO.
+    ///
+    /// The returned [`Approximation`]s contain the number of EDO steps and the deviation of the original pitch from it for each degree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder().push_fraction(5, 4).push_fraction(3, 2).push_int(2).build().unwrap();
+    /// let (tempered, approximations) = scl.tempered_to_edo(12).unwrap();
+    ///
+    /// assert_approx_eq!(tempered.relative_pitch_of(1).as_cents(), 400.0);
+    /// assert_eq!(approximations[0].approx_value, 4);
+    /// assert_approx_eq!(approximations[0].deviation.as_cents(), -13.686286);
+    /// assert_eq!(approximations[2].approx_value, 12);
+    /// ```
+    pub fn tempered_to_edo(
+        &self,
+        num_steps_per_octave: u16,
+    ) -> Result<(Scl, Vec<Approximation<i32>>), SclBuildError> {
+        if num_steps_per_octave == 0 {
+            return Err(SclBuildError::NoEdoSteps);
+        }
+        let step_size = Ratio::octave().divided_into_equal_steps(num_steps_per_octave);
+        let approximations = self
+            .pitch_values
+            .iter()
+            .map(|pitch_value| {
+                let ratio = pitch_value.as_ratio();
+                let num_steps = ratio.num_equal_steps_of_size(step_size).round();
+                Approximation {
+                    approx_value: num_steps as i32,
+                    deviation: ratio.deviation_from(step_size.repeated(num_steps)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let tempered = self.transformed(
+            format!("{} ({}-EDO)", self.description, num_steps_per_octave),
+            approximations.iter().map(|approximation| {
+                PitchValue::Cents(step_size.repeated(approximation.approx_value).as_cents())
+            }),
+        )?;
+        Ok((self.with_format_transferred_to(tempered), approximations))
+    }
+
+    /// Replaces the steps of the scale with equal steps of the same period.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder().push_fraction(9, 8).push_fraction(5, 4).push_int(2).build().unwrap();
+    /// let equalized = scl.equalized().unwrap();
+    ///
+    /// assert_approx_eq!(equalized.relative_pitch_of(1).as_cents(), 400.0);
+    /// assert_approx_eq!(equalized.relative_pitch_of(2).as_cents(), 800.0);
+    /// assert_approx_eq!(equalized.relative_pitch_of(3).as_cents(), 1200.0);
+    /// ```
+    pub fn equalized(&self) -> Result<Scl, SclBuildError> {
+        let step_size = self.period.divided_into_equal_steps(self.size() as f64);
+        let period = *self.pitch_values.last().expect("Scale is never empty");
+        self.transformed(
+            format!("{} (equalized)", self.description),
+            (1..self.size())
+                .map(|degree| PitchValue::Cents(step_size.repeated(degree as f64).as_cents()))
+                .chain(std::iter::once(period)),
+        )
+        .map(|scl| self.with_format_transferred_to(scl))
+    }
+
+    /// Returns the largest prime factor of all fractions of the scale or [`None`] if the scale contains cents values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let septimal = Scl::builder().push_fraction(7, 6).push_fraction(3, 2).push_int(2).build().unwrap();
+    /// let mixed = Scl::builder().push_fraction(5, 4).push_cents(700.0).push_int(2).build().unwrap();
+    ///
+    /// assert_eq!(septimal.prime_limit(), Some(7));
+    /// assert_eq!(mixed.prime_limit(), None);
+    /// ```
+    pub fn prime_limit(&self) -> Option<u32> {
+        self.pitch_values
+            .iter()
+            .map(|pitch_value| {
+                pitch_value.as_fraction().map(|(numer, denom)| {
+                    math::prime_factors_u64(numer)
+                        .into_iter()
+                        .chain(math::prime_factors_u64(denom))
+                        .map(|(prime, _)| prime as u32)
+                        .max()
+                        .unwrap_or(1)
+                })
+            })
+            .try_fold(1, |prime_limit, item_limit| {
+                item_limit.map(|item_limit| prime_limit.max(item_limit))
+            })
+    }
+
+    /// Checks whether the scale is a moment of symmetry, i.e. it has exactly two step sizes and every interval spanning `k` steps comes in at most two sizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ratio::Ratio;
+    /// # use tune::scala::{self, Scl};
+    /// let tolerance = Ratio::from_cents(0.01);
+    ///
+    /// let diatonic = scala::create_rank2_temperament_scale(None, Ratio::from_float(1.5), 5, 1, Ratio::octave()).unwrap();
+    /// assert!(diatonic.is_mos(tolerance));
+    ///
+    /// let harmonic_minor = Scl::builder()
+    ///     .push_cents(200.0)
+    ///     .push_cents(300.0)
+    ///     .push_cents(500.0)
+    ///     .push_cents(700.0)
+    ///     .push_cents(800.0)
+    ///     .push_cents(1100.0)
+    ///     .push_cents(1200.0)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(!harmonic_minor.is_mos(tolerance));
+    ///
+    /// let edo_12 = Scl::builder().push_cents(100.0).build().unwrap();
+    /// assert!(!edo_12.is_mos(tolerance));
+    /// ```
+    pub fn is_mos(&self, tolerance: Ratio) -> bool {
+        let tolerance_in_cents = tolerance.as_cents().abs();
+        (1..self.size().max(2) as i32).all(|num_steps| {
+            let mut interval_sizes = Vec::<f64>::new();
+            for degree in 0..self.size() as i32 {
+                let interval_size = self
+                    .sorted_relative_pitch_of(degree + num_steps)
+                    .deviation_from(self.sorted_relative_pitch_of(degree))
+                    .as_cents();
+                if interval_sizes
+                    .iter()
+                    .all(|&size| (size - interval_size).abs() > tolerance_in_cents)
+                {
+                    interval_sizes.push(interval_size);
+                }
+            }
+            interval_sizes.len() == 2 || num_steps > 1 && interval_sizes.len() == 1
+        })
+    }
+
+    /// Checks whether both scales contain the same pitches within the given `tolerance`, regardless of their description or notation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ratio::Ratio;
+    /// # use tune::scala::Scl;
+    /// let just = Scl::builder().push_fraction(5, 4).push_fraction(3, 2).push_int(2).build().unwrap();
+    /// let cents = Scl::builder().push_cents(386.314).push_cents(701.955).push_cents(1200.0).build().unwrap();
+    ///
+    /// assert!(just.is_equivalent_to(&cents, Ratio::from_cents(0.01)));
+    /// assert!(!just.is_equivalent_to(&cents, Ratio::from_cents(0.0001)));
+    /// ```
+    pub fn is_equivalent_to(&self, other: &Scl, tolerance: Ratio) -> bool {
+        self.find_mode_offset(other, 0, tolerance)
+    }
+
+    /// Finds the (sorted) degree `k` s.t. `self` is equivalent to the mode of `other` starting at degree `k`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ratio::Ratio;
+    /// # use tune::scala::Scl;
+    /// let major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build()
+    ///     .unwrap();
+    /// let minor = major.mode(5).unwrap();
+    ///
+    /// assert_eq!(minor.find_mode_of(&major, Ratio::from_cents(0.01)), Some(5));
+    /// assert_eq!(major.find_mode_of(&minor, Ratio::from_cents(0.01)), Some(2));
+    /// assert!(major.is_mode_of(&major, Ratio::from_cents(0.01)));
+    /// ```
+    pub fn find_mode_of(&self, other: &Scl, tolerance: Ratio) -> Option<i32> {
+        (0..other.size() as i32).find(|&degree| self.find_mode_offset(other, degree, tolerance))
+    }
+
+    /// Checks whether `self` is a mode of `other`, see [`Scl::find_mode_of`].
+    pub fn is_mode_of(&self, other: &Scl, tolerance: Ratio) -> bool {
+        self.find_mode_of(other, tolerance).is_some()
+    }
+
+    fn find_mode_offset(&self, other: &Scl, degree: i32, tolerance: Ratio) -> bool {
+        if self.size() != other.size() {
+            return false;
+        }
+        let root = other.sorted_relative_pitch_of(degree);
+        (1..=self.size() as i32).all(|offset| {
+            let pitch_of_other = other
+                .sorted_relative_pitch_of(degree + offset)
+                .deviation_from(root);
+            self.sorted_relative_pitch_of(offset)
+                .deviation_from(pitch_of_other)
+                .as_cents()
+                .abs()
+                <= tolerance.as_cents().abs()
+        })
+    }
+
+    /// Checks whether all pitches of `self`, including its period, are pitches of `other` within the given `tolerance`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ratio::Ratio;
+    /// # use tune::scala::Scl;
+    /// let edo_12 = Scl::builder().push_cents(100.0).build().unwrap();
+    /// let major = edo_12.subset(&[2, 4, 5, 7, 9, 11, 12]).unwrap();
+    /// let just_major_third = Scl::builder().push_fraction(5, 4).push_int(2).build().unwrap();
+    ///
+    /// assert!(major.is_subset_of(&edo_12, Ratio::from_cents(0.01)));
+    /// assert!(!edo_12.is_subset_of(&major, Ratio::from_cents(0.01)));
+    /// assert!(!just_major_third.is_subset_of(&edo_12, Ratio::from_cents(10.0)));
+    /// assert!(just_major_third.is_subset_of(&edo_12, Ratio::from_cents(15.0)));
+    /// ```
+    pub fn is_subset_of(&self, other: &Scl, tolerance: Ratio) -> bool {
+        (1..=self.size() as i32).all(|degree| {
+            other
+                .find_by_relative_pitch(self.sorted_relative_pitch_of(degree))
+                .deviation
+                .as_cents()
+                .abs()
+                <= tolerance.as_cents().abs()
+        })
+    }
+
+    /// Returns a representation of the scale that is independent of its mode, description and notation.
+    ///
+    /// All pitches are rounded to a resolution of 0.001 cents. The canonical mode is the one whose step sequence is lexicographically smallest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build()
+    ///     .unwrap();
+    /// let minor_in_cents = Scl::builder()
+    ///     .push_cents(203.910)
+    ///     .push_cents(315.641)
+    ///     .push_cents(519.551)
+    ///     .push_cents(701.955)
+    ///     .push_cents(813.686)
+    ///     .push_cents(1017.596)
+    ///     .push_cents(1200.0)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(major.canonical_form(), minor_in_cents.canonical_form());
+    /// assert_eq!(major.fingerprint(), minor_in_cents.fingerprint());
+    /// assert_eq!(major.canonical_form().steps_in_cents().next(), Some(111.731));
+    /// ```
+    pub fn canonical_form(&self) -> SclCanonicalForm {
+        let quantized_pitches = (0..=self.size() as i32)
+            .map(|degree| {
+                (self.sorted_relative_pitch_of(degree).as_cents() * CANONICAL_RESOLUTION).round()
+                    as i64
+            })
+            .collect::<Vec<_>>();
+        let steps = quantized_pitches
+            .windows(2)
+            .map(|window| window[1] - window[0])
+            .collect::<Vec<_>>();
+
+        let steps = (0..steps.len())
+            .map(|mode| {
+                let mut rotated = steps.clone();
+                rotated.rotate_left(mode);
+                rotated
+            })
+            .min()
+            .unwrap_or(steps);
+
+        SclCanonicalForm { steps }
+    }
+
+    /// Returns a stable hash of the [`Scl::canonical_form`].
+    ///
+    /// Scales with the same fingerprint are modes of each other with a high probability.
+    pub fn fingerprint(&self) -> u64 {
+        self.canonical_form().fingerprint()
+    }
+
+    fn sorted_pitch_value_of(&self, degree: i32) -> PitchValue {
+        let (num_periods, degree_within_period) = math::i32_dr_u32(degree, self.size() as u32);
+        let pitch_value = if degree_within_period == 0 {
+            PitchValue::Fraction(1, None)
+        } else {
+            let index = self.pitch_value_ordering[(degree_within_period - 1) as usize];
+            self.pitch_values[index]
+        };
+        let period = *self.pitch_values.last().expect("Scale is never empty");
+        pitch_value.stretched_by(period.repeated(num_periods))
+    }
+
+    fn transformed(
+        &self,
+        description: String,
+        pitch_values: impl IntoIterator<Item = PitchValue>,
+    ) -> Result<Scl, SclBuildError> {
+        let mut builder = Scl::builder();
+        for pitch_value in pitch_values {
+            builder = builder.push_pitch_value(pitch_value);
+        }
+        builder.build_with_description(description)
+    }
+
+    /// Lets a degree-by-degree transformation keep the comments and layout of the original file.
+    fn with_format_transferred_to(&self, mut scl: Scl) -> Scl {
+        scl.format = self.format.clone();
+        scl
+    }
+
     pub fn import(reader: impl Read) -> Result<Self, SclImportError> {
         let mut importer = SclImporter::ExpectingDescription;
 
@@ -281,6 +835,75 @@ impl Scl {
         importer.finalize()
     }
 
+    /// Like [`Scl::import`] but retains comments, line endings and the original spelling of all lines.
+    ///
+    /// As long as a line's value is unchanged, [`Scl::export`] reproduces it byte-for-byte.
+    /// Changed lines are regenerated while the comments preceding them and any text following the pitch value are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let input = "! major.scl\r\n!\r\nJust major\r\n 3\r\n!\r\n 5/4   major third\r\n 701.955\r\n 2/1\r\n";
+    ///
+    /// let mut scl = Scl::import_lossless(input.as_bytes()).unwrap();
+    /// assert_eq!(scl.export().to_string(), input);
+    ///
+    /// scl.set_description("Just major triad");
+    /// assert_eq!(
+    ///     scl.export().to_string(),
+    ///     "! major.scl\r\n!\r\nJust major triad\r\n 3\r\n!\r\n 5/4   major third\r\n 701.955\r\n 2/1\r\n"
+    /// );
+    ///
+    /// let (tempered, _) = scl.tempered_to_edo(12).unwrap();
+    /// assert_eq!(
+    ///     tempered.export().to_string(),
+    ///     "! major.scl\r\n!\r\nJust major triad (12-EDO)\r\n 3\r\n!\r\n 400.000   major third\r\n 700.000\r\n 1200.000\r\n"
+    /// );
+    /// ```
+    pub fn import_lossless(reader: impl Read) -> Result<Self, SclImportError> {
+        let mut importer = SclImporter::ExpectingDescription;
+        let mut comments = String::new();
+        let mut lines = Vec::new();
+
+        let mut reader = BufReader::new(reader);
+        let mut line_number = 0;
+        loop {
+            let mut raw = String::new();
+            if reader.read_line(&mut raw)? == 0 {
+                break;
+            }
+            line_number += 1;
+            let trimmed = raw.trim();
+            if trimmed.starts_with('!') {
+                comments.push_str(&raw);
+            } else {
+                importer = importer.consume(line_number, trimmed)?;
+                lines.push(SclLine {
+                    comments: std::mem::take(&mut comments),
+                    raw,
+                });
+            }
+        }
+
+        let mut scl = importer.finalize()?;
+
+        let mut lines = lines.into_iter();
+        let description_line = lines.next().expect("Description is always present");
+        let num_notes_line = lines.next().expect("Number of notes is always present");
+        let pitch_lines = lines.zip(scl.pitch_values.iter().copied()).collect();
+        scl.format = Some(Box::new(SclFormat {
+            line_ending: line_ending_of(&description_line.raw),
+            original_description: scl.description.clone(),
+            description_line,
+            num_notes_line,
+            pitch_lines,
+            trailing_comments: comments,
+        }));
+
+        Ok(scl)
+    }
+
     pub fn export(&self) -> SclExport<'_> {
         SclExport(self)
     }
@@ -380,6 +1003,7 @@ pub enum SclParseErrorKind {
     CentsValue,
     Numer,
     Denom,
+    AblDirective,
 }
 
 #[derive(Clone, Debug)]
@@ -407,6 +1031,38 @@ impl From<SclBuildError> for SclImportError {
     }
 }
 
+const CANONICAL_RESOLUTION: f64 = 1000.0;
+
+/// A mode-independent representation of an [`Scl`], see [`Scl::canonical_form`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SclCanonicalForm {
+    steps: Vec<i64>,
+}
+
+impl SclCanonicalForm {
+    /// The step sizes of the canonical mode in cents.
+    pub fn steps_in_cents(&self) -> impl Iterator<Item = f64> + '_ {
+        self.steps
+            .iter()
+            .map(|&step| step as f64 / CANONICAL_RESOLUTION)
+    }
+
+    /// Calculates a 64-bit FNV-1a hash of the steps. The hash is stable across platforms and versions.
+    pub fn fingerprint(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+        let mut hash = FNV_OFFSET_BASIS;
+        for step in &self.steps {
+            for byte in step.to_le_bytes().iter() {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+        hash
+    }
+}
+
 pub struct SclBuilder {
     period: Ratio,
     pitch_values: Vec<PitchValue>,
@@ -480,6 +1136,7 @@ impl SclBuilder {
                 period: self.period,
                 pitch_values: self.pitch_values,
                 pitch_value_ordering,
+                format: None,
             })
         }
     }
@@ -492,6 +1149,12 @@ pub enum SclBuildError {
 
     /// The scale contains an item that is smaller than the default ratio (0 cents) or larger than the period.
     ItemOutOfRange,
+
+    /// The scale is tempered to an EDO without any steps.
+    NoEdoSteps,
+
+    /// The root of a chain of fifths lies outside the chain.
+    RootOutOfRange,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -501,6 +1164,16 @@ enum PitchValue {
 }
 
 impl PitchValue {
+    fn is_identical_to(self, other: PitchValue) -> bool {
+        match (self, other) {
+            (PitchValue::Cents(a), PitchValue::Cents(b)) => a.to_bits() == b.to_bits(),
+            (PitchValue::Fraction(numer_a, denom_a), PitchValue::Fraction(numer_b, denom_b)) => {
+                numer_a == numer_b && denom_a == denom_b
+            }
+            _ => false,
+        }
+    }
+
     fn as_ratio(self) -> Ratio {
         match self {
             PitchValue::Cents(cents_value) => Ratio::from_cents(cents_value),
@@ -509,6 +1182,72 @@ impl PitchValue {
             }
         }
     }
+
+    /// Unlike [`PitchValue::as_ratio`], this method does not overflow for pitch values far outside of the audible range.
+    fn as_cents(self) -> f64 {
+        match self {
+            PitchValue::Cents(cents_value) => cents_value,
+            PitchValue::Fraction(..) => self.as_ratio().as_cents(),
+        }
+    }
+
+    fn as_fraction(self) -> Option<(u64, u64)> {
+        match self {
+            PitchValue::Cents(_) => None,
+            PitchValue::Fraction(numer, denom) => {
+                Some((u64::from(numer), u64::from(denom.unwrap_or(1))))
+            }
+        }
+    }
+
+    fn from_fraction(numer: u64, denom: u64) -> Option<Self> {
+        let gcd = math::gcd_u64(numer, denom);
+        let (numer, denom) = (
+            u32::try_from(numer / gcd).ok()?,
+            u32::try_from(denom / gcd).ok()?,
+        );
+        Some(PitchValue::Fraction(
+            numer,
+            if denom == 1 { None } else { Some(denom) },
+        ))
+    }
+
+    /// Combines two pitch values, keeping the result a fraction if possible.
+    fn stretched_by(self, other: PitchValue) -> PitchValue {
+        self.as_fraction()
+            .zip(other.as_fraction())
+            .and_then(|((numer_a, denom_a), (numer_b, denom_b))| {
+                PitchValue::from_fraction(numer_a * numer_b, denom_a * denom_b)
+            })
+            .unwrap_or_else(|| PitchValue::Cents(self.as_cents() + other.as_cents()))
+    }
+
+    /// Repeats the pitch value `num_repetitions` times, keeping the result a fraction if possible.
+    fn repeated(self, num_repetitions: i32) -> PitchValue {
+        let exponent = num_repetitions.unsigned_abs();
+        self.as_fraction()
+            .and_then(|(numer, denom)| {
+                Some((numer.checked_pow(exponent)?, denom.checked_pow(exponent)?))
+            })
+            .and_then(|(numer, denom)| {
+                if num_repetitions >= 0 {
+                    PitchValue::from_fraction(numer, denom)
+                } else {
+                    PitchValue::from_fraction(denom, numer)
+                }
+            })
+            .unwrap_or_else(|| PitchValue::Cents(self.as_cents() * f64::from(num_repetitions)))
+    }
+
+    /// Calculates the interval between two pitch values, keeping the result a fraction if possible.
+    fn deviation_from(self, reference: PitchValue) -> PitchValue {
+        self.as_fraction()
+            .zip(reference.as_fraction())
+            .and_then(|((numer_a, denom_a), (numer_b, denom_b))| {
+                PitchValue::from_fraction(numer_a * denom_b, denom_a * numer_b)
+            })
+            .unwrap_or_else(|| PitchValue::Cents(self.as_cents() - reference.as_cents()))
+    }
 }
 
 impl Display for PitchValue {
@@ -525,12 +1264,116 @@ pub struct SclExport<'a>(&'a Scl);
 
 impl<'a> Display for SclExport<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        writeln!(f, "{}", self.0.description())?;
-        writeln!(f, "{}", self.0.pitch_values.len())?;
-        for pitch_value in &self.0.pitch_values {
-            writeln!(f, "{}", pitch_value)?;
+        let scl = self.0;
+        let format = match &scl.format {
+            Some(format) => format,
+            None => {
+                writeln!(f, "{}", scl.description())?;
+                writeln!(f, "{}", scl.pitch_values.len())?;
+                for pitch_value in &scl.pitch_values {
+                    writeln!(f, "{}", pitch_value)?;
+                }
+                return Ok(());
+            }
+        };
+
+        let mut writer = LineWriter {
+            f,
+            line_ending: format.line_ending,
+            is_at_line_start: true,
+        };
+
+        writer.write(&format.description_line.comments)?;
+        if scl.description == format.original_description {
+            writer.write(&format.description_line.raw)?;
+        } else {
+            writer.write_line(&scl.description)?;
         }
-        Ok(())
+
+        writer.write(&format.num_notes_line.comments)?;
+        if format.pitch_lines.len() == scl.size() {
+            writer.write(&format.num_notes_line.raw)?;
+        } else {
+            writer.write_line(&scl.size().to_string())?;
+        }
+
+        for (index, pitch_value) in scl.pitch_values.iter().enumerate() {
+            match format.pitch_lines.get(index) {
+                Some((line, original_value)) => {
+                    writer.write(&line.comments)?;
+                    if pitch_value.is_identical_to(*original_value) {
+                        writer.write(&line.raw)?;
+                    } else {
+                        writer.write(&line.with_main_item_replaced(&pitch_value.to_string()))?;
+                    }
+                }
+                None => writer.write_line(&pitch_value.to_string())?,
+            }
+        }
+
+        writer.write(&format.trailing_comments)
+    }
+}
+
+/// Original layout of an imported scl file, see [`Scl::import_lossless`].
+#[derive(Clone, Debug)]
+struct SclFormat {
+    line_ending: &'static str,
+    original_description: String,
+    description_line: SclLine,
+    num_notes_line: SclLine,
+    pitch_lines: Vec<(SclLine, PitchValue)>,
+    trailing_comments: String,
+}
+
+/// A non-comment line of an scl file together with the comment lines preceding it. All texts include their line endings.
+#[derive(Clone, Debug)]
+struct SclLine {
+    comments: String,
+    raw: String,
+}
+
+impl SclLine {
+    fn with_main_item_replaced(&self, main_item: &str) -> String {
+        let content = self.raw.trim_start();
+        let indentation = &self.raw[..self.raw.len() - content.len()];
+        let remainder = content
+            .find(|c: char| c.is_ascii_whitespace())
+            .map(|end_of_main_item| &content[end_of_main_item..])
+            .unwrap_or_default();
+        format!("{}{}{}", indentation, main_item, remainder)
+    }
+}
+
+fn line_ending_of(line: &str) -> &'static str {
+    if line.ends_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Writes raw text and makes sure that a new line is started after a final line without line ending.
+struct LineWriter<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    line_ending: &'static str,
+    is_at_line_start: bool,
+}
+
+impl LineWriter<'_, '_> {
+    fn write(&mut self, text: &str) -> fmt::Result {
+        if text.is_empty() {
+            return Ok(());
+        }
+        if !self.is_at_line_start {
+            self.f.write_str(self.line_ending)?;
+        }
+        self.is_at_line_start = text.ends_with('\n');
+        self.f.write_str(text)
+    }
+
+    fn write_line(&mut self, line: &str) -> fmt::Result {
+        self.write(&format!("{}{}", line, self.line_ending))
     }
 }
 
@@ -549,11 +1392,134 @@ impl Kbm {
         }
     }
 
+    /// Imports a kbm file. Only linear mappings, i.e. every key is mapped to the next degree, are supported.
+    ///
+    /// The range of retuned keys is not part of [`Kbm`] and, therefore, ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::scala::Kbm;
+    /// let input = "! Linear mapping\n0\n0\n127\n62\n69\n432.0\n0\n";
+    /// let kbm = Kbm::import(input.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(kbm.root_key.midi_number(), 62);
+    /// assert_eq!(kbm.ref_pitch.key().midi_number(), 69);
+    /// assert_approx_eq!(kbm.ref_pitch.pitch().as_hz(), 432.0);
+    ///
+    /// let non_linear = "2\n0\n127\n60\n69\n440.0\n12\n0\n7\n";
+    /// assert!(Kbm::import(non_linear.as_bytes()).is_err());
+    /// ```
+    pub fn import(reader: impl Read) -> Result<Self, KbmImportError> {
+        let mut items = Vec::new();
+        for (line_number, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let trimmed = line.trim();
+            if !trimmed.starts_with('!') && !trimmed.is_empty() {
+                let main_item = trimmed
+                    .split_ascii_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_owned();
+                items.push((line_number + 1, main_item));
+            }
+        }
+
+        let parse_int = |index: usize| -> Result<i32, KbmImportError> {
+            let (line_number, item) = items.get(index).ok_or(KbmImportError::StructuralError(
+                KbmStructuralError::FieldMissing,
+            ))?;
+            item.parse().map_err(|_| KbmImportError::ParseError {
+                line_number: *line_number,
+                kind: KbmParseErrorKind::IntValue,
+            })
+        };
+
+        let map_size = parse_int(0)?;
+        let root_key = PianoKey::from_midi_number(parse_int(3)?);
+        let ref_key = PianoKey::from_midi_number(parse_int(4)?);
+        let (line_number, ref_pitch_item) = items.get(5).ok_or(KbmImportError::StructuralError(
+            KbmStructuralError::FieldMissing,
+        ))?;
+        let ref_pitch_in_hz = ref_pitch_item
+            .parse()
+            .map_err(|_| KbmImportError::ParseError {
+                line_number: *line_number,
+                kind: KbmParseErrorKind::FloatValue,
+            })?;
+        let formal_octave = parse_int(6)?;
+
+        let mapping = (0..map_size.max(0) as usize)
+            .map(|index| match items.get(7 + index) {
+                Some((_, item)) if item == "x" => Ok(None),
+                Some(_) => parse_int(7 + index).map(Some),
+                None => Err(KbmImportError::StructuralError(
+                    KbmStructuralError::InconsistentMapSize,
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if items.len() > 7 + mapping.len() {
+            return Err(KbmStructuralError::InconsistentMapSize.into());
+        }
+
+        let is_linear = mapping.is_empty()
+            || formal_octave == map_size
+                && mapping
+                    .iter()
+                    .enumerate()
+                    .all(|(index, &degree)| degree == Some(index as i32));
+        if !is_linear {
+            return Err(KbmStructuralError::NonLinearMapping.into());
+        }
+
+        Ok(Kbm {
+            ref_pitch: ReferencePitch::from_key_and_pitch(ref_key, Pitch::from_hz(ref_pitch_in_hz)),
+            root_key,
+        })
+    }
+
     pub fn export(&self) -> KbmExport<'_> {
         KbmExport(self)
     }
 }
 
+#[derive(Debug)]
+pub enum KbmImportError {
+    IoError(io::Error),
+    ParseError {
+        line_number: usize,
+        kind: KbmParseErrorKind,
+    },
+    StructuralError(KbmStructuralError),
+}
+
+#[derive(Clone, Debug)]
+pub enum KbmParseErrorKind {
+    IntValue,
+    FloatValue,
+}
+
+#[derive(Clone, Debug)]
+pub enum KbmStructuralError {
+    FieldMissing,
+    InconsistentMapSize,
+    /// The mapping skips or reorders degrees, which cannot be represented by [`Kbm`].
+    NonLinearMapping,
+}
+
+impl From<io::Error> for KbmImportError {
+    fn from(v: io::Error) -> Self {
+        KbmImportError::IoError(v)
+    }
+}
+
+impl From<KbmStructuralError> for KbmImportError {
+    fn from(v: KbmStructuralError) -> Self {
+        KbmImportError::StructuralError(v)
+    }
+}
+
 pub struct KbmExport<'a>(&'a Kbm);
 
 impl<'a> Display for KbmExport<'a> {
@@ -570,6 +1536,252 @@ impl<'a> Display for KbmExport<'a> {
     }
 }
 
+/// Ableton's extended scl format adding `@ABL` directives to the comments of an scl file.
+///
+/// Octave numbers of the `REFERENCE_PITCH` directive follow the convention of this crate (C4 = MIDI note 60).
+/// The root key of the [`Kbm`] is degree 0 of the octave that contains the root key.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::note::NoteLetter;
+/// # use tune::scala::{Ascl, Kbm, Scl};
+/// let major = Scl::builder()
+///     .push_fraction(9, 8)
+///     .push_fraction(5, 4)
+///     .push_fraction(4, 3)
+///     .push_fraction(3, 2)
+///     .push_fraction(5, 3)
+///     .push_fraction(15, 8)
+///     .push_int(2)
+///     .build_with_description("Just major")
+///     .unwrap();
+/// let kbm = Kbm::root_at(NoteLetter::C.in_octave(4));
+///
+/// let ascl = Ascl::new(major, kbm).with_heptatonic_note_names().with_source("Textbook");
+/// let exported = ascl.export().to_string();
+/// assert_eq!(
+///     exported.lines().skip(9).collect::<Vec<_>>(),
+///     [
+///         "!",
+///         "! @ABL NOTE_NAMES C D E F G A B",
+///         "! @ABL REFERENCE_PITCH 4 0 261.6255653005986",
+///         "! @ABL SOURCE Textbook",
+///     ]
+/// );
+///
+/// let imported = Ascl::import(exported.as_bytes()).unwrap();
+/// assert_eq!(imported.note_names, ["C", "D", "E", "F", "G", "A", "B"]);
+/// assert_eq!(imported.kbm.root_key.midi_number(), 60);
+/// assert_eq!(imported.source.as_deref(), Some("Textbook"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Ascl {
+    pub scl: Scl,
+    pub kbm: Kbm,
+    /// Names of the degrees starting at the root. Empty if no names are given.
+    pub note_names: Vec<String>,
+    pub source: Option<String>,
+    pub link: Option<String>,
+    /// Unsupported `@ABL` directives (e.g. `NOTE_RANGE_BY_INDEX 1 0 9 0`), exported verbatim after the supported ones.
+    pub other_directives: Vec<String>,
+}
+
+impl Ascl {
+    pub fn new(scl: Scl, kbm: Kbm) -> Self {
+        Self {
+            scl,
+            kbm,
+            note_names: Vec::new(),
+            source: None,
+            link: None,
+            other_directives: Vec::new(),
+        }
+    }
+
+    pub fn with_note_names(mut self, note_names: Vec<String>) -> Self {
+        self.note_names = note_names;
+        self
+    }
+
+    /// Names the degrees after the notes of the EDO with the same number of steps as the scale, see [`EqualTemperament::get_heptatonic_name`].
+    ///
+    /// The names are aligned s.t. the root key receives the name closest to its 12-EDO note name.
+    pub fn with_heptatonic_note_names(mut self) -> Self {
+        let num_steps = self.scl.size();
+        let temperament = EqualTemperament::find().by_edo(num_steps as f64);
+        let semitones_above_d = (self.kbm.root_key.midi_number() - 62).rem_euclid(12);
+        let offset = (f64::from(semitones_above_d) * num_steps as f64 / 12.0).round() as i32;
+        self.note_names = (0..num_steps as i32)
+            .map(|degree| {
+                temperament.get_heptatonic_name((degree + offset).rem_euclid(num_steps as i32))
+            })
+            .collect();
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn with_link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    /// Imports an ascl file. Without a `REFERENCE_PITCH` directive the root is C4 at its usual frequency.
+    ///
+    /// The scale is imported using [`Scl::import_lossless`] s.t. unrelated comments survive a round-trip.
+    pub fn import(mut reader: impl Read) -> Result<Self, SclImportError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        let mut ascl = Ascl::new(
+            Scl::import_lossless(content.as_bytes())?,
+            Kbm::root_at(NoteLetter::C.in_octave(4)),
+        );
+
+        for (line_number, line) in content.lines().enumerate() {
+            if let Some(directive) = abl_directive_of(line) {
+                ascl.consume_abl_directive(directive)
+                    .ok_or(SclImportError::ParseError {
+                        line_number: line_number + 1,
+                        kind: SclParseErrorKind::AblDirective,
+                    })?;
+            }
+        }
+
+        Ok(ascl)
+    }
+
+    fn consume_abl_directive(&mut self, directive: &str) -> Option<()> {
+        let (keyword, args) = match directive.find(char::is_whitespace) {
+            Some(end_of_keyword) => (
+                &directive[..end_of_keyword],
+                directive[end_of_keyword..].trim(),
+            ),
+            None => (directive, ""),
+        };
+        match keyword {
+            "NOTE_NAMES" => self.note_names = split_note_names(args)?,
+            "REFERENCE_PITCH" => {
+                let mut args = args.split_ascii_whitespace();
+                let octave: i32 = args.next()?.parse().ok()?;
+                let degree: i32 = args.next()?.parse().ok()?;
+                let pitch_in_hz: f64 = args.next()?.parse().ok()?;
+                if !(pitch_in_hz.is_finite() && pitch_in_hz > 0.0) {
+                    return None;
+                }
+                let root_midi_number = octave.checked_add(1)?.checked_mul(12)?;
+                let root_key = PianoKey::from_midi_number(root_midi_number);
+                self.kbm = Kbm {
+                    ref_pitch: ReferencePitch::from_key_and_pitch(
+                        PianoKey::from_midi_number(root_midi_number.checked_add(degree)?),
+                        Pitch::from_hz(pitch_in_hz),
+                    ),
+                    root_key,
+                };
+            }
+            "SOURCE" => self.source = Some(args.to_owned()),
+            "LINK" => self.link = Some(args.to_owned()),
+            _ => self.other_directives.push(directive.to_owned()),
+        }
+        Some(())
+    }
+
+    pub fn export(&self) -> AsclExport<'_> {
+        AsclExport(self)
+    }
+}
+
+fn abl_directive_of(line: &str) -> Option<&str> {
+    let comment = line.trim().strip_prefix('!')?.trim_start();
+    comment.strip_prefix("@ABL").map(str::trim)
+}
+
+fn split_note_names(args: &str) -> Option<Vec<String>> {
+    let mut note_names = Vec::new();
+    let mut remainder = args.trim_start();
+    while !remainder.is_empty() {
+        let (note_name, rest) = match remainder.strip_prefix('"') {
+            Some(quoted) => {
+                let end_of_name = quoted.find('"')?;
+                (&quoted[..end_of_name], &quoted[end_of_name + 1..])
+            }
+            None => {
+                let end_of_name = remainder
+                    .find(char::is_whitespace)
+                    .unwrap_or(remainder.len());
+                (&remainder[..end_of_name], &remainder[end_of_name..])
+            }
+        };
+        note_names.push(note_name.to_owned());
+        remainder = rest.trim_start();
+    }
+    Some(note_names)
+}
+
+pub struct AsclExport<'a>(&'a Ascl);
+
+impl<'a> Display for AsclExport<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let ascl = self.0;
+
+        // Directives of a previously imported ascl file are replaced
+        let scl_export = ascl.scl.export().to_string();
+        let mut last_line = "";
+        for line in scl_export.split_inclusive('\n') {
+            if abl_directive_of(line).is_none() {
+                write!(f, "{}", line)?;
+                last_line = line;
+            }
+        }
+        if !last_line.ends_with('\n') {
+            writeln!(f)?;
+        }
+        if last_line.trim() != "!" {
+            writeln!(f, "!")?;
+        }
+        if !ascl.note_names.is_empty() {
+            write!(f, "! @ABL NOTE_NAMES")?;
+            for note_name in &ascl.note_names {
+                if note_name.contains(char::is_whitespace) {
+                    write!(f, " \"{}\"", note_name)?;
+                } else {
+                    write!(f, " {}", note_name)?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        let kbm = &ascl.kbm;
+        let (num_periods, degree) = math::i32_dr_u32(
+            kbm.root_key.num_keys_before(kbm.ref_pitch.key()),
+            ascl.scl.size() as u32,
+        );
+        let (_, root_octave) = Note::from_piano_key(kbm.root_key).letter_and_octave();
+        writeln!(
+            f,
+            "! @ABL REFERENCE_PITCH {} {} {}",
+            root_octave.octave_number() + num_periods,
+            degree,
+            kbm.ref_pitch.pitch().as_hz()
+        )?;
+
+        if let Some(source) = &ascl.source {
+            writeln!(f, "! @ABL SOURCE {}", source)?;
+        }
+        if let Some(link) = &ascl.link {
+            writeln!(f, "! @ABL LINK {}", link)?;
+        }
+        for directive in &ascl.other_directives {
+            writeln!(f, "! @ABL {}", directive)?;
+        }
+        Ok(())
+    }
+}
+
 impl<S: Borrow<Scl>, K: Borrow<Kbm>> Tuning<PianoKey> for (S, K) {
     fn pitch_of(&self, key: PianoKey) -> Pitch {
         let degree = self.1.borrow().root_key.num_keys_before(key);
@@ -866,6 +2078,76 @@ mod tests {
         .exports_lines(&["Custom scale", "5", "7/5", "9/5", "8/5", "6/5", "10/5"]);
     }
 
+    #[test]
+    fn merge_scales_with_different_periods() {
+        let octave = Scl::builder().push_int(2).build().unwrap();
+        let edo_5 = Scl::builder().push_cents(240.0).build().unwrap();
+        let tritave = Scl::builder().push_int(3).build().unwrap();
+
+        let merged = octave.merged(&edo_5).unwrap();
+        assert_eq!(merged.size(), 5);
+        assert_approx_eq!(merged.relative_pitch_of(4).as_cents(), 960.0);
+        assert_approx_eq!(merged.period().as_cents(), 1200.0);
+
+        let merged = octave.merged(&tritave).unwrap();
+        assert_eq!(merged.size(), 1);
+        assert_approx_eq!(merged.period().as_cents(), 1200.0);
+
+        let tiny_step = Scl::builder().push_cents(1e-3).build().unwrap();
+        let merged = Scl::builder()
+            .push_cents(1e-2)
+            .build()
+            .unwrap()
+            .merged(&tiny_step)
+            .unwrap();
+        assert_eq!(merged.size(), 10);
+    }
+
+    #[test]
+    fn mode_of_distant_degree() {
+        let major = Scl::builder()
+            .push_fraction(9, 8)
+            .push_fraction(5, 4)
+            .push_fraction(4, 3)
+            .push_fraction(3, 2)
+            .push_fraction(5, 3)
+            .push_fraction(15, 8)
+            .push_int(2)
+            .build()
+            .unwrap();
+        let aeolian = major.mode(5).unwrap();
+
+        assert_eq!(
+            major.mode(5 - 7 * 20).unwrap().export().to_string(),
+            aeolian.export().to_string().replace("mode 5", "mode -135")
+        );
+
+        for &degree in [5 + 7 * 100_000_000, 5 - 7 * 100_000_000].iter() {
+            let mode = major.mode(degree).unwrap();
+            for index in 0..=7 {
+                assert!(
+                    (mode.relative_pitch_of(index).as_cents()
+                        - aeolian.relative_pitch_of(index).as_cents())
+                    .abs()
+                        < 1e-3
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn temper_to_edo_without_steps() {
+        let scl = Scl::builder()
+            .push_fraction(3, 2)
+            .push_int(2)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            scl.tempered_to_edo(0),
+            Err(SclBuildError::NoEdoSteps)
+        ));
+    }
+
     #[test]
     fn build_scale_error_cases() {
         let item_greater_than_period = Scl::builder()
@@ -935,6 +2217,138 @@ mod tests {
         assert_approx_eq!(scl.relative_pitch_of(7).as_float(), 2.0);
     }
 
+    #[test]
+    fn import_scl_lossless() {
+        let input = "!A comment\n\
+            Test scale \n\
+            ! A second comment\n\
+            4\n\
+            100.\n\
+            \t150.0 ignore any text\n\
+            !175.0 ignore comment\n\
+            6/5 (ignore parentheses)\n\
+            2/1\n\
+            ! trailing comment";
+
+        let scl = Scl::import_lossless(input.as_bytes()).unwrap();
+        assert_eq!(scl.description(), "Test scale");
+        assert_eq!(scl.export().to_string(), input);
+
+        let equalized = scl.equalized().unwrap();
+        assert_eq!(
+            equalized.export().to_string(),
+            "!A comment\n\
+            Test scale (equalized)\n\
+            ! A second comment\n\
+            4\n\
+            300.000\n\
+            \t600.000 ignore any text\n\
+            !175.0 ignore comment\n\
+            900.000 (ignore parentheses)\n\
+            2/1\n\
+            ! trailing comment"
+        );
+
+        let mode = scl.mode(1).unwrap();
+        assert_eq!(
+            mode.export().to_string(),
+            "Test scale (mode 1)\n4\n50.000\n215.641\n1100.000\n1200.000\n"
+        );
+    }
+
+    #[test]
+    fn import_and_reexport_ascl() {
+        let input = "! 5-EDO\n\
+            5-EDO\n\
+            5\n\
+            240.\n\
+            480.\n\
+            720.\n\
+            960.\n\
+            2/1\n\
+            !\n\
+            ! @ABL NOTE_NAMES \"Do\" Re \"Mi Fa\" Sol La\n\
+            ! @ABL REFERENCE_PITCH 3 2 200.5\n\
+            ! @ABL NOTE_RANGE_BY_INDEX 1 0 9 0\n\
+            ! @ABL LINK https://example.com/5-edo\n";
+
+        let ascl = Ascl::import(input.as_bytes()).unwrap();
+        assert_eq!(ascl.scl.size(), 5);
+        assert_eq!(ascl.note_names, ["Do", "Re", "Mi Fa", "Sol", "La"]);
+        assert_eq!(ascl.kbm.root_key.midi_number(), 48);
+        assert_eq!(ascl.kbm.ref_pitch.key().midi_number(), 50);
+        assert_approx_eq!(ascl.kbm.ref_pitch.pitch().as_hz(), 200.5);
+        assert_eq!(ascl.source, None);
+        assert_eq!(ascl.link.as_deref(), Some("https://example.com/5-edo"));
+
+        assert_eq!(
+            ascl.export().to_string(),
+            "! 5-EDO\n\
+            5-EDO\n\
+            5\n\
+            240.\n\
+            480.\n\
+            720.\n\
+            960.\n\
+            2/1\n\
+            !\n\
+            ! @ABL NOTE_NAMES Do Re \"Mi Fa\" Sol La\n\
+            ! @ABL REFERENCE_PITCH 3 2 200.5\n\
+            ! @ABL LINK https://example.com/5-edo\n\
+            ! @ABL NOTE_RANGE_BY_INDEX 1 0 9 0\n"
+        );
+
+        for directive in [
+            "REFERENCE_PITCH 4 x 440",
+            "REFERENCE_PITCH 2000000000 0 440",
+            "REFERENCE_PITCH 4 2147483647 440",
+            "REFERENCE_PITCH 4 0 0",
+            "REFERENCE_PITCH 4 0 -440",
+            "REFERENCE_PITCH 4 0 inf",
+        ]
+        .iter()
+        {
+            let input = format!("Bad reference\n1\n2\n! @ABL {}", directive);
+            assert!(matches!(
+                Ascl::import(input.as_bytes()),
+                Err(SclImportError::ParseError {
+                    line_number: 4,
+                    kind: SclParseErrorKind::AblDirective
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn kbm_round_trip() {
+        let kbm = Kbm {
+            ref_pitch: ReferencePitch::from_key_and_pitch(
+                PianoKey::from_midi_number(69),
+                Pitch::from_hz(432.0),
+            ),
+            root_key: PianoKey::from_midi_number(62),
+        };
+
+        let imported = Kbm::import(kbm.export().to_string().as_bytes()).unwrap();
+        assert_eq!(imported.root_key, kbm.root_key);
+        assert_eq!(imported.ref_pitch.key(), kbm.ref_pitch.key());
+        assert_approx_eq!(imported.ref_pitch.pitch().as_hz(), 432.0);
+
+        assert!(matches!(
+            Kbm::import(&b"0\n0\n127\n60\n69\n440x\n0"[..]),
+            Err(KbmImportError::ParseError {
+                line_number: 6,
+                kind: KbmParseErrorKind::FloatValue
+            })
+        ));
+        assert!(matches!(
+            Kbm::import(&b"2\n0\n127\n60\n69\n440\n2\n0"[..]),
+            Err(KbmImportError::StructuralError(
+                KbmStructuralError::InconsistentMapSize
+            ))
+        ));
+    }
+
     #[test]
     fn import_scl_error_cases() {
         assert!(matches!(
@@ -1083,4 +2497,26 @@ mod tests {
             ["1", "0", "127", "60", "69", "430", "1", "0"]
         )
     }
+
+    #[test]
+    fn fingerprint_is_stable() {
+        let edo_12 = Scl::builder().push_cents(100.0).build().unwrap();
+        let pythagorean =
+            create_rank2_temperament_scale(None, Ratio::from_float(1.5), 6, 0, Ratio::octave())
+                .unwrap();
+
+        assert_eq!(edo_12.fingerprint(), 2382277743992889674);
+        assert_eq!(pythagorean.fingerprint(), 3399645884051779388);
+        assert_ne!(
+            pythagorean.fingerprint(),
+            pythagorean
+                .with_period(Ratio::from_cents(1200.01))
+                .unwrap()
+                .fingerprint()
+        );
+        assert_eq!(
+            pythagorean.fingerprint(),
+            pythagorean.mode(3).unwrap().fingerprint()
+        );
+    }
 }
@@ -0,0 +1,620 @@
+//! [`serde`] support for the core types, enabled by the `serde` feature.
+//!
+//! The representations are chosen to be human-friendly:
+//! - [`Ratio`]s are expression strings, e.g. `"1.5"`. Any expression, e.g. `"3/2"` or `"702c"`, can be read.
+//! - [`Pitch`]es are frequencies in Hz.
+//! - [`Note`]s are note names, e.g. `"C#4"`.
+//! - [`PianoKey`]s are MIDI numbers.
+//! - [`Scl`] items are fractions (e.g. `"5/4"`) or cents values (e.g. `"700c"`).
+//! - MTS messages are the bytes of the SysEx message.
+
+use crate::{
+    key::PianoKey,
+    mts::{
+        DeviceId, ScaleOctaveTuningMessage, SingleNoteTuningChange, SingleNoteTuningChangeMessage,
+    },
+    note::Note,
+    pitch::{Pitch, ReferencePitch},
+    ratio::Ratio,
+    scala::{Kbm, Scl, SclBuilder},
+    temperament::{EqualTemperament, TemperamentType},
+};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{convert::TryFrom, fmt, marker::PhantomData, str::FromStr};
+
+impl Serialize for Ratio {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.as_float())
+    }
+}
+
+impl<'de> Deserialize<'de> for Ratio {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserialize_parsed_or_number(deserializer, "a ratio expression, e.g. 3/2 or 702c")? {
+            Ok(ratio) => Ok(ratio),
+            Err(float_value) if is_finite_and_positive(float_value) => {
+                Ok(Ratio::from_float(float_value))
+            }
+            Err(float_value) => Err(de::Error::custom(format!(
+                "Ratio must be finite and positive but was {}",
+                float_value
+            ))),
+        }
+    }
+}
+
+impl Serialize for Pitch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_hz())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pitch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pitch = if deserializer.is_human_readable() {
+            deserialize_parsed_or_number(deserializer, "a frequency in Hz, e.g. 440 or 440Hz")?
+                .unwrap_or_else(Pitch::from_hz)
+        } else {
+            Pitch::from_hz(f64::deserialize(deserializer)?)
+        };
+        if !is_finite_and_positive(pitch.as_hz()) {
+            return Err(de::Error::custom(format!(
+                "Pitch must be finite and positive but was {} Hz",
+                pitch.as_hz()
+            )));
+        }
+        Ok(pitch)
+    }
+}
+
+impl Serialize for Note {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (letter, octave) = self.letter_and_octave();
+        serializer.collect_str(&format_args!("{:+}{}", letter, octave.octave_number()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Note {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_parsed_or_number(deserializer, "a note name, e.g. C#4")
+            .map(|parsed| parsed.unwrap_or_else(|number| Note::from_midi_number(number as i32)))
+    }
+}
+
+impl Serialize for PianoKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.midi_number())
+    }
+}
+
+impl<'de> Deserialize<'de> for PianoKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i32::deserialize(deserializer).map(PianoKey::from_midi_number)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReferencePitchRepr {
+    key: PianoKey,
+    pitch: Pitch,
+}
+
+impl Serialize for ReferencePitch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ReferencePitchRepr {
+            key: self.key(),
+            pitch: self.pitch(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReferencePitch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ReferencePitchRepr::deserialize(deserializer)?;
+        Ok(ReferencePitch::from_key_and_pitch(repr.key, repr.pitch))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct KbmRepr {
+    root_key: PianoKey,
+    ref_pitch: ReferencePitch,
+}
+
+impl Serialize for Kbm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        KbmRepr {
+            root_key: self.root_key,
+            ref_pitch: self.ref_pitch,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Kbm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = KbmRepr::deserialize(deserializer)?;
+        Ok(Kbm {
+            root_key: repr.root_key,
+            ref_pitch: repr.ref_pitch,
+        })
+    }
+}
+
+/// The last item is the period.
+#[derive(Serialize, Deserialize)]
+struct SclRepr {
+    description: String,
+    items: Vec<String>,
+}
+
+impl Serialize for Scl {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = (1..=self.size() as i32)
+            .map(|degree| match self.fraction_of(degree) {
+                Some((numer, 1)) => numer.to_string(),
+                Some((numer, denom)) => format!("{}/{}", numer, denom),
+                None => format!("{}c", self.relative_pitch_of(degree).as_cents()),
+            })
+            .collect();
+
+        SclRepr {
+            description: self.description().to_owned(),
+            items,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Scl {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SclRepr::deserialize(deserializer)?;
+
+        let mut builder = Scl::builder();
+        for item in &repr.items {
+            builder = push_scl_item(builder, item).map_err(de::Error::custom)?;
+        }
+        builder
+            .build_with_description(repr.description)
+            .map_err(|err| de::Error::custom(format!("Invalid scale ({:?})", err)))
+    }
+}
+
+fn push_scl_item(builder: SclBuilder, item: &str) -> Result<SclBuilder, String> {
+    let invalid_item = || format!("Invalid scale item '{}': Must be finite and positive", item);
+    if let Some(cents_value) = item.strip_suffix('c').and_then(|c| c.parse::<f64>().ok()) {
+        if !cents_value.is_finite() {
+            return Err(invalid_item());
+        }
+        return Ok(builder.push_cents(cents_value));
+    }
+    if let Ok(int_value) = item.parse() {
+        if int_value == 0 {
+            return Err(invalid_item());
+        }
+        return Ok(builder.push_int(int_value));
+    }
+    if let Some((numer, denom)) = item.split_once('/') {
+        if let (Ok(numer), Ok(denom)) = (numer.parse(), denom.parse()) {
+            if numer == 0 || denom == 0 {
+                return Err(invalid_item());
+            }
+            return Ok(builder.push_fraction(numer, denom));
+        }
+    }
+    Ok(builder.push_ratio(item.parse()?))
+}
+
+/// The meaning of `generator` depends on the temperament type:
+/// - Fifth-based temperaments (meantone, mavila, superpyth): Number of steps per fifth
+/// - All other temperaments: Number of steps per primary step
+#[derive(Serialize, Deserialize)]
+struct EqualTemperamentRepr {
+    temperament_type: TemperamentType,
+    num_steps_per_octave: u16,
+    generator: u16,
+    size_of_octave: Ratio,
+}
+
+impl Serialize for EqualTemperament {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let generator = match self.temperament_type() {
+            TemperamentType::Meantone | TemperamentType::Mavila | TemperamentType::Superpyth => {
+                self.num_steps_per_fifth()
+            }
+            _ => self.primary_step() as u16,
+        };
+
+        EqualTemperamentRepr {
+            temperament_type: self.temperament_type(),
+            num_steps_per_octave: self.num_steps_per_octave(),
+            generator,
+            size_of_octave: self.size_of_octave(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EqualTemperament {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = EqualTemperamentRepr::deserialize(deserializer)?;
+        let num_steps = i32::from(repr.num_steps_per_octave);
+        let generator = i32::from(repr.generator);
+
+        let fits_into_i16 = |value: i32| i16::try_from(value).is_ok();
+        let is_valid = num_steps > 0
+            && match repr.temperament_type {
+                TemperamentType::Meantone
+                | TemperamentType::Mavila
+                | TemperamentType::Superpyth => {
+                    generator < num_steps
+                        && fits_into_i16(2 * generator - num_steps)
+                        && fits_into_i16(3 * num_steps - 5 * generator)
+                }
+                TemperamentType::Porcupine => {
+                    3 * generator <= num_steps
+                        && fits_into_i16(generator)
+                        && fits_into_i16(num_steps - 6 * generator)
+                }
+                TemperamentType::Diminished => is_valid_multi_period(num_steps, 4, generator),
+                TemperamentType::Augmented => is_valid_multi_period(num_steps, 3, generator),
+                TemperamentType::Blackwood => is_valid_multi_period(num_steps, 5, generator),
+            };
+        if !is_valid {
+            return Err(de::Error::custom(format!(
+                "{} temperament with generator {} is not applicable to {}-EDO",
+                repr.temperament_type, repr.generator, repr.num_steps_per_octave
+            )));
+        }
+
+        let (num_steps, generator) = (repr.num_steps_per_octave, repr.generator);
+        let temperament = match repr.temperament_type {
+            TemperamentType::Meantone => EqualTemperament::meantone(num_steps, generator),
+            TemperamentType::Porcupine => EqualTemperament::porcupine(num_steps, generator),
+            TemperamentType::Mavila => EqualTemperament::mavila(num_steps, generator),
+            TemperamentType::Superpyth => EqualTemperament::superpyth(num_steps, generator),
+            TemperamentType::Diminished => EqualTemperament::diminished(num_steps, generator),
+            TemperamentType::Augmented => EqualTemperament::augmented(num_steps, generator),
+            TemperamentType::Blackwood => EqualTemperament::blackwood(num_steps, generator),
+        };
+        Ok(temperament.with_size_of_octave(repr.size_of_octave))
+    }
+}
+
+/// The octave must split into `num_periods` periods and the generator must lie strictly within the first period.
+fn is_valid_multi_period(num_steps: i32, num_periods: i32, generator: i32) -> bool {
+    num_steps % num_periods == 0 && generator > 0 && generator < num_steps / num_periods
+}
+
+#[derive(Serialize, Deserialize)]
+struct SingleNoteTuningChangeRepr {
+    source_note: u8,
+    target_pitch: Pitch,
+}
+
+impl Serialize for SingleNoteTuningChange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SingleNoteTuningChangeRepr {
+            source_note: self.source_note(),
+            target_pitch: self.target_pitch(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SingleNoteTuningChange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = SingleNoteTuningChangeRepr::deserialize(deserializer)?;
+        let approximation = repr.target_pitch.find_in(&());
+        Ok(SingleNoteTuningChange::new(
+            repr.source_note,
+            approximation.approx_value.midi_number(),
+            approximation.deviation,
+        ))
+    }
+}
+
+impl Serialize for SingleNoteTuningChangeMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.sysex_bytes())
+    }
+}
+
+impl<'de> Deserialize<'de> for SingleNoteTuningChangeMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        SingleNoteTuningChangeMessage::from_sysex_bytes(&bytes).map_err(|err| {
+            de::Error::custom(format!("Invalid single note tuning message ({:?})", err))
+        })
+    }
+}
+
+/// Serialization only. There is no parser for scale/octave tuning messages yet.
+impl Serialize for ScaleOctaveTuningMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.sysex_bytes())
+    }
+}
+
+impl Serialize for DeviceId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let device_id = u8::deserialize(deserializer)?;
+        DeviceId::from(device_id)
+            .ok_or_else(|| de::Error::custom(format!("Invalid device ID {}", device_id)))
+    }
+}
+
+fn is_finite_and_positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+/// Reads a string using [`FromStr`]. Human-readable formats may provide a plain number instead.
+fn deserialize_parsed_or_number<'de, D: Deserializer<'de>, T: FromStr<Err = String>>(
+    deserializer: D,
+    expecting: &'static str,
+) -> Result<Result<T, f64>, D::Error> {
+    let visitor = ParsedOrNumberVisitor {
+        expecting,
+        target: PhantomData,
+    };
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(visitor)
+    } else {
+        deserializer.deserialize_str(visitor)
+    }
+}
+
+struct ParsedOrNumberVisitor<T> {
+    expecting: &'static str,
+    target: PhantomData<T>,
+}
+
+impl<'de, T: FromStr<Err = String>> Visitor<'de> for ParsedOrNumberVisitor<T> {
+    type Value = Result<T, f64>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map(Ok).map_err(E::custom)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Err(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Err(v as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Err(v as f64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{mts::ScaleOctaveTuning, note::NoteLetter};
+    use assert_approx_eq::assert_approx_eq;
+    use serde_json::json;
+
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> (serde_json::Value, T) {
+        let json = serde_json::to_value(value).unwrap();
+        let deserialized = serde_json::from_value(json.clone()).unwrap();
+        (json, deserialized)
+    }
+
+    #[test]
+    fn serialize_ratios_pitches_notes_and_keys() {
+        let (json, ratio) = round_trip(&Ratio::from_float(1.5));
+        assert_eq!(json, json!("1.5"));
+        assert_approx_eq!(ratio.as_float(), 1.5);
+
+        let ratio: Ratio = serde_json::from_value(json!("7\\12")).unwrap();
+        assert_approx_eq!(ratio.as_cents(), 700.0);
+        let ratio: Ratio = serde_json::from_value(json!(1.25)).unwrap();
+        assert_approx_eq!(ratio.as_float(), 1.25);
+        assert!(serde_json::from_value::<Ratio>(json!("3/2*foo")).is_err());
+
+        let (json, pitch) = round_trip(&Pitch::from_hz(440.0));
+        assert_eq!(json, json!(440.0));
+        assert_approx_eq!(pitch.as_hz(), 440.0);
+        let pitch: Pitch = serde_json::from_value(json!("A4")).unwrap();
+        assert_approx_eq!(pitch.as_hz(), 440.0);
+
+        let (json, note) = round_trip(&NoteLetter::Csh.in_octave(-1));
+        assert_eq!(json, json!("C#-1"));
+        assert_eq!(note, NoteLetter::Csh.in_octave(-1));
+        let note: Note = serde_json::from_value(json!(69)).unwrap();
+        assert_eq!(note, NoteLetter::A.in_octave(4));
+
+        let (json, key) = round_trip(&PianoKey::from_midi_number(62));
+        assert_eq!(json, json!(62));
+        assert_eq!(key, PianoKey::from_midi_number(62));
+    }
+
+    #[test]
+    fn serialize_scl_and_kbm() {
+        let scl = Scl::builder()
+            .push_fraction(9, 8)
+            .push_cents(400.0)
+            .push_int(2)
+            .build_with_description("Test scale")
+            .unwrap();
+        let (json, scl) = round_trip(&scl);
+        assert_eq!(
+            json,
+            json!({ "description": "Test scale", "items": ["9/8", "400c", "2"] })
+        );
+        assert_eq!(serde_json::to_value(&scl).unwrap(), json);
+
+        let scl: Scl =
+            serde_json::from_value(json!({ "description": "", "items": ["3:12:2", "octave"] }))
+                .unwrap();
+        assert_approx_eq!(scl.relative_pitch_of(1).as_cents(), 300.0);
+        assert_approx_eq!(scl.period().as_cents(), 1200.0);
+
+        let kbm = Kbm {
+            ref_pitch: ReferencePitch::from_note(NoteLetter::A.in_octave(4)),
+            root_key: PianoKey::from_midi_number(62),
+        };
+        let (json, kbm) = round_trip(&kbm);
+        assert_eq!(
+            json,
+            json!({ "root_key": 62, "ref_pitch": { "key": 69, "pitch": 440.0 } })
+        );
+        assert_eq!(kbm.root_key.midi_number(), 62);
+        assert_approx_eq!(kbm.ref_pitch.pitch().as_hz(), 440.0);
+    }
+
+    #[test]
+    fn serialize_equal_temperaments() {
+        for temperament in EqualTemperament::find()
+            .all_by_edo(16)
+            .into_iter()
+            .chain(EqualTemperament::find().all_by_edo(15))
+        {
+            let (_, deserialized) = round_trip(&temperament);
+            assert_eq!(
+                deserialized.temperament_type(),
+                temperament.temperament_type()
+            );
+            assert_eq!(deserialized.primary_step(), temperament.primary_step());
+            assert_eq!(deserialized.secondary_step(), temperament.secondary_step());
+            assert_eq!(deserialized.num_periods(), temperament.num_periods());
+            assert_eq!(
+                deserialized.get_heptatonic_name(5),
+                temperament.get_heptatonic_name(5)
+            );
+        }
+
+        let json = serde_json::to_value(EqualTemperament::meantone(31, 18)).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "temperament_type": "Meantone",
+                "num_steps_per_octave": 31,
+                "generator": 18,
+                "size_of_octave": "2"
+            })
+        );
+
+        let result = serde_json::from_value::<EqualTemperament>(json!({
+            "temperament_type": "Diminished",
+            "num_steps_per_octave": 15,
+            "generator": 2,
+            "size_of_octave": "2"
+        }));
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Diminished temperament with generator 2 is not applicable to 15-EDO"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_values() {
+        for value in [json!(0), json!(-1), json!(0.0), json!("-3/2")].iter() {
+            assert!(serde_json::from_value::<Ratio>(value.clone()).is_err());
+        }
+        assert_eq!(
+            serde_json::from_value::<Ratio>(json!(-1))
+                .err()
+                .unwrap()
+                .to_string(),
+            "Ratio must be finite and positive but was -1"
+        );
+
+        for value in [json!(0), json!(-440.0)].iter() {
+            assert!(serde_json::from_value::<Pitch>(value.clone()).is_err());
+        }
+        assert_eq!(
+            serde_json::from_value::<Pitch>(json!(0))
+                .err()
+                .unwrap()
+                .to_string(),
+            "Pitch must be finite and positive but was 0 Hz"
+        );
+
+        for &item in ["0", "1/0", "0/3", "infc", "NaNc"].iter() {
+            let result =
+                serde_json::from_value::<Scl>(json!({ "description": "", "items": [item, "2"] }));
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                format!("Invalid scale item '{}': Must be finite and positive", item)
+            );
+        }
+
+        for &(temperament_type, num_steps_per_octave, generator) in [
+            ("Diminished", 16, 0),
+            ("Diminished", 16, 4),
+            ("Diminished", 16, 100),
+            ("Augmented", 15, 0),
+            ("Augmented", 15, 5),
+            ("Blackwood", 15, 0),
+            ("Blackwood", 15, 3),
+        ]
+        .iter()
+        {
+            let result = serde_json::from_value::<EqualTemperament>(json!({
+                "temperament_type": temperament_type,
+                "num_steps_per_octave": num_steps_per_octave,
+                "generator": generator,
+                "size_of_octave": "2"
+            }));
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                format!(
+                    "{} temperament with generator {} is not applicable to {}-EDO",
+                    temperament_type, generator, num_steps_per_octave
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn serialize_mts_messages() {
+        let tuning_change = SingleNoteTuningChange::new(60, 61, Ratio::from_cents(-50.0));
+        let (json, tuning_change) = round_trip(&tuning_change);
+        assert_eq!(json["source_note"], json!(60));
+        assert_approx_eq!(tuning_change.target_pitch().as_hz(), 269.291780);
+
+        let message = SingleNoteTuningChangeMessage::from_tuning_changes(
+            vec![tuning_change],
+            DeviceId::broadcast(),
+            0,
+        )
+        .unwrap();
+        let (json, message) = round_trip(&message);
+        assert_eq!(
+            json,
+            json!([0xf0, 0x7f, 0x7f, 0x08, 0x02, 0, 1, 60, 60, 64, 0, 0xf7])
+        );
+        assert_eq!(message.retuned_notes().len(), 1);
+
+        let octave_tuning = ScaleOctaveTuning {
+            d: Ratio::from_cents(-20.0),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&octave_tuning).unwrap();
+        assert_eq!(
+            json["d"],
+            json!(Ratio::from_cents(-20.0).as_float().to_string())
+        );
+        let octave_tuning: ScaleOctaveTuning = serde_json::from_value(json).unwrap();
+        assert_approx_eq!(octave_tuning.d.as_cents(), -20.0);
+
+        assert!(serde_json::from_value::<DeviceId>(json!(128)).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+//! Render keyboard layouts, scales and just intonation lattices as SVG images.
+
+use crate::{
+    key::Keyboard,
+    layout::{self, KeyColoring, Rgb},
+    math,
+    scala::Scl,
+    temperament::EqualTemperament,
+};
+use std::{
+    collections::HashMap,
+    f64::consts::PI,
+    fmt::{self, Display, Formatter},
+};
+
+const FONT: &str = "font-family=\"sans-serif\" text-anchor=\"middle\"";
+const MARGIN: f64 = 20.0;
+
+fn write_header(f: &mut Formatter<'_>, width: f64, height: f64) -> fmt::Result {
+    writeln!(
+        f,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0:.0}\" height=\"{1:.0}\" viewBox=\"0 0 {0:.0} {1:.0}\">",
+        width, height
+    )?;
+    writeln!(
+        f,
+        "<rect width=\"{:.0}\" height=\"{:.0}\" fill=\"#ffffff\"/>",
+        width, height
+    )
+}
+
+fn write_footer(f: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(f, "</svg>")
+}
+
+fn write_text(f: &mut Formatter<'_>, x: f64, y: f64, font_size: f64, text: &str) -> fmt::Result {
+    writeln!(
+        f,
+        "<text x=\"{:.1}\" y=\"{:.1}\" {} font-size=\"{:.0}\">{}</text>",
+        x, y, FONT, font_size, text
+    )
+}
+
+fn fill(color: Rgb) -> String {
+    format!("#{}", color)
+}
+
+const HEX_RADIUS: f64 = 30.0;
+
+/// Draws a hexagonal isomorphic layout where every key is labeled with its note name and step number.
+///
+/// The grid has the shape of a parallelogram, s.t. the rows are aligned as on most hexagonal controllers.
+/// The root key of the [`Keyboard`] is located at the left edge of the middle row.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::key::{Keyboard, PianoKey};
+/// # use tune::svg::KeyboardSvg;
+/// # use tune::temperament::EqualTemperament;
+/// let temperament = EqualTemperament::find().by_edo(31);
+/// let keyboard = Keyboard::root_at(PianoKey::from_midi_number(0))
+///     .with_steps_of(&temperament)
+///     .coprime();
+///
+/// let svg = KeyboardSvg::new(&keyboard, &temperament)
+///     .with_grid_size(8, 3)
+///     .to_string();
+///
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+/// assert_eq!(svg.matches("<polygon").count(), 24);
+/// assert!(svg.contains(">D#</text>"));
+/// ```
+pub struct KeyboardSvg<'a> {
+    keyboard: &'a Keyboard,
+    temperament: &'a EqualTemperament,
+    coloring: KeyColoring,
+    num_columns: i16,
+    num_rows: i16,
+}
+
+impl<'a> KeyboardSvg<'a> {
+    /// Creates a drawing with 12 × 7 keys colored by note name.
+    pub fn new(keyboard: &'a Keyboard, temperament: &'a EqualTemperament) -> Self {
+        Self {
+            keyboard,
+            temperament,
+            coloring: KeyColoring::NoteName(temperament.clone()),
+            num_columns: 12,
+            num_rows: 7,
+        }
+    }
+
+    pub fn with_coloring(mut self, coloring: KeyColoring) -> Self {
+        self.coloring = coloring;
+        self
+    }
+
+    pub fn with_grid_size(mut self, num_columns: i16, num_rows: i16) -> Self {
+        self.num_columns = num_columns;
+        self.num_rows = num_rows;
+        self
+    }
+}
+
+impl Display for KeyboardSvg<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let key_width = 3f64.sqrt() * HEX_RADIUS;
+        let row_height = 1.5 * HEX_RADIUS;
+        let width = (f64::from(self.num_columns) + 0.5) * key_width + 2.0 * MARGIN;
+        let height =
+            (f64::from(self.num_rows) - 1.0) * row_height + 2.0 * HEX_RADIUS + 2.0 * MARGIN;
+
+        let root_key = self.keyboard.get_key(0, 0);
+        let num_steps_per_octave = i32::from(self.temperament.num_steps_per_octave());
+        let top_row = (self.num_rows - 1) / 2;
+
+        write_header(f, width, height)?;
+        for row in 0..self.num_rows {
+            let y = top_row - row;
+            let shift = if y.rem_euclid(2) == 0 { 0.5 } else { 0.0 };
+            for column in 0..self.num_columns {
+                let x = column + y.div_euclid(2);
+                let degree = root_key.num_keys_before(self.keyboard.get_key(x, y));
+
+                let center_x = MARGIN + (f64::from(column) + shift + 0.5) * key_width;
+                let center_y = MARGIN + HEX_RADIUS + f64::from(row) * row_height;
+                let corners = (0..6)
+                    .map(|corner| {
+                        let angle = PI / 3.0 * f64::from(corner) + PI / 6.0;
+                        format!(
+                            "{:.1},{:.1}",
+                            center_x + HEX_RADIUS * angle.cos(),
+                            center_y + HEX_RADIUS * angle.sin()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(
+                    f,
+                    "<polygon points=\"{}\" fill=\"{}\" stroke=\"#000000\"/>",
+                    corners,
+                    fill(self.coloring.color_of(degree))
+                )?;
+
+                let name = self.temperament.get_heptatonic_name(degree);
+                let name = name.split(" / ").next().unwrap_or_default();
+                write_text(f, center_x, center_y, 14.0, name)?;
+                write_text(
+                    f,
+                    center_x,
+                    center_y + 14.0,
+                    10.0,
+                    &degree.rem_euclid(num_steps_per_octave).to_string(),
+                )?;
+            }
+        }
+        write_footer(f)
+    }
+}
+
+const CIRCLE_RADIUS: f64 = 150.0;
+const LABEL_DISTANCE: f64 = 40.0;
+
+/// Draws the degrees of a scale on a circle representing one period, labeled with their cents values and, if available, their fractions.
+///
+/// The root is located at the top and the pitch increases clockwise.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::scala::Scl;
+/// # use tune::svg::ScaleCircleSvg;
+/// let scl = Scl::builder()
+///     .push_fraction(5, 4)
+///     .push_cents(700.0)
+///     .push_int(2)
+///     .build()
+///     .unwrap();
+///
+/// let svg = ScaleCircleSvg::new(&scl).to_string();
+///
+/// assert_eq!(svg.matches("<line").count(), 3);
+/// assert!(svg.contains(">1/1</text>"));
+/// assert!(svg.contains(">386.3c</text>"));
+/// assert!(svg.contains(">5/4</text>"));
+/// assert!(svg.contains(">700.0c</text>"));
+/// ```
+pub struct ScaleCircleSvg<'a> {
+    scl: &'a Scl,
+}
+
+impl<'a> ScaleCircleSvg<'a> {
+    pub fn new(scl: &'a Scl) -> Self {
+        Self { scl }
+    }
+}
+
+impl Display for ScaleCircleSvg<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let size = 2.0 * (CIRCLE_RADIUS + LABEL_DISTANCE + MARGIN + 20.0);
+        let center = size / 2.0;
+        let period_in_cents = self.scl.period().as_cents();
+
+        write_header(f, size, size)?;
+        writeln!(
+            f,
+            "<circle cx=\"{0:.1}\" cy=\"{0:.1}\" r=\"{1:.1}\" fill=\"none\" stroke=\"#000000\"/>",
+            center, CIRCLE_RADIUS
+        )?;
+
+        for degree in 0..self.scl.size() as i32 {
+            let cents = self.scl.relative_pitch_of(degree).as_cents();
+            let angle = 2.0 * PI * cents / period_in_cents;
+            let (sin, cos) = angle.sin_cos();
+
+            writeln!(
+                f,
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#a5a5a5\"/>",
+                center,
+                center,
+                center + CIRCLE_RADIUS * sin,
+                center - CIRCLE_RADIUS * cos
+            )?;
+            writeln!(
+                f,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"5\" fill=\"#000000\"/>",
+                center + CIRCLE_RADIUS * sin,
+                center - CIRCLE_RADIUS * cos
+            )?;
+
+            let label_x = center + (CIRCLE_RADIUS + LABEL_DISTANCE) * sin;
+            let label_y = center - (CIRCLE_RADIUS + LABEL_DISTANCE) * cos;
+            write_text(f, label_x, label_y, 12.0, &format!("{:.1}c", cents))?;
+            if let Some((numer, denom)) = self.scl.fraction_of(degree) {
+                write_text(
+                    f,
+                    label_x,
+                    label_y + 14.0,
+                    12.0,
+                    &format!("{}/{}", numer, denom),
+                )?;
+            }
+        }
+        write_footer(f)
+    }
+}
+
+/// The primes spanning the lattice and their projected unit vectors. 7 is drawn diagonally, creating a 3-D impression.
+const LATTICE_AXES: [(u64, (f64, f64)); 3] =
+    [(3, (90.0, 0.0)), (5, (0.0, -80.0)), (7, (40.0, -35.0))];
+const NODE_RADIUS: f64 = 22.0;
+
+/// Draws the degrees of a just intonation scale as points of an octave-equivalent lattice.
+///
+/// Moving to the right multiplies by 3, moving up multiplies by 5 and moving diagonally multiplies by 7.
+/// Points that differ by a single prime factor are connected.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::scala::Scl;
+/// # use tune::svg::{LatticeSvg, LatticeSvgError};
+/// let just_major = Scl::builder()
+///     .push_fraction(9, 8)
+///     .push_fraction(5, 4)
+///     .push_fraction(4, 3)
+///     .push_fraction(3, 2)
+///     .push_fraction(5, 3)
+///     .push_fraction(15, 8)
+///     .push_int(2)
+///     .build()
+///     .unwrap();
+///
+/// let svg = LatticeSvg::new(&just_major).unwrap().to_string();
+/// assert_eq!(svg.matches("<circle").count(), 7);
+/// assert_eq!(svg.matches("<line").count(), 8);
+/// assert!(svg.contains(">15/8</text>"));
+///
+/// let tempered = Scl::builder().push_cents(700.0).push_int(2).build().unwrap();
+/// assert!(matches!(LatticeSvg::new(&tempered), Err(LatticeSvgError::NotAFraction)));
+///
+/// let undecimal = Scl::builder().push_fraction(11, 8).push_int(2).build().unwrap();
+/// assert!(matches!(LatticeSvg::new(&undecimal), Err(LatticeSvgError::PrimeLimitExceeded(11))));
+/// ```
+pub struct LatticeSvg<'a> {
+    scl: &'a Scl,
+    points: Vec<[i32; 3]>,
+}
+
+/// Reasons why a [`Scl`] cannot be drawn as a [`LatticeSvg`].
+#[derive(Clone, Debug)]
+pub enum LatticeSvgError {
+    /// The scale contains a degree that is not an exact fraction.
+    NotAFraction,
+
+    /// The scale contains a prime factor larger than 7.
+    PrimeLimitExceeded(u32),
+}
+
+impl<'a> LatticeSvg<'a> {
+    pub fn new(scl: &'a Scl) -> Result<Self, LatticeSvgError> {
+        match scl.prime_limit() {
+            None => return Err(LatticeSvgError::NotAFraction),
+            Some(prime_limit) if prime_limit > 7 => {
+                return Err(LatticeSvgError::PrimeLimitExceeded(prime_limit))
+            }
+            Some(_) => {}
+        }
+
+        let points = (0..scl.size() as i32)
+            .map(|degree| {
+                let (numer, denom) = scl
+                    .fraction_of(degree)
+                    .ok_or(LatticeSvgError::NotAFraction)?;
+                let mut point = [0; 3];
+                let numer_factors = math::prime_factors_u64(numer)
+                    .into_iter()
+                    .map(|factor| (factor, 1));
+                let denom_factors = math::prime_factors_u64(denom)
+                    .into_iter()
+                    .map(|factor| (factor, -1));
+                for ((prime, multiplicity), sign) in numer_factors.chain(denom_factors) {
+                    if let Some(axis) = LATTICE_AXES.iter().position(|&(p, _)| p == prime) {
+                        point[axis] += sign * multiplicity as i32;
+                    }
+                }
+                Ok(point)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { scl, points })
+    }
+}
+
+impl Display for LatticeSvg<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let project = |point: &[i32; 3]| {
+            LATTICE_AXES.iter().zip(point).fold(
+                (0.0, 0.0),
+                |(x, y), (&(_, (dx, dy)), &exponent)| {
+                    (x + f64::from(exponent) * dx, y + f64::from(exponent) * dy)
+                },
+            )
+        };
+        let positions = self.points.iter().map(project).collect::<Vec<_>>();
+
+        let min_x = positions.iter().map(|p| p.0).fold(0.0, f64::min);
+        let max_x = positions.iter().map(|p| p.0).fold(0.0, f64::max);
+        let min_y = positions.iter().map(|p| p.1).fold(0.0, f64::min);
+        let max_y = positions.iter().map(|p| p.1).fold(0.0, f64::max);
+        let offset_x = MARGIN + NODE_RADIUS - min_x;
+        let offset_y = MARGIN + NODE_RADIUS - min_y;
+
+        write_header(
+            f,
+            max_x - min_x + 2.0 * (MARGIN + NODE_RADIUS),
+            max_y - min_y + 2.0 * (MARGIN + NODE_RADIUS),
+        )?;
+
+        let index_of_point = self
+            .points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| (*point, index))
+            .collect::<HashMap<_, _>>();
+        for (index, point) in self.points.iter().enumerate() {
+            for axis in 0..LATTICE_AXES.len() {
+                let mut neighbor = *point;
+                neighbor[axis] += 1;
+                if let Some(&neighbor_index) = index_of_point.get(&neighbor) {
+                    writeln!(
+                        f,
+                        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#000000\"/>",
+                        positions[index].0 + offset_x,
+                        positions[index].1 + offset_y,
+                        positions[neighbor_index].0 + offset_x,
+                        positions[neighbor_index].1 + offset_y
+                    )?;
+                }
+            }
+        }
+
+        for (degree, &(x, y)) in positions.iter().enumerate() {
+            let (x, y) = (x + offset_x, y + offset_y);
+            let color = if degree == 0 {
+                layout::ROOT_COLOR
+            } else {
+                layout::NATURAL_COLOR
+            };
+            writeln!(
+                f,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.0}\" fill=\"{}\" stroke=\"#000000\"/>",
+                x,
+                y,
+                NODE_RADIUS,
+                fill(color)
+            )?;
+            let (numer, denom) = self.scl.fraction_of(degree as i32).unwrap_or((1, 1));
+            write_text(f, x, y + 4.0, 12.0, &format!("{}/{}", numer, denom))?;
+        }
+        write_footer(f)
+    }
+}
@@ -7,6 +7,7 @@ use std::{convert::TryInto, fmt::Display};
 #[derive(Clone, Debug)]
 pub struct EqualTemperament {
     temperament_type: TemperamentType,
+    num_steps_per_octave: u16,
     primary_step: i16,
     secondary_step: i16,
     num_steps_per_fifth: u16,
@@ -17,6 +18,40 @@ pub struct EqualTemperament {
 
 impl EqualTemperament {
     pub fn meantone(num_steps_per_octave: u16, num_steps_per_fifth: u16) -> Self {
+        Self::fifth_based(
+            TemperamentType::Meantone,
+            num_steps_per_octave,
+            num_steps_per_fifth,
+        )
+    }
+
+    pub fn superpyth(num_steps_per_octave: u16, num_steps_per_fifth: u16) -> Self {
+        Self::fifth_based(
+            TemperamentType::Superpyth,
+            num_steps_per_octave,
+            num_steps_per_fifth,
+        )
+    }
+
+    pub fn mavila(num_steps_per_octave: u16, num_steps_per_fifth: u16) -> Self {
+        let mut temperament = Self::fifth_based(
+            TemperamentType::Mavila,
+            num_steps_per_octave,
+            num_steps_per_fifth,
+        );
+        temperament.formatter = temperament
+            .formatter
+            .with_cycle_signs('b', '#', '▲')
+            .with_sharpness(temperament.secondary_step - temperament.primary_step)
+            .with_note_order(NoteOrder::Reversed);
+        temperament
+    }
+
+    fn fifth_based(
+        temperament_type: TemperamentType,
+        num_steps_per_octave: u16,
+        num_steps_per_fifth: u16,
+    ) -> Self {
         let primary_step = (2 * i32::from(num_steps_per_fifth) - i32::from(num_steps_per_octave))
             .try_into()
             .expect("primary step out of range");
@@ -25,20 +60,20 @@ impl EqualTemperament {
         .try_into()
         .expect("secondary step out of range");
         Self {
-            temperament_type: TemperamentType::Meantone,
+            temperament_type,
+            num_steps_per_octave,
             primary_step,
             secondary_step,
             num_steps_per_fifth,
             size_of_octave: Ratio::octave(),
             per_gen: PerGen::new(num_steps_per_octave, num_steps_per_fifth),
-            formatter: NoteFormatter {
-                note_names: &["F", "C", "G", "D", "A", "E", "B"],
-                genchain_origin: 3,
-                next_cycle_sign: '#',
-                prev_cycle_sign: 'b',
-                sharpness: primary_step - secondary_step,
-                note_order: NoteOrder::Normal,
-            },
+            formatter: NoteFormatter::new(
+                to_owned_names(&["F", "C", "G", "D", "A", "E", "B"]),
+                1,
+                3,
+            )
+            .expect("valid note names")
+            .with_sharpness(primary_step - secondary_step),
         }
     }
 
@@ -49,6 +84,7 @@ impl EqualTemperament {
             .expect("secondary step out of range");
         EqualTemperament {
             temperament_type: TemperamentType::Porcupine,
+            num_steps_per_octave,
             primary_step,
             secondary_step,
             num_steps_per_fifth: (i32::from(num_steps_per_octave) - 3 * i32::from(primary_step))
@@ -59,14 +95,102 @@ impl EqualTemperament {
                 num_steps_per_octave,
                 primary_step.try_into().expect("primary step out of range"),
             ),
-            formatter: NoteFormatter {
-                note_names: &["A", "B", "C", "D", "E", "F", "G"],
-                genchain_origin: 3,
-                next_cycle_sign: '-',
-                prev_cycle_sign: '+',
-                sharpness: secondary_step - primary_step,
-                note_order: NoteOrder::Reversed,
-            },
+            formatter: NoteFormatter::new(
+                to_owned_names(&["A", "B", "C", "D", "E", "F", "G"]),
+                1,
+                3,
+            )
+            .expect("valid note names")
+            .with_cycle_signs('-', '+', '▲')
+            .with_sharpness(secondary_step - primary_step)
+            .with_note_order(NoteOrder::Reversed),
+        }
+    }
+
+    /// Creates an octatonic temperament with 4 periods per octave and 2 notes per period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_steps_per_octave` is not divisible by 4.
+    pub fn diminished(num_steps_per_octave: u16, generator: u16) -> Self {
+        Self::multi_period(
+            TemperamentType::Diminished,
+            num_steps_per_octave,
+            4,
+            generator,
+            &["A", "B", "C", "D", "E", "F", "G", "H"],
+        )
+    }
+
+    /// Creates a hexatonic temperament with 3 periods per octave and 2 notes per period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_steps_per_octave` is not divisible by 3.
+    pub fn augmented(num_steps_per_octave: u16, generator: u16) -> Self {
+        Self::multi_period(
+            TemperamentType::Augmented,
+            num_steps_per_octave,
+            3,
+            generator,
+            &["A", "B", "C", "D", "E", "F"],
+        )
+    }
+
+    /// Creates a decatonic temperament with 5 periods per octave and 2 notes per period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_steps_per_octave` is not divisible by 5.
+    pub fn blackwood(num_steps_per_octave: u16, generator: u16) -> Self {
+        Self::multi_period(
+            TemperamentType::Blackwood,
+            num_steps_per_octave,
+            5,
+            generator,
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J"],
+        )
+    }
+
+    fn multi_period(
+        temperament_type: TemperamentType,
+        num_steps_per_octave: u16,
+        num_periods: u16,
+        generator: u16,
+        note_names: &[&str],
+    ) -> Self {
+        let period = num_steps_per_octave / num_periods;
+        assert!(
+            period * num_periods == num_steps_per_octave,
+            "{}-EDO cannot be divided into {} periods",
+            num_steps_per_octave,
+            num_periods
+        );
+
+        // Use the larger of the two step sizes s.t. the generator chain ascends by sharps
+        let generator = generator % period.max(1);
+        let generator = generator.max(period - generator);
+
+        let primary_step = generator.try_into().expect("primary step out of range");
+        let secondary_step = (period - generator)
+            .try_into()
+            .expect("secondary step out of range");
+
+        Self {
+            temperament_type,
+            num_steps_per_octave,
+            primary_step,
+            secondary_step,
+            num_steps_per_fifth: Ratio::from_float(1.5)
+                .num_equal_steps_of_size(
+                    Ratio::octave().divided_into_equal_steps(num_steps_per_octave),
+                )
+                .round() as u16,
+            size_of_octave: Ratio::octave(),
+            per_gen: PerGen::new(period, generator),
+            formatter: NoteFormatter::new(to_owned_names(note_names), num_periods, 0)
+                .expect("valid note names")
+                .with_sharpness(primary_step - secondary_step),
         }
     }
 
@@ -82,6 +206,34 @@ impl EqualTemperament {
         }
     }
 
+    /// Creates an [`EdoSearch`] rating EDOs by how well they approximate a set of target intervals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::ratio::Ratio;
+    /// # use tune::temperament::EqualTemperament;
+    /// let ratings = EqualTemperament::search_edos()
+    ///     .with_target(Ratio::from_float(3.0 / 2.0))
+    ///     .with_target(Ratio::from_float(5.0 / 4.0))
+    ///     .with_target(Ratio::from_float(7.0 / 4.0))
+    ///     .with_consistency_required(true)
+    ///     .in_range(10..=35);
+    ///
+    /// let best = &ratings[0];
+    /// assert_eq!(best.num_steps_per_octave(), 31);
+    /// assert_eq!(best.mapped_steps(), &[18, 10, 25]);
+    /// assert!(best.is_consistent());
+    /// ```
+    pub fn search_edos() -> EdoSearch {
+        EdoSearch {
+            targets: Vec::new(),
+            stretch_optimized: false,
+            consistency_required: false,
+            ranking: EdoRanking::AbsoluteError,
+        }
+    }
+
     pub fn as_porcupine(&self) -> Option<EqualTemperament> {
         let num_steps_of_fourth = self.num_steps_per_octave() - self.num_steps_per_fifth();
         if num_steps_of_fourth % 3 == 0 {
@@ -107,11 +259,16 @@ impl EqualTemperament {
     }
 
     pub fn sharpness(&self) -> i16 {
-        self.formatter.sharpness
+        self.formatter.sharpness()
     }
 
     pub fn num_steps_per_octave(&self) -> u16 {
-        self.per_gen.period()
+        self.num_steps_per_octave
+    }
+
+    /// The number of equal periods the octave is divided into, e.g. 4 for [`TemperamentType::Diminished`].
+    pub fn num_periods(&self) -> u16 {
+        self.formatter.num_periods()
     }
 
     pub fn size_of_octave(&self) -> Ratio {
@@ -135,6 +292,18 @@ impl EqualTemperament {
     pub fn get_heptatonic_name(&self, index: i32) -> String {
         self.formatter.get_name_by_step(&self.per_gen, index)
     }
+
+    pub fn per_gen(&self) -> &PerGen {
+        &self.per_gen
+    }
+
+    pub fn formatter(&self) -> &NoteFormatter {
+        &self.formatter
+    }
+}
+
+fn to_owned_names(note_names: &[&str]) -> Vec<String> {
+    note_names.iter().map(|&name| name.to_owned()).collect()
 }
 
 pub struct TemperamentFinder {
@@ -164,10 +333,154 @@ impl TemperamentFinder {
             .num_equal_steps_of_size(step_size)
             .round() as u16;
 
-        self.from_starting_point(num_steps_per_octave, best_fifth)
+        self.preferred_family(step_size, num_steps_per_octave, best_fifth)
+            .unwrap_or_else(|| self.from_starting_point(num_steps_per_octave, best_fifth))
             .with_size_of_octave(step_size.repeated(num_steps_per_octave))
     }
 
+    /// Lists all temperament families that can be applied to the given EDO.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::temperament::EqualTemperament;
+    /// # use tune::temperament::TemperamentType;
+    /// let families = EqualTemperament::find()
+    ///     .all_by_edo(16)
+    ///     .iter()
+    ///     .map(EqualTemperament::temperament_type)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     families,
+    ///     [
+    ///         TemperamentType::Meantone,
+    ///         TemperamentType::Mavila,
+    ///         TemperamentType::Diminished
+    ///     ]
+    /// );
+    /// ```
+    pub fn all_by_edo(&self, num_steps_per_octave: impl Into<f64>) -> Vec<EqualTemperament> {
+        self.all_by_step_size(Ratio::octave().divided_into_equal_steps(num_steps_per_octave))
+    }
+
+    pub fn all_by_step_size(&self, step_size: Ratio) -> Vec<EqualTemperament> {
+        let num_steps_per_octave =
+            Ratio::octave().num_equal_steps_of_size(step_size).round() as u16;
+        let best_fifth = Ratio::from_float(1.5)
+            .num_equal_steps_of_size(step_size)
+            .round() as u16;
+
+        TemperamentType::ALL
+            .iter()
+            .filter_map(|&temperament_type| {
+                self.family(
+                    temperament_type,
+                    step_size,
+                    num_steps_per_octave,
+                    best_fifth,
+                )
+            })
+            .map(|temperament| {
+                temperament.with_size_of_octave(step_size.repeated(num_steps_per_octave))
+            })
+            .collect()
+    }
+
+    fn preferred_family(
+        &self,
+        step_size: Ratio,
+        num_steps_per_octave: u16,
+        best_fifth: u16,
+    ) -> Option<EqualTemperament> {
+        let temperament_type = match self.preference {
+            TemperamentPreference::Meantone
+            | TemperamentPreference::PorcupineWhenMeantoneIsBad
+            | TemperamentPreference::Porcupine => return None,
+            TemperamentPreference::Mavila => TemperamentType::Mavila,
+            TemperamentPreference::Superpyth => TemperamentType::Superpyth,
+            TemperamentPreference::Diminished => TemperamentType::Diminished,
+            TemperamentPreference::Augmented => TemperamentType::Augmented,
+            TemperamentPreference::Blackwood => TemperamentType::Blackwood,
+        };
+        self.family(
+            temperament_type,
+            step_size,
+            num_steps_per_octave,
+            best_fifth,
+        )
+    }
+
+    fn family(
+        &self,
+        temperament_type: TemperamentType,
+        step_size: Ratio,
+        num_steps_per_octave: u16,
+        best_fifth: u16,
+    ) -> Option<EqualTemperament> {
+        let mut fifths = vec![best_fifth];
+        if self.second_best_fifth_allowed && best_fifth > 0 {
+            fifths.push(best_fifth - 1);
+        }
+        let fifths = fifths.into_iter();
+
+        let num_periods = match temperament_type {
+            TemperamentType::Meantone => {
+                return fifths
+                    .map(|fifth| EqualTemperament::meantone(num_steps_per_octave, fifth))
+                    .find(|t| t.primary_step() > 0 && t.secondary_step() >= 0)
+            }
+            TemperamentType::Superpyth => {
+                return fifths
+                    .map(|fifth| EqualTemperament::superpyth(num_steps_per_octave, fifth))
+                    .find(|t| {
+                        t.primary_step() > 0
+                            && t.secondary_step() >= 0
+                            && t.size_of_fifth() > Ratio::from_float(1.5)
+                    })
+            }
+            TemperamentType::Mavila => {
+                return fifths
+                    .map(|fifth| EqualTemperament::mavila(num_steps_per_octave, fifth))
+                    .find(|t| t.primary_step() > 0 && t.secondary_step() > 0 && t.sharpness() > 0)
+            }
+            TemperamentType::Porcupine => {
+                return fifths
+                    .filter_map(|fifth| {
+                        EqualTemperament::meantone(num_steps_per_octave, fifth).as_porcupine()
+                    })
+                    .find(|t| t.primary_step() > 0 && t.secondary_step() >= 0)
+            }
+            TemperamentType::Diminished => 4,
+            TemperamentType::Augmented => 3,
+            TemperamentType::Blackwood => 5,
+        };
+
+        let period = num_steps_per_octave / num_periods;
+        if period * num_periods != num_steps_per_octave || period < 2 {
+            return None;
+        }
+
+        // Blackwood tempers out the fifth modulo the period s.t. the major third has to be used
+        let best_major_third = Ratio::from_float(1.25)
+            .num_equal_steps_of_size(step_size)
+            .round() as u16;
+        let generator = [best_fifth, best_major_third]
+            .iter()
+            .map(|steps| steps % period)
+            .find(|&generator| generator != 0)?;
+
+        Some(match temperament_type {
+            TemperamentType::Diminished => {
+                EqualTemperament::diminished(num_steps_per_octave, generator)
+            }
+            TemperamentType::Augmented => {
+                EqualTemperament::augmented(num_steps_per_octave, generator)
+            }
+            _ => EqualTemperament::blackwood(num_steps_per_octave, generator),
+        })
+    }
+
     fn from_starting_point(&self, num_steps_per_octave: u16, best_fifth: u16) -> EqualTemperament {
         let (best_fifth_temperament, has_acceptable_qualities) =
             self.create_and_rate_temperament(num_steps_per_octave, best_fifth);
@@ -197,7 +510,12 @@ impl TemperamentFinder {
 
         let try_pocupine = match self.preference {
             TemperamentPreference::Meantone => false,
-            TemperamentPreference::PorcupineWhenMeantoneIsBad => {
+            TemperamentPreference::PorcupineWhenMeantoneIsBad
+            | TemperamentPreference::Mavila
+            | TemperamentPreference::Superpyth
+            | TemperamentPreference::Diminished
+            | TemperamentPreference::Augmented
+            | TemperamentPreference::Blackwood => {
                 !has_acceptable_qualities || temperament.sharpness() < 0
             }
             TemperamentPreference::Porcupine => true,
@@ -222,9 +540,20 @@ pub enum TemperamentPreference {
     PorcupineWhenMeantoneIsBad,
     /// Use porcupine whenever possible.
     Porcupine,
+    /// Use mavila whenever possible. Otherwise, behave like [`TemperamentPreference::PorcupineWhenMeantoneIsBad`].
+    Mavila,
+    /// Use superpyth whenever possible. Otherwise, behave like [`TemperamentPreference::PorcupineWhenMeantoneIsBad`].
+    Superpyth,
+    /// Use diminished whenever possible. Otherwise, behave like [`TemperamentPreference::PorcupineWhenMeantoneIsBad`].
+    Diminished,
+    /// Use augmented whenever possible. Otherwise, behave like [`TemperamentPreference::PorcupineWhenMeantoneIsBad`].
+    Augmented,
+    /// Use blackwood whenever possible. Otherwise, behave like [`TemperamentPreference::PorcupineWhenMeantoneIsBad`].
+    Blackwood,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TemperamentType {
     /// Octave-reduced temperament treating 4 fifths to be equal to one major third.
     ///
@@ -242,6 +571,47 @@ pub enum TemperamentType {
     /// The note names are derived from the genchain of primary steps [ &hellip; G# A B C D E F G Ab &hellip; ].
     /// In contrast to meantone, the intervals E-F and F-G have the same size of one primary step while G-A is different, usually larger.
     Porcupine,
+
+    /// Octave-reduced temperament with a fifth so flat that 4 fifths form a minor rather than a major third.
+    ///
+    /// The note names are derived from the same genchain of fifths as in meantone.
+    /// Since the genchain of 7 fifths descends, the meaning of the accidentals is swapped (anti-diatonic notation) s.t. # is still a raising accidental.
+    Mavila,
+
+    /// Octave-reduced temperament with a fifth sharper than 3/2 s.t. 4 fifths approximate 9/7 rather than 5/4.
+    ///
+    /// The note names are the same as in meantone but the sharps are larger.
+    Superpyth,
+
+    /// Temperament with a period of 1/4 octave and 2 notes per period, forming an octatonic scale.
+    ///
+    /// The note names are derived from the alphabet [ A B C D E F G H ], 2 letters per period.
+    /// The *primary step* is the larger and the *secondary step* the smaller step of the scale.
+    Diminished,
+
+    /// Temperament with a period of 1/3 octave and 2 notes per period, forming a hexatonic scale.
+    ///
+    /// The note names are derived from the alphabet [ A B C D E F ], 2 letters per period.
+    /// The *primary step* is the larger and the *secondary step* the smaller step of the scale.
+    Augmented,
+
+    /// Temperament with a period of 1/5 octave and 2 notes per period, forming a decatonic scale.
+    ///
+    /// The note names are derived from the alphabet [ A B C D E F G H I J ], 2 letters per period.
+    /// The *primary step* is the larger and the *secondary step* the smaller step of the scale.
+    Blackwood,
+}
+
+impl TemperamentType {
+    const ALL: [TemperamentType; 7] = [
+        TemperamentType::Meantone,
+        TemperamentType::Porcupine,
+        TemperamentType::Mavila,
+        TemperamentType::Superpyth,
+        TemperamentType::Diminished,
+        TemperamentType::Augmented,
+        TemperamentType::Blackwood,
+    ];
 }
 
 impl Display for TemperamentType {
@@ -249,11 +619,222 @@ impl Display for TemperamentType {
         let display_name = match self {
             TemperamentType::Meantone => "Meantone",
             TemperamentType::Porcupine => "Porcupine",
+            TemperamentType::Mavila => "Mavila",
+            TemperamentType::Superpyth => "Superpyth",
+            TemperamentType::Diminished => "Diminished",
+            TemperamentType::Augmented => "Augmented",
+            TemperamentType::Blackwood => "Blackwood",
         };
         write!(f, "{}", display_name)
     }
 }
 
+/// Rates EDOs by how well they approximate a list of target intervals.
+///
+/// Create an instance via [`EqualTemperament::search_edos`].
+#[derive(Clone, Debug)]
+pub struct EdoSearch {
+    targets: Vec<(Ratio, f64)>,
+    stretch_optimized: bool,
+    consistency_required: bool,
+    ranking: EdoRanking,
+}
+
+impl EdoSearch {
+    /// Adds a target interval with weight 1.
+    pub fn with_target(self, target: Ratio) -> Self {
+        self.with_weighted_target(target, 1.0)
+    }
+
+    /// Adds a target interval whose squared error is multiplied by `weight`.
+    pub fn with_weighted_target(mut self, target: Ratio, weight: f64) -> Self {
+        self.targets.push((target, weight));
+        self
+    }
+
+    /// Optimizes the step size of each EDO s.t. the weighted error is minimized.
+    ///
+    /// The mapping of the targets is still determined using a pure octave.
+    pub fn with_stretch_optimized(mut self, stretch_optimized: bool) -> Self {
+        self.stretch_optimized = stretch_optimized;
+        self
+    }
+
+    /// Excludes EDOs for which the mapped differences between two targets are not the best approximations of the difference itself.
+    pub fn with_consistency_required(mut self, consistency_required: bool) -> Self {
+        self.consistency_required = consistency_required;
+        self
+    }
+
+    pub fn with_ranking(mut self, ranking: EdoRanking) -> Self {
+        self.ranking = ranking;
+        self
+    }
+
+    /// Rates every EDO in `range` and returns the ratings, best first.
+    pub fn in_range(&self, range: impl IntoIterator<Item = u16>) -> Vec<EdoRating> {
+        let mut ratings = range
+            .into_iter()
+            .filter(|&num_steps_per_octave| num_steps_per_octave > 0)
+            .map(|num_steps_per_octave| self.rate(num_steps_per_octave))
+            .filter(|rating| !self.consistency_required || rating.is_consistent())
+            .collect::<Vec<_>>();
+
+        ratings.sort_by(|a, b| {
+            let (a, b) = match self.ranking {
+                EdoRanking::AbsoluteError => (a.error_in_cents(), b.error_in_cents()),
+                EdoRanking::RelativeError => (a.relative_error(), b.relative_error()),
+            };
+            a.partial_cmp(&b).expect("Comparison failed")
+        });
+
+        ratings
+    }
+
+    fn rate(&self, num_steps_per_octave: u16) -> EdoRating {
+        let num_steps = f64::from(num_steps_per_octave);
+        let targets_in_steps = self
+            .targets
+            .iter()
+            .map(|(target, _)| target.as_octaves() * num_steps)
+            .collect::<Vec<_>>();
+
+        let mapped_steps = targets_in_steps
+            .iter()
+            .map(|target_in_steps| target_in_steps.round() as i32)
+            .collect::<Vec<_>>();
+
+        let consistent = targets_in_steps
+            .iter()
+            .zip(&mapped_steps)
+            .all(|(a, a_mapped)| {
+                targets_in_steps
+                    .iter()
+                    .zip(&mapped_steps)
+                    .all(|(b, b_mapped)| (a - b).round() as i32 == a_mapped - b_mapped)
+            });
+
+        let pure_step_size = Ratio::octave().divided_into_equal_steps(num_steps);
+        let step_size = if self.stretch_optimized {
+            let (numer, denom) = self.targets.iter().zip(&mapped_steps).fold(
+                (0.0, 0.0),
+                |(numer, denom), (&(target, weight), &mapped_steps)| {
+                    let mapped_steps = f64::from(mapped_steps);
+                    (
+                        numer + weight * mapped_steps * target.as_octaves(),
+                        denom + weight * mapped_steps * mapped_steps,
+                    )
+                },
+            );
+            if denom > 0.0 {
+                Ratio::from_octaves(numer / denom)
+            } else {
+                pure_step_size
+            }
+        } else {
+            pure_step_size
+        };
+
+        let deviations = self
+            .targets
+            .iter()
+            .zip(&mapped_steps)
+            .map(|(&(target, _), &mapped_steps)| {
+                step_size.repeated(mapped_steps).deviation_from(target)
+            })
+            .collect::<Vec<_>>();
+
+        let (weighted_square_sum, weight_sum) = self.targets.iter().zip(&deviations).fold(
+            (0.0, 0.0),
+            |(square_sum, weight_sum), (&(_, weight), deviation)| {
+                (
+                    square_sum + weight * deviation.as_cents().powi(2),
+                    weight_sum + weight,
+                )
+            },
+        );
+        let error_in_cents = if weight_sum > 0.0 {
+            (weighted_square_sum / weight_sum).sqrt()
+        } else {
+            0.0
+        };
+
+        EdoRating {
+            num_steps_per_octave,
+            step_size,
+            mapped_steps,
+            deviations,
+            error_in_cents,
+            consistent,
+        }
+    }
+}
+
+/// Criterion used to sort the results of an [`EdoSearch`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum EdoRanking {
+    /// Sort by the weighted RMS error in cents. Favors large EDOs.
+    AbsoluteError,
+    /// Sort by the weighted RMS error in units of the step size. Favors small EDOs.
+    RelativeError,
+}
+
+/// The result of rating a single EDO in an [`EdoSearch`].
+#[derive(Clone, Debug)]
+pub struct EdoRating {
+    num_steps_per_octave: u16,
+    step_size: Ratio,
+    mapped_steps: Vec<i32>,
+    deviations: Vec<Ratio>,
+    error_in_cents: f64,
+    consistent: bool,
+}
+
+impl EdoRating {
+    pub fn num_steps_per_octave(&self) -> u16 {
+        self.num_steps_per_octave
+    }
+
+    pub fn step_size(&self) -> Ratio {
+        self.step_size
+    }
+
+    pub fn size_of_octave(&self) -> Ratio {
+        self.step_size.repeated(self.num_steps_per_octave)
+    }
+
+    /// The number of EDO steps used to approximate each target, in the order the targets were added.
+    pub fn mapped_steps(&self) -> &[i32] {
+        &self.mapped_steps
+    }
+
+    /// The deviation of each approximation from its target, in the order the targets were added.
+    pub fn deviations(&self) -> &[Ratio] {
+        &self.deviations
+    }
+
+    /// The weighted RMS error of all approximations.
+    pub fn error_in_cents(&self) -> f64 {
+        self.error_in_cents
+    }
+
+    /// The weighted RMS error of all approximations in units of the step size.
+    pub fn relative_error(&self) -> f64 {
+        self.error_in_cents / self.step_size.as_cents()
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.consistent
+    }
+
+    /// Classifies the rated EDO using the (possibly stretched) octave of the rating.
+    pub fn temperament(&self) -> EqualTemperament {
+        EqualTemperament::find()
+            .by_edo(self.num_steps_per_octave)
+            .with_size_of_octave(self.size_of_octave())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,4 +874,100 @@ mod tests {
         std::fs::write("edo-notes-1-to-99.txt", &output).unwrap();
         assert_eq!(output, include_str!("../edo-notes-1-to-99.txt"));
     }
+
+    #[test]
+    fn temperament_families() {
+        let note_names = |temperament: &EqualTemperament| {
+            (0..temperament.num_steps_per_octave())
+                .map(|index| temperament.get_heptatonic_name(index.into()))
+                .collect::<Vec<_>>()
+        };
+
+        let mavila = EqualTemperament::find()
+            .with_preference(TemperamentPreference::Mavila)
+            .by_edo(9);
+        assert_eq!(mavila.temperament_type(), TemperamentType::Mavila);
+        assert_eq!(mavila.primary_step(), 1);
+        assert_eq!(mavila.secondary_step(), 2);
+        assert_eq!(mavila.sharpness(), 1);
+        assert_eq!(
+            note_names(&mavila),
+            ["D", "E", "E# / Fb", "F", "G", "A", "B", "B# / Cb", "C"]
+        );
+
+        let diminished = EqualTemperament::find()
+            .with_preference(TemperamentPreference::Diminished)
+            .by_edo(12);
+        assert_eq!(diminished.temperament_type(), TemperamentType::Diminished);
+        assert_eq!(diminished.num_periods(), 4);
+        assert_eq!(diminished.primary_step(), 2);
+        assert_eq!(diminished.secondary_step(), 1);
+        assert_eq!(
+            note_names(&diminished),
+            ["A", "A# / Bb", "B", "C", "C# / Db", "D", "E", "E# / Fb", "F", "G", "G# / Hb", "H"]
+        );
+
+        let blackwood = EqualTemperament::find()
+            .with_preference(TemperamentPreference::Blackwood)
+            .by_edo(15);
+        assert_eq!(blackwood.temperament_type(), TemperamentType::Blackwood);
+        assert_eq!(blackwood.primary_step(), 2);
+        assert_eq!(blackwood.secondary_step(), 1);
+        assert_eq!(
+            note_names(&blackwood)[..6],
+            ["A", "A# / Bb", "B", "C", "C# / Db", "D"]
+        );
+
+        let fallback = EqualTemperament::find()
+            .with_preference(TemperamentPreference::Augmented)
+            .by_edo(16);
+        assert_eq!(fallback.temperament_type(), TemperamentType::Meantone);
+    }
+
+    #[test]
+    fn edo_search() {
+        let search = EqualTemperament::search_edos()
+            .with_target(Ratio::from_float(3.0 / 2.0))
+            .with_target(Ratio::from_float(5.0 / 4.0))
+            .with_target(Ratio::from_float(7.0 / 4.0))
+            .with_target(Ratio::from_float(11.0 / 8.0));
+
+        let absolute = search.in_range(1..=72);
+        assert_eq!(absolute.len(), 72);
+        assert_eq!(absolute[0].num_steps_per_octave(), 72);
+        assert_eq!(absolute[0].mapped_steps(), &[42, 23, 58, 33]);
+
+        let relative = search
+            .clone()
+            .with_ranking(EdoRanking::RelativeError)
+            .in_range(5..=72);
+        assert!(relative[0].relative_error() <= relative[1].relative_error());
+
+        let consistent = search
+            .clone()
+            .with_consistency_required(true)
+            .in_range(10..=31);
+        assert!(consistent.iter().all(EdoRating::is_consistent));
+        assert!(consistent
+            .iter()
+            .all(|rating| rating.num_steps_per_octave() != 12));
+        assert_eq!(consistent[0].num_steps_per_octave(), 31);
+
+        let twelve = &search.in_range(12..=12)[0];
+        assert!(!twelve.is_consistent());
+        assert_eq!(twelve.mapped_steps(), &[7, 4, 10, 6]);
+        assert!((twelve.error_in_cents() - 29.719).abs() < 0.001);
+        assert!((twelve.size_of_octave().as_cents() - 1200.0).abs() < 0.001);
+
+        let stretched = &search
+            .clone()
+            .with_stretch_optimized(true)
+            .in_range(12..=12)[0];
+        assert!(stretched.error_in_cents() < twelve.error_in_cents());
+        assert!((stretched.size_of_octave().as_cents() - 1200.0).abs() > 1.0);
+        assert_eq!(
+            stretched.temperament().size_of_octave(),
+            stretched.size_of_octave()
+        );
+    }
 }
@@ -0,0 +1,191 @@
+use crate::{
+    dto::{ChordDto, ChordRealizationDto, TuneDto},
+    shared::SclCommand,
+    App, CliResult, KbmOptions,
+};
+use structopt::StructOpt;
+use tune::{
+    chord::{Chord, ChordRealization},
+    scala::{Kbm, Scl},
+};
+
+#[derive(StructOpt)]
+pub(crate) struct ChordsOptions {
+    /// Chord to find in the scale, e.g. 4:5:6:7. If omitted, all chords of the given odd limit are checked
+    #[structopt(long = "chord")]
+    chord: Option<Chord>,
+
+    /// Odd limit of the chords to check if no chord is given
+    #[structopt(long = "odd-lim", default_value = "9")]
+    odd_limit: u16,
+
+    /// Number of notes of the chords to check if no chord is given
+    #[structopt(long = "notes", default_value = "3")]
+    num_notes: usize,
+
+    /// Largest acceptable deviation of a single chord tone in cents
+    #[structopt(long = "max-err", default_value = "15")]
+    max_error_in_cents: f64,
+
+    /// Number of realizations to list
+    #[structopt(short = "n", default_value = "10")]
+    num_results: usize,
+
+    #[structopt(flatten)]
+    kbm_params: KbmOptions,
+
+    #[structopt(subcommand)]
+    command: SclCommand,
+}
+
+impl ChordsOptions {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        let scl = self.command.to_scl(None)?;
+        let kbm = self.kbm_params.to_kbm();
+        match &self.chord {
+            Some(chord) => self.print_realizations(app, (&scl, &kbm), chord),
+            None => self.print_playable_chords(app, (&scl, &kbm)),
+        }
+    }
+
+    fn print_realizations(
+        &self,
+        app: &mut App,
+        (scl, kbm): (&Scl, &Kbm),
+        chord: &Chord,
+    ) -> CliResult<()> {
+        let realizations = self.playable_realizations(scl, chord);
+
+        if app.json {
+            let dto = chord_to_dto(kbm, chord, &realizations, self.num_results);
+            app.write_json(TuneDto::Chords(vec![dto]))?;
+            return Ok(());
+        }
+
+        app.writeln(format_args!(
+            "---- Realizations of {} within {:.1}c ----",
+            chord, self.max_error_in_cents
+        ))?;
+        app.writeln(format_args!(""))?;
+
+        if realizations.is_empty() {
+            app.writeln(format_args!("The chord is not playable in this scale"))?;
+            return Ok(());
+        }
+
+        for (rank, realization) in realizations.iter().take(self.num_results).enumerate() {
+            app.write(format_args!(
+                "{:>3}. root {:>3} | degrees",
+                rank + 1,
+                realization.root_degree()
+            ))?;
+            for degree in &realization.degrees {
+                app.write(format_args!(" {:>3}", degree))?;
+            }
+            app.write(format_args!(" | errors"))?;
+            for deviation in &realization.deviations {
+                app.write(format_args!(" {:>+6.1}c", deviation.as_cents()))?;
+            }
+            app.write(format_args!(
+                " | total {:>5.1}c",
+                realization.total_error_in_cents()
+            ))?;
+            app.write(format_args!(" | keys"))?;
+            for key in keys_of(kbm, realization) {
+                app.write(format_args!(" {:>3}", key))?;
+            }
+            app.writeln(format_args!(""))?;
+        }
+
+        Ok(())
+    }
+
+    fn print_playable_chords(&self, app: &mut App, (scl, kbm): (&Scl, &Kbm)) -> CliResult<()> {
+        let chords = Chord::all_of_odd_limit(self.odd_limit, self.num_notes);
+
+        if app.json {
+            let dtos = chords
+                .iter()
+                .map(|chord| (chord, self.playable_realizations(scl, chord)))
+                .filter(|(_, realizations)| !realizations.is_empty())
+                .map(|(chord, realizations)| chord_to_dto(kbm, chord, &realizations, 1))
+                .collect();
+            app.write_json(TuneDto::Chords(dtos))?;
+            return Ok(());
+        }
+
+        app.writeln(format_args!(
+            "---- {}-note chords of the {}-odd-limit within {:.1}c ----",
+            self.num_notes, self.odd_limit, self.max_error_in_cents
+        ))?;
+        app.writeln(format_args!(""))?;
+
+        let mut num_playable_chords = 0;
+        for chord in &chords {
+            let realizations = self.playable_realizations(scl, chord);
+            if let Some(best_realization) = realizations.first() {
+                num_playable_chords += 1;
+                app.writeln(format_args!(
+                    "{:<16} | {:>3} roots | best root {:>3} | total {:>5.1}c",
+                    chord.to_string(),
+                    realizations.len(),
+                    best_realization.root_degree(),
+                    best_realization.total_error_in_cents()
+                ))?;
+            }
+        }
+
+        app.writeln(format_args!(""))?;
+        app.writeln(format_args!(
+            "{} of {} chords are playable",
+            num_playable_chords,
+            chords.len()
+        ))?;
+
+        Ok(())
+    }
+
+    fn playable_realizations(&self, scl: &Scl, chord: &Chord) -> Vec<ChordRealization> {
+        chord
+            .find_in(scl)
+            .into_iter()
+            .filter(|realization| realization.max_error_in_cents() <= self.max_error_in_cents)
+            .collect()
+    }
+}
+
+fn chord_to_dto(
+    kbm: &Kbm,
+    chord: &Chord,
+    realizations: &[ChordRealization],
+    num_results: usize,
+) -> ChordDto {
+    ChordDto {
+        chord: chord.to_string(),
+        num_realizations: realizations.len(),
+        realizations: realizations
+            .iter()
+            .take(num_results)
+            .map(|realization| ChordRealizationDto {
+                root_degree: realization.root_degree(),
+                degrees: realization.degrees.clone(),
+                deviations_in_cents: realization
+                    .deviations
+                    .iter()
+                    .map(|deviation| deviation.as_cents())
+                    .collect(),
+                total_error_in_cents: realization.total_error_in_cents(),
+                keys: keys_of(kbm, realization),
+            })
+            .collect(),
+    }
+}
+
+/// The keys mapped to the degrees of a realization, i.e. the degrees counted from the root key of the [`Kbm`].
+fn keys_of(kbm: &Kbm, realization: &ChordRealization) -> Vec<i32> {
+    realization
+        .degrees
+        .iter()
+        .map(|&degree| kbm.root_key.plus_steps(degree).midi_number())
+        .collect()
+}
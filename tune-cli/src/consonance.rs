@@ -0,0 +1,225 @@
+use crate::{
+    dto::{
+        DissonanceCurveDto, DissonancePointDto, DissonanceScoreDto, DissonanceScoresDto, TuneDto,
+    },
+    shared::SclCommand,
+    App, CliResult,
+};
+use structopt::StructOpt;
+use tune::{
+    consonance::{self, Partial, Spectrum, Timbre},
+    interval,
+    pitch::Pitch,
+    ratio::Ratio,
+    scala::Scl,
+};
+
+const BAR_WIDTH: f64 = 40.0;
+
+#[derive(StructOpt)]
+pub(crate) struct DissonanceOptions {
+    /// Timbre of both tones [sine, triangle, square, saw]
+    #[structopt(long = "timbre", default_value = "saw")]
+    timbre: Timbre,
+
+    /// Number of partials of the timbre
+    #[structopt(long = "partials", default_value = "8")]
+    num_partials: u16,
+
+    /// Explicit partials of both tones given as ratio:amplitude, e.g. 1:1,2.01:0.5,3.03:0.25. Overrides --timbre and --partials
+    #[structopt(long = "spectrum", use_delimiter = true)]
+    spectrum: Option<Vec<Partial>>,
+
+    /// Pitch of the lower tone, e.g. C4 or 261.63Hz
+    #[structopt(long = "base", default_value = "C4")]
+    base: Pitch,
+
+    /// Lower end of the dissonance curve
+    #[structopt(long = "from", default_value = "1")]
+    from: Ratio,
+
+    /// Upper end of the dissonance curve
+    #[structopt(long = "to", default_value = "2")]
+    to: Ratio,
+
+    /// Step size of the dissonance curve
+    #[structopt(long = "step", default_value = "10c")]
+    step: Ratio,
+
+    /// Width of the Gaussian distribution used for calculating the harmonic entropy
+    #[structopt(long = "spread", default_value = "17c")]
+    spread: Ratio,
+
+    /// Score the degrees of the given scale against its root instead of printing a dissonance curve
+    #[structopt(subcommand)]
+    command: Option<SclCommand>,
+}
+
+impl DissonanceOptions {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        let spectrum = match &self.spectrum {
+            Some(partials) => Spectrum::from_partials(partials.clone()),
+            None => self.timbre.spectrum(self.num_partials),
+        };
+        match &self.command {
+            Some(command) => self.print_scl_scores(app, &spectrum, &command.to_scl(None)?),
+            None => self.print_curve(app, &spectrum),
+        }
+    }
+
+    fn print_curve(&self, app: &mut App, spectrum: &Spectrum) -> CliResult<()> {
+        let step_in_cents = self.step.as_cents();
+        if step_in_cents <= 0.0 {
+            return Err(format!("Step size must be positive but was {:#}", self.step).into());
+        }
+
+        let num_steps =
+            ((self.to.as_cents() - self.from.as_cents()) / step_in_cents + 1e-9).floor();
+        let curve = (0..=num_steps.max(0.0) as usize)
+            .map(|step| {
+                let ratio = Ratio::from_cents(self.from.as_cents() + step as f64 * step_in_cents);
+                (ratio, spectrum.dissonance(self.base, ratio))
+            })
+            .collect::<Vec<_>>();
+
+        let is_minimum = |index: usize| {
+            let dissonance = curve[index].1;
+            let previous = index.checked_sub(1).map(|previous| curve[previous].1);
+            let next = curve.get(index + 1).map(|&(_, next)| next);
+            previous.iter().all(|&previous| dissonance < previous)
+                && next.iter().all(|&next| dissonance <= next)
+        };
+        let nearest_ji_interval =
+            |ratio| interval::find_ji_interval(ratio, Ratio::from_cents(step_in_cents));
+
+        if app.json {
+            let points = curve
+                .iter()
+                .enumerate()
+                .map(|(index, &(ratio, dissonance))| DissonancePointDto {
+                    interval_in_cents: ratio.as_cents(),
+                    dissonance,
+                    is_minimum: is_minimum(index),
+                    nearest_ji_interval: if is_minimum(index) {
+                        nearest_ji_interval(ratio).map(|interval| interval.to_string())
+                    } else {
+                        None
+                    },
+                })
+                .collect();
+            app.write_json(TuneDto::DissonanceCurve(DissonanceCurveDto {
+                timbre: self.timbre_name(),
+                num_partials: spectrum.partials().len(),
+                base_pitch_in_hz: self.base.as_hz(),
+                points,
+            }))?;
+            return Ok(());
+        }
+
+        let max_dissonance = curve
+            .iter()
+            .map(|&(_, dissonance)| dissonance)
+            .fold(0.0, f64::max);
+
+        self.print_header(app, spectrum)?;
+        app.writeln(format_args!(""))?;
+
+        for (index, &(ratio, dissonance)) in curve.iter().enumerate() {
+            let bar_length = if max_dissonance > 0.0 {
+                (dissonance / max_dissonance * BAR_WIDTH).round() as usize
+            } else {
+                0
+            };
+
+            app.write(format_args!(
+                "{:>8.1}c | {:>7.4} | {:<width$} |",
+                ratio.as_cents(),
+                dissonance,
+                "#".repeat(bar_length),
+                width = BAR_WIDTH as usize,
+            ))?;
+            if is_minimum(index) {
+                app.write(format_args!(" minimum"))?;
+                if let Some(interval) = nearest_ji_interval(ratio) {
+                    app.write(format_args!(" near {}", interval))?;
+                }
+            }
+            app.writeln(format_args!(""))?;
+        }
+
+        Ok(())
+    }
+
+    fn print_scl_scores(&self, app: &mut App, spectrum: &Spectrum, scl: &Scl) -> CliResult<()> {
+        let scores = (0..=scl.size() as i32)
+            .map(|degree| {
+                let ratio = scl.relative_pitch_of(degree);
+                DissonanceScoreDto {
+                    degree,
+                    interval_in_cents: ratio.as_cents(),
+                    dissonance: spectrum.dissonance(self.base, ratio),
+                    harmonic_entropy: consonance::harmonic_entropy(ratio, self.spread, 10000),
+                    nearest_ji_interval: interval::find_ji_interval(ratio, self.spread)
+                        .map(|interval| interval.to_string()),
+                }
+            })
+            .collect::<Vec<_>>();
+        let mean_dissonance = scores[1..]
+            .iter()
+            .map(|score| score.dissonance)
+            .sum::<f64>()
+            / scl.size() as f64;
+
+        if app.json {
+            app.write_json(TuneDto::DissonanceScores(DissonanceScoresDto {
+                timbre: self.timbre_name(),
+                num_partials: spectrum.partials().len(),
+                base_pitch_in_hz: self.base.as_hz(),
+                degrees: scores,
+                mean_dissonance,
+            }))?;
+            return Ok(());
+        }
+
+        self.print_header(app, spectrum)?;
+        app.writeln(format_args!(""))?;
+
+        app.writeln(format_args!(
+            "  {:>6} | {:>10} | {:>10} | {:>7} | {}",
+            "Degree", "Interval", "Dissonance", "Entropy", "Nearest JI interval"
+        ))?;
+
+        for score in &scores {
+            app.write(format_args!(
+                "  {:>6} | {:>9.3}c | {:>10.4} | {:>7.4} |",
+                score.degree, score.interval_in_cents, score.dissonance, score.harmonic_entropy,
+            ))?;
+            if let Some(interval) = &score.nearest_ji_interval {
+                app.write(format_args!(" {}", interval))?;
+            }
+            app.writeln(format_args!(""))?;
+        }
+
+        app.writeln(format_args!(""))?;
+        app.writeln(format_args!("Mean dissonance: {:.4}", mean_dissonance))?;
+
+        Ok(())
+    }
+
+    fn print_header(&self, app: &mut App, spectrum: &Spectrum) -> CliResult<()> {
+        app.writeln(format_args!(
+            "---- Dissonance of {} timbre ({} partials) at {:.3} Hz ----",
+            self.timbre_name(),
+            spectrum.partials().len(),
+            self.base.as_hz()
+        ))?;
+        Ok(())
+    }
+
+    fn timbre_name(&self) -> String {
+        match self.spectrum {
+            Some(_) => "Custom".to_owned(),
+            None => format!("{:?}", self.timbre),
+        }
+    }
+}
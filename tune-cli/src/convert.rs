@@ -0,0 +1,372 @@
+use crate::{
+    dto::{ScaleDto, ScaleItemDto, TuneDocumentDto, TuneDto},
+    shared, App, CliResult,
+};
+use std::{
+    ffi::OsStr,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use structopt::StructOpt;
+use tune::{
+    key::PianoKey,
+    mts::{DeviceId, SingleNoteTuningChange, SingleNoteTuningChangeMessage},
+    note::NoteLetter,
+    pitch::{Pitch, ReferencePitch},
+    ratio::Ratio,
+    scala::{Ascl, Kbm, KbmImportError, Scl},
+};
+
+#[derive(StructOpt)]
+pub(crate) struct ConvertOptions {
+    /// Input file. The format is inferred from the extension [scl, ascl, kbm, json, syx]. Can be repeated, e.g. to combine an scl and a kbm file
+    #[structopt(long = "from", required = true, number_of_values = 1)]
+    input_files: Vec<PathBuf>,
+
+    /// Output file. The format is inferred from the extension. A bare format name, e.g. json, writes to stdout. Defaults to json if --json is given
+    #[structopt(long = "to")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Format {
+    Scl,
+    Ascl,
+    Kbm,
+    Json,
+    Syx,
+}
+
+impl Format {
+    fn of_file(file_name: &Path) -> Result<Self, String> {
+        file_name
+            .extension()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format!("Cannot infer the format of {}", file_name.display()))?
+            .to_lowercase()
+            .parse()
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scl" => Ok(Format::Scl),
+            "ascl" => Ok(Format::Ascl),
+            "kbm" => Ok(Format::Kbm),
+            "json" => Ok(Format::Json),
+            "syx" => Ok(Format::Syx),
+            _ => Err(format!(
+                "Unknown format '{}'. Use scl, ascl, kbm, json or syx",
+                s
+            )),
+        }
+    }
+}
+
+/// Everything known about the tuning after reading all input files.
+#[derive(Default)]
+struct TuningData {
+    scl: Option<Scl>,
+    kbm: Option<Kbm>,
+    ascl: Option<Ascl>,
+    table: Option<ScaleDto>,
+}
+
+impl ConvertOptions {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        let mut data = TuningData::default();
+        for input_file in &self.input_files {
+            data.read(input_file)?;
+        }
+
+        let output = match (&self.output, app.json) {
+            (Some(output), _) => output,
+            (None, true) => Path::new("json"),
+            (None, false) => return Err("No output given. Use --to or --json".to_owned().into()),
+        };
+        let (target_format, target_file) = match output.to_str().map(str::parse) {
+            Some(Ok(format)) => (format, None),
+            _ => (Format::of_file(output)?, Some(output)),
+        };
+        if app.json && target_format != Format::Json {
+            return Err("--json can only be combined with JSON output"
+                .to_owned()
+                .into());
+        }
+
+        let output = match target_format {
+            Format::Scl => data.to_scl(app)?.export().to_string().into_bytes(),
+            Format::Ascl => data.to_ascl(app)?.export().to_string().into_bytes(),
+            Format::Kbm => data.to_kbm(app)?.export().to_string().into_bytes(),
+            Format::Json => {
+                let document = TuneDocumentDto::new(TuneDto::Scale(data.to_table()?));
+                let mut json = serde_json::to_string_pretty(&document).map_err(io::Error::from)?;
+                json.push('\n');
+                json.into_bytes()
+            }
+            Format::Syx => data.to_sysex(app)?,
+        };
+
+        match target_file {
+            Some(target_file) => fs::write(target_file, output)
+                .map_err(|err| format!("Could not write output file: {}", err))?,
+            None if target_format == Format::Syx => {
+                for byte in output {
+                    app.writeln(format_args!("0x{:02x}", byte))?;
+                }
+            }
+            None => app.write(format_args!("{}", String::from_utf8_lossy(&output)))?,
+        }
+
+        Ok(())
+    }
+}
+
+impl TuningData {
+    fn read(&mut self, file_name: &PathBuf) -> CliResult<()> {
+        let format = Format::of_file(file_name)?;
+        let already_given = match format {
+            Format::Scl | Format::Ascl => self.scl.is_some(),
+            Format::Kbm => self.kbm.is_some() && self.ascl.is_none(),
+            Format::Json | Format::Syx => self.table.is_some(),
+        };
+        if already_given {
+            return Err(format!(
+                "{} provides information that has already been read from another file",
+                file_name.display()
+            )
+            .into());
+        }
+
+        match format {
+            Format::Scl => self.scl = Some(shared::import_scl_file(file_name)?),
+            Format::Ascl => {
+                let file = open(file_name)?;
+                let ascl =
+                    Ascl::import(file).map_err(|err| shared::describe_scl_import_error(&err))?;
+                self.scl = Some(ascl.scl.clone());
+                self.kbm = self.kbm.take().or_else(|| Some(ascl.kbm.clone()));
+                self.ascl = Some(ascl);
+            }
+            Format::Kbm => {
+                let file = open(file_name)?;
+                self.kbm = Some(Kbm::import(file).map_err(|err| describe_kbm_import_error(&err))?);
+            }
+            Format::Json => self.table = Some(ScaleDto::read(open(file_name)?)?),
+            Format::Syx => {
+                let bytes = fs::read(file_name)
+                    .map_err(|err| format!("Could not read syx file: {}", err))?;
+                let message = SingleNoteTuningChangeMessage::from_sysex_bytes(&bytes)
+                    .map_err(|err| format!("Unsupported syx file ({:?})", err))?;
+                let items = message
+                    .retuned_notes()
+                    .iter()
+                    .map(|tuning_change| ScaleItemDto {
+                        key_midi_number: i32::from(tuning_change.source_note()),
+                        pitch_in_hz: tuning_change.target_pitch().as_hz(),
+                        scale_degree: None,
+                        period_index: None,
+                        note_name: None,
+                    })
+                    .collect::<Vec<_>>();
+                // The root is not part of the message. Choose the key closest to the middle of the keyboard
+                let root = items
+                    .iter()
+                    .min_by_key(|item| (item.key_midi_number - 60).abs())
+                    .ok_or_else(|| "The syx file does not retune any note".to_owned())?;
+                self.table = Some(ScaleDto {
+                    root_key_midi_number: root.key_midi_number,
+                    root_pitch_in_hz: root.pitch_in_hz,
+                    items,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn to_scl(&self, app: &mut App) -> CliResult<Scl> {
+        if self.ascl.is_some() {
+            app.errln(format_args!(
+                "Warning: The note names, reference pitch and metadata of the ascl file will be lost"
+            ))?;
+        } else if self.kbm.is_some() {
+            app.errln(format_args!(
+                "Warning: The keyboard mapping is not part of the scl format and will be lost"
+            ))?;
+        }
+        self.scl_or_derived_from_table(app)
+    }
+
+    fn to_ascl(&self, app: &mut App) -> CliResult<Ascl> {
+        let scl = self.scl_or_derived_from_table(app)?;
+        if (scl.period().as_octaves() - 1.0).abs() > 1e-6 {
+            app.errln(format_args!(
+                "Warning: The period is not an octave. The reference octave will count periods instead"
+            ))?;
+        }
+
+        let kbm = match self.kbm.clone() {
+            Some(kbm) => kbm,
+            None => match &self.table {
+                Some(table) => kbm_of_table(table),
+                None => {
+                    app.errln(format_args!(
+                        "Warning: No keyboard mapping given. Assuming C4 at its usual pitch"
+                    ))?;
+                    Kbm::root_at(NoteLetter::C.in_octave(4))
+                }
+            },
+        };
+
+        Ok(match &self.ascl {
+            Some(ascl) if ascl.note_names.len() == scl.size() => Ascl {
+                scl,
+                kbm,
+                ..ascl.clone()
+            },
+            _ => Ascl::new(scl, kbm).with_heptatonic_note_names(),
+        })
+    }
+
+    fn to_kbm(&self, app: &mut App) -> CliResult<Kbm> {
+        if let Some(kbm) = &self.kbm {
+            return Ok(kbm.clone());
+        }
+        match &self.table {
+            Some(table) => {
+                app.errln(format_args!(
+                    "Warning: Only the pitch of the root key of the table is kept"
+                ))?;
+                Ok(kbm_of_table(table))
+            }
+            None => Err("No keyboard mapping given. Add a kbm or ascl file"
+                .to_owned()
+                .into()),
+        }
+    }
+
+    fn to_table(&self) -> CliResult<ScaleDto> {
+        if let Some(table) = &self.table {
+            return Ok(table.clone());
+        }
+
+        match (&self.scl, &self.kbm) {
+            (Some(scl), Some(kbm)) => {
+                let ascl = match &self.ascl {
+                    Some(ascl) if ascl.note_names.len() == scl.size() => Ascl {
+                        kbm: kbm.clone(),
+                        ..ascl.clone()
+                    },
+                    _ => Ascl::new(scl.clone(), kbm.clone()).with_heptatonic_note_names(),
+                };
+                Ok(crate::scale_dto(&ascl))
+            }
+            (Some(_), None) => Err(
+                "Absolute pitches require a keyboard mapping. Add a kbm file or use an ascl file"
+                    .to_owned()
+                    .into(),
+            ),
+            (None, _) => Err("No scale given. Add an scl, ascl, json or syx file"
+                .to_owned()
+                .into()),
+        }
+    }
+
+    fn to_sysex(&self, app: &mut App) -> CliResult<Vec<u8>> {
+        let table = self.to_table()?;
+
+        let (in_range, out_of_range): (Vec<_>, Vec<_>) = table
+            .items
+            .iter()
+            .partition(|item| (1..128).contains(&item.key_midi_number));
+        if !out_of_range.is_empty() {
+            app.errln(format_args!(
+                "Warning: {} keys outside of the MIDI range 1..127 will be lost",
+                out_of_range.len()
+            ))?;
+        }
+
+        let tuning_changes = in_range.iter().map(|item| {
+            let approx = Pitch::from_hz(item.pitch_in_hz).find_in(&());
+            SingleNoteTuningChange::new(
+                item.key_midi_number as u8,
+                approx.approx_value.midi_number(),
+                approx.deviation,
+            )
+        });
+        let message = SingleNoteTuningChangeMessage::from_tuning_changes(
+            tuning_changes,
+            DeviceId::broadcast(),
+            0,
+        )
+        .map_err(|err| format!("Could not apply single note tuning ({:?})", err))?;
+
+        if !message.out_of_range_notes().is_empty() {
+            app.errln(format_args!(
+                "Warning: {} pitches outside of the MIDI range will be lost",
+                message.out_of_range_notes().len()
+            ))?;
+        }
+
+        Ok(message.sysex_bytes().to_vec())
+    }
+
+    fn scl_or_derived_from_table(&self, app: &mut App) -> CliResult<Scl> {
+        if let Some(scl) = &self.scl {
+            return Ok(scl.clone());
+        }
+        let table = self
+            .table
+            .as_ref()
+            .ok_or_else(|| "No scale given. Add an scl, ascl, json or syx file".to_owned())?;
+
+        app.errln(format_args!(
+            "Warning: The table is converted into a non-repeating scale with one degree per key"
+        ))?;
+        let root_pitch = Pitch::from_hz(table.root_pitch_in_hz);
+        let mut builder = Scl::builder();
+        for item in table
+            .items
+            .iter()
+            .filter(|item| item.key_midi_number > table.root_key_midi_number)
+        {
+            builder = builder.push_ratio(Ratio::between_pitches(
+                root_pitch,
+                Pitch::from_hz(item.pitch_in_hz),
+            ));
+        }
+        Ok(builder.build_with_description("Converted from an absolute table")?)
+    }
+}
+
+fn kbm_of_table(table: &ScaleDto) -> Kbm {
+    let root_key = PianoKey::from_midi_number(table.root_key_midi_number);
+    Kbm {
+        ref_pitch: ReferencePitch::from_key_and_pitch(
+            root_key,
+            Pitch::from_hz(table.root_pitch_in_hz),
+        ),
+        root_key,
+    }
+}
+
+fn open(file_name: &Path) -> Result<File, String> {
+    File::open(file_name).map_err(|err| format!("Could not read {}: {}", file_name.display(), err))
+}
+
+fn describe_kbm_import_error(err: &KbmImportError) -> String {
+    match err {
+        KbmImportError::IoError(err) => format!("Could not read kbm file: {}", err),
+        KbmImportError::ParseError { line_number, kind } => format!(
+            "Could not parse kbm file at line {} ({:?})",
+            line_number, kind
+        ),
+        KbmImportError::StructuralError(err) => format!("Unsupported kbm file ({:?})", err),
+    }
+}
@@ -1,13 +1,94 @@
 use io::Read;
+use schemars::{
+    schema::{RootSchema, Schema},
+    JsonSchema,
+};
 use serde::{Deserialize, Serialize};
 use std::io;
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Version of the JSON documents written by `tune`. Documents without a version number are treated as version 1.
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct TuneDocumentDto {
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub content: TuneDto,
+}
+
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+impl TuneDocumentDto {
+    pub fn new(content: TuneDto) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            content,
+        }
+    }
+
+    pub fn read(input: impl Read) -> io::Result<TuneDocumentDto> {
+        let document: TuneDocumentDto = serde_json::from_reader(input)?;
+        if document.schema_version > SCHEMA_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Unsupported schema version {} (latest supported version is {})",
+                    document.schema_version, SCHEMA_VERSION
+                ),
+            ));
+        }
+        Ok(document)
+    }
+}
+
+/// Generates the JSON Schema of [`TuneDocumentDto`].
+pub fn json_schema() -> RootSchema {
+    let mut schema = schemars::schema_for!(TuneDocumentDto);
+
+    // The variants of the flattened TuneDto live next to the schema_version field
+    let variants = schema
+        .schema
+        .subschemas
+        .as_mut()
+        .and_then(|subschemas| subschemas.one_of.as_mut());
+    for variant in variants.into_iter().flatten() {
+        if let Schema::Object(variant) = variant {
+            if let Some(object) = &mut variant.object {
+                object.additional_properties = None;
+            }
+        }
+    }
+
+    schema
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub enum TuneDto {
     Scale(ScaleDto),
+    Scl(SclDto),
+    Kbm(KbmDto),
+    Edo(EdoDto),
+    EdoSearch(EdoSearchDto),
+    DissonanceCurve(DissonanceCurveDto),
+    DissonanceScores(DissonanceScoresDto),
+    Chords(Vec<ChordDto>),
+    Comparison(Vec<ComparisonItemDto>),
+    Dupes(Vec<DupeGroupDto>),
+    Archive(Vec<ArchiveEntryDto>),
+    Mts(Vec<MidiMessageDto>),
+    Devices(DevicesDto),
+    Lumatone(LumatoneDto),
+    LinnStrument(LinnStrumentDto),
+    Layouts(LayoutsDto),
+    Presets(Vec<PresetDto>),
+    PcSetAnalysis(PcSetAnalysisDto),
+    SetClasses(SetClassesDto),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
 pub struct ScaleDto {
     pub root_key_midi_number: i32,
     pub root_pitch_in_hz: f64,
@@ -16,16 +97,315 @@ pub struct ScaleDto {
 
 impl ScaleDto {
     pub fn read(input: impl Read) -> io::Result<ScaleDto> {
-        let input: TuneDto = serde_json::from_reader(input)?;
-
-        match input {
+        match TuneDocumentDto::read(input)?.content {
             TuneDto::Scale(scale) => Ok(scale),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Expected a JSON document containing a Scale",
+            )),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ScaleItemDto {
     pub key_midi_number: i32,
     pub pitch_in_hz: f64,
+    /// Degree within the period, starting at 0 for the root key
+    #[serde(default)]
+    pub scale_degree: Option<i32>,
+    /// Number of periods between the root key and this key
+    #[serde(default)]
+    pub period_index: Option<i32>,
+    #[serde(default)]
+    pub note_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SclDto {
+    pub description: String,
+    pub period_in_cents: f64,
+    pub items: Vec<SclItemDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SclItemDto {
+    pub degree: i32,
+    pub pitch_in_cents: f64,
+    /// Only present if the degree is an exact fraction
+    pub numer: Option<u64>,
+    /// Only present if the degree is an exact fraction
+    pub denom: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct KbmDto {
+    pub root_key_midi_number: i32,
+    pub ref_key_midi_number: i32,
+    pub ref_pitch_in_hz: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EdoDto {
+    pub num_steps_per_octave: u16,
+    pub temperaments: Vec<EdoTemperamentDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EdoTemperamentDto {
+    pub temperament_type: String,
+    pub num_periods: u16,
+    pub num_cycles: u16,
+    pub num_steps_per_fifth: u16,
+    pub fifth_in_cents: f64,
+    pub primary_step: i16,
+    pub secondary_step: i16,
+    pub sharpness: i16,
+    /// Rows of the keyboard layout from top to bottom
+    pub keyboard_layout: Vec<Vec<i32>>,
+    pub steps: Vec<EdoStepDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EdoStepDto {
+    pub index: u16,
+    pub heptatonic_name: String,
+    pub notation: Option<String>,
+    pub ji_intervals: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EdoSearchDto {
+    pub targets_in_cents: Vec<f64>,
+    pub ratings: Vec<EdoRatingDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EdoRatingDto {
+    pub num_steps_per_octave: u16,
+    pub octave_in_cents: f64,
+    pub error_in_cents: f64,
+    pub relative_error: f64,
+    pub is_consistent: bool,
+    pub mapped_steps: Vec<i32>,
+    pub deviations_in_cents: Vec<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DissonanceCurveDto {
+    pub timbre: String,
+    pub num_partials: usize,
+    pub base_pitch_in_hz: f64,
+    pub points: Vec<DissonancePointDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DissonancePointDto {
+    pub interval_in_cents: f64,
+    pub dissonance: f64,
+    pub is_minimum: bool,
+    pub nearest_ji_interval: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DissonanceScoresDto {
+    pub timbre: String,
+    pub num_partials: usize,
+    pub base_pitch_in_hz: f64,
+    pub degrees: Vec<DissonanceScoreDto>,
+    pub mean_dissonance: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DissonanceScoreDto {
+    pub degree: i32,
+    pub interval_in_cents: f64,
+    pub dissonance: f64,
+    pub harmonic_entropy: f64,
+    pub nearest_ji_interval: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ChordDto {
+    pub chord: String,
+    pub num_realizations: usize,
+    pub realizations: Vec<ChordRealizationDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ChordRealizationDto {
+    pub root_degree: i32,
+    pub degrees: Vec<i32>,
+    pub deviations_in_cents: Vec<f64>,
+    pub total_error_in_cents: f64,
+    pub keys: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ComparisonItemDto {
+    pub key_midi_number: i32,
+    pub source_index: i32,
+    pub pitch_in_hz: f64,
+    pub nearest_fraction: Option<NearestFractionDto>,
+    pub interval_name: Option<String>,
+    pub target_key_midi_number: i32,
+    /// Index of the target key relative to the root of the target scale (`diff` only)
+    pub target_index: Option<i32>,
+    /// Name of the nearest 12-EDO note (`dump` only)
+    pub target_note_name: Option<String>,
+    pub deviation_in_cents: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct NearestFractionDto {
+    pub numer: u16,
+    pub denom: u16,
+    pub deviation_in_cents: f64,
+    pub num_octaves: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DupeGroupDto {
+    pub fingerprint: String,
+    pub size: usize,
+    pub period_in_cents: f64,
+    pub members: Vec<DupeMemberDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DupeMemberDto {
+    pub file_name: String,
+    pub mode: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveEntryDto {
+    pub file_name: String,
+    pub description: String,
+    pub size: usize,
+    pub period_in_cents: f64,
+    pub prime_limit: Option<u32>,
+    pub is_mos: bool,
+    pub edo: Option<u16>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct MidiMessageDto {
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DevicesDto {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LumatoneDto {
+    pub notes_per_channel: u16,
+    pub keys: Vec<LumatoneKeyDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LumatoneKeyDto {
+    pub board: i16,
+    /// Index of the key within its board, counted row by row from the top
+    pub index: usize,
+    /// Only present if the key is enabled
+    pub note: Option<i32>,
+    /// Only present if the key is enabled
+    pub channel: Option<i32>,
+    pub color: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LinnStrumentDto {
+    pub column_offset: i32,
+    pub row_offset: i32,
+    pub lowest_note: i32,
+    /// Note numbers of all pads from the top row to the bottom row. Numbers outside of 0..128 cannot be played
+    pub notes: Vec<Vec<i32>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LayoutsDto {
+    pub num_steps_per_octave: u16,
+    pub num_columns: i16,
+    pub num_rows: i16,
+    pub targets_in_cents: Vec<f64>,
+    pub ratings: Vec<LayoutRatingDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LayoutRatingDto {
+    pub primary_step: i16,
+    pub secondary_step: i16,
+    pub score: f64,
+    pub coverage: f64,
+    pub duplicate_fraction: f64,
+    /// Location (x, y) of the octave relative to the root key. Only present if it can be reached within the grid
+    pub octave_location: Option<(i16, i16)>,
+    /// Number of keys to move to play each target. Absent entries cannot be reached within the grid
+    pub target_distances: Vec<Option<u16>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PresetDto {
+    pub name: String,
+    pub description: String,
+    pub definition: PresetDefinitionDto,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub enum PresetDefinitionDto {
+    FifthsChain {
+        num_fifths_below_root: u16,
+        fifths: Vec<TemperedFifthDto>,
+    },
+    /// Fractions in ascending order, excluding the unison and including the period
+    Just { fractions: Vec<FractionDto> },
+}
+
+/// A 3/2 fifth widened or, if `numer` is negative, narrowed by `numer/denom` of a comma
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TemperedFifthDto {
+    pub numer: i32,
+    pub denom: u32,
+    /// One of `syntonic` (81/80), `pythagorean` (531441/524288) or `schisma` (32805/32768)
+    pub comma: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FractionDto {
+    pub numer: u32,
+    pub denom: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PcSetAnalysisDto {
+    pub num_steps_per_octave: u16,
+    pub pitch_classes: Vec<u16>,
+    pub note_names: Vec<String>,
+    pub normal_form: Vec<u16>,
+    pub prime_form: Vec<u16>,
+    pub interval_class_vector: Vec<u16>,
+    pub is_inversionally_symmetric: bool,
+    pub complement: Vec<u16>,
+    pub complement_prime_form: Vec<u16>,
+    /// Prime forms of all set classes sharing the interval-class vector
+    pub z_related_set_classes: Vec<Vec<u16>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SetClassesDto {
+    pub num_steps_per_octave: u16,
+    pub cardinality: usize,
+    pub set_classes: Vec<SetClassDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SetClassDto {
+    pub prime_form: Vec<u16>,
+    pub interval_class_vector: Vec<u16>,
+    pub is_z_related: bool,
 }
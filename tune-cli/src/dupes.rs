@@ -0,0 +1,123 @@
+use crate::{
+    dto::{DupeGroupDto, DupeMemberDto, TuneDto},
+    shared, App, CliResult,
+};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use tune::{archive::Archive, ratio::Ratio, scala::Scl};
+
+#[derive(StructOpt)]
+pub(crate) struct FindDupesOptions {
+    /// Largest acceptable deviation of two pitches considered equal
+    #[structopt(long = "tolerance", default_value = "0.01c")]
+    tolerance: Ratio,
+
+    /// Directory to search for scl files recursively
+    dir: PathBuf,
+}
+
+struct DupeGroup {
+    representative: Scl,
+    members: Vec<(PathBuf, i32)>,
+}
+
+impl FindDupesOptions {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        let archive = Archive::import_dir(&self.dir)?;
+        for (file_name, err) in archive.errors() {
+            app.errln(format_args!(
+                "Skipping {}: {}",
+                display_path(file_name),
+                shared::describe_scl_import_error(err)
+            ))?;
+        }
+
+        let mut groups = Vec::<DupeGroup>::new();
+        for entry in archive.entries() {
+            let relative_file_name = entry
+                .path
+                .strip_prefix(&self.dir)
+                .unwrap_or(&entry.path)
+                .to_owned();
+
+            let matching_group = groups.iter_mut().find_map(|group| {
+                entry
+                    .scl
+                    .find_mode_of(&group.representative, self.tolerance)
+                    .map(|mode| (group, mode))
+            });
+            match matching_group {
+                Some((group, mode)) => group.members.push((relative_file_name, mode)),
+                None => groups.push(DupeGroup {
+                    representative: entry.scl.clone(),
+                    members: vec![(relative_file_name, 0)],
+                }),
+            }
+        }
+
+        let dupe_groups = groups
+            .iter()
+            .filter(|group| group.members.len() > 1)
+            .collect::<Vec<_>>();
+
+        if app.json {
+            let dtos = dupe_groups
+                .iter()
+                .map(|group| DupeGroupDto {
+                    fingerprint: format!("{:016x}", group.representative.fingerprint()),
+                    size: group.representative.size(),
+                    period_in_cents: group.representative.period().as_cents(),
+                    members: group
+                        .members
+                        .iter()
+                        .map(|(file_name, mode)| DupeMemberDto {
+                            file_name: display_path(file_name),
+                            mode: *mode,
+                        })
+                        .collect(),
+                })
+                .collect();
+            app.write_json(TuneDto::Dupes(dtos))?;
+            return Ok(());
+        }
+
+        app.writeln(format_args!(
+            "---- Equivalent scales in {} ----",
+            self.dir.display()
+        ))?;
+
+        for (index, group) in dupe_groups.iter().enumerate() {
+            app.writeln(format_args!(""))?;
+            app.writeln(format_args!(
+                "[{}] fingerprint {:016x} | {} notes | period {:#}",
+                index + 1,
+                group.representative.fingerprint(),
+                group.representative.size(),
+                group.representative.period(),
+            ))?;
+            for (file_name, mode) in &group.members {
+                app.write(format_args!("  {}", display_path(file_name)))?;
+                if *mode != 0 {
+                    app.write(format_args!(" (mode {})", mode))?;
+                }
+                app.writeln(format_args!(""))?;
+            }
+        }
+
+        app.writeln(format_args!(""))?;
+        app.writeln(format_args!(
+            "{} groups of equivalent scales among {} files",
+            dupe_groups.len(),
+            archive.entries().len() + archive.errors().len()
+        ))?;
+
+        Ok(())
+    }
+}
+
+pub(crate) fn display_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
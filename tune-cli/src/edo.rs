@@ -1,21 +1,218 @@
+use crate::{
+    dto::{EdoDto, EdoRatingDto, EdoSearchDto, EdoStepDto, EdoTemperamentDto, TuneDto},
+    App, CliResult,
+};
 use std::io;
+use structopt::StructOpt;
 use tune::{
     key::{Keyboard, PianoKey},
-    ratio::Ratio,
-    temperament::{EqualTemperament, TemperamentType},
+    notation::{EdoNotation, Notation, NotationStyle},
+    ratio::{Ratio, RatioExpression, RatioExpressionVariant},
+    svg::KeyboardSvg,
+    temperament::{EdoRanking, EqualTemperament},
 };
 
-pub fn print_info(mut dst: impl io::Write, num_steps_per_octave: u16) -> io::Result<()> {
-    let temperament = EqualTemperament::find().by_edo(num_steps_per_octave);
-    print_temperament(&mut dst, &temperament)?;
-    match temperament.temperament_type() {
-        TemperamentType::Meantone => {
-            if let Some(porcupine) = temperament.as_porcupine() {
-                writeln!(dst)?;
-                print_temperament(dst, &porcupine)?;
+#[derive(StructOpt)]
+pub(crate) struct FindEdoOptions {
+    /// Smallest EDO to consider
+    #[structopt(long = "min", default_value = "1")]
+    min_num_steps_per_octave: u16,
+
+    /// Largest EDO to consider
+    #[structopt(long = "max", default_value = "99")]
+    max_num_steps_per_octave: u16,
+
+    /// Weight the error of each target by its inverse Tenney height (targets must be fractions)
+    #[structopt(long = "weighted")]
+    weighted: bool,
+
+    /// Optimize the octave stretch of each EDO
+    #[structopt(long = "stretch")]
+    stretch_optimized: bool,
+
+    /// Only list EDOs that approximate the targets consistently
+    #[structopt(long = "consistent")]
+    consistency_required: bool,
+
+    /// Rank by the error relative to the step size instead of by the absolute error
+    #[structopt(long = "rel")]
+    relative_error: bool,
+
+    /// Number of EDOs to list
+    #[structopt(short = "n", default_value = "10")]
+    num_results: usize,
+
+    /// Target intervals, e.g. 3/2 5/4 7/4 11/8
+    #[structopt(required = true)]
+    targets: Vec<RatioExpression>,
+}
+
+impl FindEdoOptions {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        let mut search = EqualTemperament::search_edos()
+            .with_stretch_optimized(self.stretch_optimized)
+            .with_consistency_required(self.consistency_required)
+            .with_ranking(if self.relative_error {
+                EdoRanking::RelativeError
+            } else {
+                EdoRanking::AbsoluteError
+            });
+
+        for &target in &self.targets {
+            let weight = if self.weighted {
+                tenney_weight(target).ok_or_else(|| {
+                    format!(
+                        "Weighted search requires fractional targets (e.g. 5/4) but target was {:#}",
+                        target.ratio()
+                    )
+                })?
+            } else {
+                1.0
+            };
+            search = search.with_weighted_target(target.ratio(), weight);
+        }
+
+        let ratings =
+            search.in_range(self.min_num_steps_per_octave..=self.max_num_steps_per_octave);
+
+        if app.json {
+            let ratings = ratings
+                .iter()
+                .take(self.num_results)
+                .map(|rating| EdoRatingDto {
+                    num_steps_per_octave: rating.num_steps_per_octave(),
+                    octave_in_cents: rating.size_of_octave().as_cents(),
+                    error_in_cents: rating.error_in_cents(),
+                    relative_error: rating.relative_error(),
+                    is_consistent: rating.is_consistent(),
+                    mapped_steps: rating.mapped_steps().to_vec(),
+                    deviations_in_cents: rating
+                        .deviations()
+                        .iter()
+                        .map(|deviation| deviation.as_cents())
+                        .collect(),
+                })
+                .collect();
+            app.write_json(TuneDto::EdoSearch(EdoSearchDto {
+                targets_in_cents: self
+                    .targets
+                    .iter()
+                    .map(|target| target.ratio().as_cents())
+                    .collect(),
+                ratings,
+            }))?;
+            return Ok(());
+        }
+
+        app.write(format_args!("---- EDOs approximating"))?;
+        for target in &self.targets {
+            app.write(format_args!(" {:#}", target.ratio()))?;
+        }
+        app.writeln(format_args!(" ----"))?;
+        app.writeln(format_args!(""))?;
+
+        for (rank, rating) in ratings.iter().take(self.num_results).enumerate() {
+            app.write(format_args!(
+                "{:>3}. {:>3}-EDO | octave {:>#9.1} | error {:>6.2}c ({:>5.1}%) | {:<12} |",
+                rank + 1,
+                rating.num_steps_per_octave(),
+                rating.size_of_octave(),
+                rating.error_in_cents(),
+                rating.relative_error() * 100.0,
+                if rating.is_consistent() {
+                    "consistent"
+                } else {
+                    "inconsistent"
+                },
+            ))?;
+            for (mapped_steps, deviation) in rating.mapped_steps().iter().zip(rating.deviations()) {
+                app.write(format_args!(
+                    " {} ({:+.1}c)",
+                    mapped_steps,
+                    deviation.as_cents()
+                ))?;
             }
+            app.writeln(format_args!(""))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn tenney_weight(target: RatioExpression) -> Option<f64> {
+    let (numer, denom) = match target.variant() {
+        RatioExpressionVariant::Float { float_value } => (float_value, 1.0),
+        RatioExpressionVariant::Fraction { numer, denom }
+        | RatioExpressionVariant::Product { numer, denom } => (numer, denom),
+        _ => return None,
+    };
+    if numer.fract() == 0.0 && denom.fract() == 0.0 {
+        Some(1.0 / (numer * denom).log2().max(1.0))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn to_dto(num_steps_per_octave: u16, notation: Option<NotationStyle>) -> EdoDto {
+    let temperament = EqualTemperament::find().by_edo(num_steps_per_octave);
+    let mut temperaments = vec![temperament_to_dto(&temperament, notation)];
+    for family in EqualTemperament::find().all_by_edo(num_steps_per_octave) {
+        if family.temperament_type() != temperament.temperament_type() {
+            temperaments.push(temperament_to_dto(&family, notation));
+        }
+    }
+
+    EdoDto {
+        num_steps_per_octave,
+        temperaments,
+    }
+}
+
+fn temperament_to_dto(
+    temperament: &EqualTemperament,
+    notation: Option<NotationStyle>,
+) -> EdoTemperamentDto {
+    let notation = aligned_notation(temperament, notation);
+    let steps = (0..temperament.num_steps_per_octave())
+        .map(|index| EdoStepDto {
+            index,
+            heptatonic_name: temperament.get_heptatonic_name(i32::from(index)),
+            notation: notation.as_ref().map(|(notation, location_of_d)| {
+                notation.format_step(i32::from(index) + location_of_d)
+            }),
+            ji_intervals: ji_intervals_at(temperament, index)
+                .into_iter()
+                .map(str::to_owned)
+                .collect(),
+        })
+        .collect();
+
+    EdoTemperamentDto {
+        temperament_type: temperament.temperament_type().to_string(),
+        num_periods: temperament.num_periods(),
+        num_cycles: temperament.num_cycles(),
+        num_steps_per_fifth: temperament.num_steps_per_fifth(),
+        fifth_in_cents: temperament.size_of_fifth().as_cents(),
+        primary_step: temperament.primary_step(),
+        secondary_step: temperament.secondary_step(),
+        sharpness: temperament.sharpness(),
+        keyboard_layout: keyboard_layout(temperament),
+        steps,
+    }
+}
+
+pub fn print_info(
+    mut dst: impl io::Write,
+    num_steps_per_octave: u16,
+    notation: Option<NotationStyle>,
+) -> io::Result<()> {
+    let temperament = EqualTemperament::find().by_edo(num_steps_per_octave);
+    print_temperament(&mut dst, &temperament, notation)?;
+    for family in EqualTemperament::find().all_by_edo(num_steps_per_octave) {
+        if family.temperament_type() != temperament.temperament_type() {
+            writeln!(dst)?;
+            print_temperament(&mut dst, &family, notation)?;
         }
-        TemperamentType::Porcupine => {}
     }
 
     Ok(())
@@ -24,6 +221,7 @@ pub fn print_info(mut dst: impl io::Write, num_steps_per_octave: u16) -> io::Res
 pub fn print_temperament(
     mut dst: impl io::Write,
     temperament: &EqualTemperament,
+    notation: Option<NotationStyle>,
 ) -> io::Result<()> {
     writeln!(
         dst,
@@ -33,6 +231,9 @@ pub fn print_temperament(
     )?;
     writeln!(dst)?;
 
+    if temperament.num_periods() > 1 {
+        writeln!(dst, "Number of periods: {}", temperament.num_periods())?;
+    }
     writeln!(dst, "Number of cycles: {}", temperament.num_cycles())?;
     writeln!(
         dst,
@@ -62,19 +263,9 @@ pub fn print_temperament(
     writeln!(dst)?;
 
     writeln!(dst, "-- Keyboard layout --")?;
-    let keyboard = Keyboard::root_at(PianoKey::from_midi_number(0))
-        .with_steps_of(&temperament)
-        .coprime();
-    for y in (-5i16..5).rev() {
-        for x in 0..10 {
-            write!(
-                dst,
-                "{:^4}",
-                keyboard
-                    .get_key(x, y)
-                    .midi_number()
-                    .rem_euclid(i32::from(temperament.num_steps_per_octave())),
-            )?;
+    for row in keyboard_layout(temperament) {
+        for step in row {
+            write!(dst, "{:^4}", step)?;
         }
         writeln!(dst)?;
     }
@@ -82,32 +273,85 @@ pub fn print_temperament(
 
     writeln!(dst, "-- Scale steps --")?;
 
-    let location_of_minor_third = (Ratio::from_float(6.0 / 5.0).as_octaves()
-        * f64::from(temperament.num_steps_per_octave()))
-    .round() as u16;
-    let location_of_major_third = (Ratio::from_float(5.0 / 4.0).as_octaves()
-        * f64::from(temperament.num_steps_per_octave()))
-    .round() as u16;
-    let location_of_fourth = temperament.num_steps_per_octave() - temperament.num_steps_per_fifth();
-    let location_of_fifth = temperament.num_steps_per_fifth();
+    let notation = aligned_notation(temperament, notation);
 
     for index in 0..temperament.num_steps_per_octave() {
         write!(dst, "{:>3}. ", index,)?;
         write!(dst, "{}", temperament.get_heptatonic_name(i32::from(index)))?;
-        if index == location_of_minor_third {
-            write!(dst, " **JI m3rd**")?;
-        }
-        if index == location_of_major_third {
-            write!(dst, " **JI M3rd**")?;
-        }
-        if index == location_of_fourth {
-            write!(dst, " **JI P4th**")?;
+        if let Some((notation, location_of_d)) = &notation {
+            write!(
+                dst,
+                " [{}]",
+                notation.format_step(i32::from(index) + location_of_d)
+            )?;
         }
-        if index == location_of_fifth {
-            write!(dst, " **JI P5th**")?;
+        for ji_interval in ji_intervals_at(temperament, index) {
+            write!(dst, " **{}**", ji_interval)?;
         }
         writeln!(dst)?;
     }
 
     Ok(())
 }
+
+pub(crate) fn write_svg(mut dst: impl io::Write, num_steps_per_octave: u16) -> io::Result<()> {
+    let temperament = EqualTemperament::find().by_edo(num_steps_per_octave);
+    let keyboard = edo_keyboard(&temperament);
+    write!(dst, "{}", KeyboardSvg::new(&keyboard, &temperament))
+}
+
+fn edo_keyboard(temperament: &EqualTemperament) -> Keyboard {
+    Keyboard::root_at(PianoKey::from_midi_number(0))
+        .with_steps_of(temperament)
+        .coprime()
+}
+
+fn keyboard_layout(temperament: &EqualTemperament) -> Vec<Vec<i32>> {
+    let keyboard = edo_keyboard(temperament);
+    (-5i16..5)
+        .rev()
+        .map(|y| {
+            (0..10)
+                .map(|x| {
+                    keyboard
+                        .get_key(x, y)
+                        .midi_number()
+                        .rem_euclid(i32::from(temperament.num_steps_per_octave()))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Align the notation with the heptatonic names which are centered around D
+fn aligned_notation(
+    temperament: &EqualTemperament,
+    notation: Option<NotationStyle>,
+) -> Option<(EdoNotation, i32)> {
+    notation.map(|style| {
+        let notation = EdoNotation::new(temperament, style);
+        let location_of_d = notation.parse_step("D4").unwrap();
+        (notation, location_of_d)
+    })
+}
+
+fn ji_intervals_at(temperament: &EqualTemperament, index: u16) -> Vec<&'static str> {
+    let location_of = |ratio: f64| {
+        (Ratio::from_float(ratio).as_octaves() * f64::from(temperament.num_steps_per_octave()))
+            .round() as u16
+    };
+    let locations = [
+        (location_of(6.0 / 5.0), "JI m3rd"),
+        (location_of(5.0 / 4.0), "JI M3rd"),
+        (
+            temperament.num_steps_per_octave() - temperament.num_steps_per_fifth(),
+            "JI P4th",
+        ),
+        (temperament.num_steps_per_fifth(), "JI P5th"),
+    ];
+    locations
+        .iter()
+        .filter(|&&(location, _)| location == index)
+        .map(|&(_, name)| name)
+        .collect()
+}
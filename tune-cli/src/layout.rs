@@ -0,0 +1,288 @@
+use crate::{
+    dto::{LayoutRatingDto, LayoutsDto, LinnStrumentDto, LumatoneDto, LumatoneKeyDto, TuneDto},
+    App, CliResult,
+};
+use std::str::FromStr;
+use structopt::StructOpt;
+use tune::{
+    key::{Keyboard, PianoKey},
+    layout::{KeyColoring, LayoutPreset, LayoutSearch, LinnStrumentExport, LumatoneExport},
+    ratio::RatioExpression,
+    temperament::EqualTemperament,
+};
+
+#[derive(StructOpt)]
+pub(crate) enum LayoutOptions {
+    /// Create a Lumatone key mapping (.ltn file)
+    #[structopt(name = "lumatone")]
+    Lumatone(LumatoneOptions),
+
+    /// Print the row and column offsets and the resulting note grid of a LinnStrument
+    #[structopt(name = "linnstrument")]
+    LinnStrument(LinnStrumentOptions),
+
+    /// Rank the candidate layouts of an EDO by coverage, compactness of target intervals, octave direction and duplicate keys
+    #[structopt(name = "find")]
+    Find(FindLayoutOptions),
+}
+
+#[derive(StructOpt)]
+pub(crate) struct LumatoneOptions {
+    #[structopt(flatten)]
+    keyboard: KeyboardOptions,
+
+    /// Color keys by [note, degree]
+    #[structopt(long = "colors", default_value = "note")]
+    colors: ColorOption,
+
+    /// Number of notes per MIDI channel. Use the size of the scale to map every period to its own channel
+    #[structopt(long = "notes-per-channel", default_value = "128")]
+    notes_per_channel: u16,
+}
+
+#[derive(StructOpt)]
+pub(crate) struct LinnStrumentOptions {
+    #[structopt(flatten)]
+    keyboard: KeyboardOptions,
+
+    /// Number of columns (25 for the LinnStrument, 16 for the LinnStrument 128)
+    #[structopt(long = "columns", default_value = "25")]
+    num_columns: i16,
+}
+
+#[derive(StructOpt)]
+pub(crate) struct FindLayoutOptions {
+    /// Number of steps per octave
+    num_steps_per_octave: u16,
+
+    /// Scale degrees that should be covered by the grid, e.g. 0,9,18,22,31,40,49. If omitted, all degrees of the EDO are required
+    #[structopt(long = "degrees", use_delimiter = true)]
+    scale_degrees: Option<Vec<i32>>,
+
+    /// Number of columns of the grid
+    #[structopt(long = "columns", default_value = "12")]
+    num_columns: i16,
+
+    /// Number of rows of the grid
+    #[structopt(long = "rows", default_value = "5")]
+    num_rows: i16,
+
+    /// Number of layouts to list
+    #[structopt(short = "n", default_value = "10")]
+    num_results: usize,
+
+    /// Target intervals that should be playable with few keys between the two notes, e.g. 3/2 5/4 6/5
+    targets: Vec<RatioExpression>,
+}
+
+#[derive(StructOpt)]
+struct KeyboardOptions {
+    /// Number of steps per octave
+    num_steps_per_octave: u16,
+
+    /// Layout preset [wicki-hayden, bosanquet, janko, harmonic-table]. If omitted, the layout of `tune edo` is used
+    #[structopt(long = "preset")]
+    preset: Option<LayoutPreset>,
+
+    /// Primary step (to the right), overrides the preset
+    #[structopt(long = "ps")]
+    primary_step: Option<i16>,
+
+    /// Secondary step (to the lower right), overrides the preset
+    #[structopt(long = "ss")]
+    secondary_step: Option<i16>,
+
+    /// MIDI number of the root key
+    #[structopt(long = "root", default_value = "60")]
+    root_key: i32,
+}
+
+enum ColorOption {
+    Note,
+    Degree,
+}
+
+impl FromStr for ColorOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "note" => Ok(ColorOption::Note),
+            "degree" => Ok(ColorOption::Degree),
+            _ => Err(format!("Unknown coloring '{}'. Use note or degree", s)),
+        }
+    }
+}
+
+impl LayoutOptions {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        match self {
+            LayoutOptions::Lumatone(options) => {
+                if !(1..=128).contains(&options.notes_per_channel) {
+                    return Err("Number of notes per channel must be between 1 and 128"
+                        .to_owned()
+                        .into());
+                }
+                let (keyboard, temperament) = options.keyboard.create()?;
+                let coloring = match options.colors {
+                    ColorOption::Note => KeyColoring::NoteName(temperament),
+                    ColorOption::Degree => {
+                        KeyColoring::ScaleDegree(options.keyboard.num_steps_per_octave)
+                    }
+                };
+                let export = LumatoneExport::new(&keyboard, &coloring)
+                    .with_notes_per_channel(options.notes_per_channel);
+                if app.json {
+                    app.write_json(TuneDto::Lumatone(lumatone_dto(&export)))?;
+                } else {
+                    app.write(format_args!("{}", export))?;
+                }
+            }
+            LayoutOptions::LinnStrument(options) => {
+                let (keyboard, _) = options.keyboard.create()?;
+                let export =
+                    LinnStrumentExport::new(&keyboard).with_num_columns(options.num_columns);
+                if app.json {
+                    app.write_json(TuneDto::LinnStrument(LinnStrumentDto {
+                        column_offset: export.column_offset(),
+                        row_offset: export.row_offset(),
+                        lowest_note: export.lowest_note(),
+                        notes: export.notes(),
+                    }))?;
+                } else {
+                    app.write(format_args!("{}", export))?;
+                }
+            }
+            LayoutOptions::Find(options) => options.run(app)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl FindLayoutOptions {
+    fn run(&self, app: &mut App) -> CliResult<()> {
+        if self.num_steps_per_octave == 0 {
+            return Err("EDO must have at least one step".to_owned().into());
+        }
+
+        let mut search = LayoutSearch::for_edo(self.num_steps_per_octave)
+            .with_grid_size(self.num_columns, self.num_rows);
+        if let Some(scale_degrees) = &self.scale_degrees {
+            search = search.with_scale_degrees(scale_degrees.iter().copied());
+        }
+        for target in &self.targets {
+            search = search.with_target(target.ratio());
+        }
+
+        let ratings = search.rank();
+        let ratings = ratings.iter().take(self.num_results);
+
+        if app.json {
+            app.write_json(TuneDto::Layouts(LayoutsDto {
+                num_steps_per_octave: self.num_steps_per_octave,
+                num_columns: self.num_columns,
+                num_rows: self.num_rows,
+                targets_in_cents: self
+                    .targets
+                    .iter()
+                    .map(|target| target.ratio().as_cents())
+                    .collect(),
+                ratings: ratings
+                    .map(|rating| LayoutRatingDto {
+                        primary_step: rating.primary_step(),
+                        secondary_step: rating.secondary_step(),
+                        score: rating.score(),
+                        coverage: rating.coverage(),
+                        duplicate_fraction: rating.duplicate_fraction(),
+                        octave_location: rating.octave_location(),
+                        target_distances: rating.target_distances().to_vec(),
+                    })
+                    .collect(),
+            }))?;
+            return Ok(());
+        }
+
+        app.write(format_args!(
+            "---- Layouts of {}-EDO in a {}x{} grid",
+            self.num_steps_per_octave, self.num_columns, self.num_rows
+        ))?;
+        for target in &self.targets {
+            app.write(format_args!(" {:#}", target.ratio()))?;
+        }
+        app.writeln(format_args!(" ----"))?;
+        app.writeln(format_args!(""))?;
+
+        for (rank, rating) in ratings.enumerate() {
+            let octave_location = match rating.octave_location() {
+                Some((x, y)) => format!("({}, {})", x, y),
+                None => "-".to_owned(),
+            };
+            app.write(format_args!(
+                "{:>3}. --ps {:>3} --ss {:>4} | score {:>5.2} | coverage {:>5.1}% | duplicates {:>5.1}% | octave {:<8} |",
+                rank + 1,
+                rating.primary_step(),
+                rating.secondary_step(),
+                rating.score(),
+                rating.coverage() * 100.0,
+                rating.duplicate_fraction() * 100.0,
+                octave_location,
+            ))?;
+            for (target, distance) in self.targets.iter().zip(rating.target_distances()) {
+                match distance {
+                    Some(distance) => {
+                        app.write(format_args!(" {:#}: {}", target.ratio(), distance))?
+                    }
+                    None => app.write(format_args!(" {:#}: -", target.ratio()))?,
+                }
+            }
+            app.writeln(format_args!(""))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn lumatone_dto(export: &LumatoneExport) -> LumatoneDto {
+    LumatoneDto {
+        notes_per_channel: export.notes_per_channel(),
+        keys: export
+            .keys()
+            .into_iter()
+            .map(|key| LumatoneKeyDto {
+                board: key.board,
+                index: key.index,
+                note: key.note_and_channel.map(|(note, _)| note),
+                channel: key.note_and_channel.map(|(_, channel)| channel),
+                color: key.color.to_string(),
+            })
+            .collect(),
+    }
+}
+
+impl KeyboardOptions {
+    fn create(&self) -> CliResult<(Keyboard, EqualTemperament)> {
+        if self.num_steps_per_octave == 0 {
+            return Err("EDO must have at least one step".to_owned().into());
+        }
+
+        let temperament = EqualTemperament::find().by_edo(self.num_steps_per_octave);
+        let root_key = PianoKey::from_midi_number(self.root_key);
+
+        let keyboard = match self.preset {
+            Some(preset) => preset.keyboard(root_key, &temperament),
+            None => Keyboard::root_at(root_key)
+                .with_steps_of(&temperament)
+                .coprime(),
+        };
+        let primary_step = self.primary_step.unwrap_or_else(|| keyboard.primary_step());
+        let secondary_step = self
+            .secondary_step
+            .unwrap_or_else(|| keyboard.secondary_step());
+
+        Ok((
+            keyboard.with_steps(primary_step, secondary_step),
+            temperament,
+        ))
+    }
+}
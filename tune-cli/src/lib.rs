@@ -1,25 +1,50 @@
+mod chord;
+mod consonance;
+mod convert;
 mod dto;
+mod dupes;
 mod edo;
+mod layout;
 mod live;
 mod midi;
 mod mts;
-
-use dto::{ScaleDto, ScaleItemDto, TuneDto};
+mod pcset;
+mod search;
+
+use chord::ChordsOptions;
+use consonance::DissonanceOptions;
+use convert::ConvertOptions;
+use dto::{
+    ComparisonItemDto, DevicesDto, FractionDto, KbmDto, NearestFractionDto, PresetDefinitionDto,
+    PresetDto, ScaleDto, ScaleItemDto, SclDto, SclItemDto, TemperedFifthDto, TuneDocumentDto,
+    TuneDto,
+};
+use dupes::FindDupesOptions;
+use edo::FindEdoOptions;
 use io::Read;
+use layout::LayoutOptions;
 use live::LiveOptions;
 use mts::MtsOptions;
+use pcset::PcSetOptions;
+use search::SearchOptions;
 use shared::SclCommand;
-use std::fs::File;
+use std::fs::{self, File};
 use std::{
     fmt::{self, Arguments, Debug},
     io::{self, Write},
     path::PathBuf,
+    str::FromStr,
 };
 use structopt::StructOpt;
+use tune::interval;
 use tune::key::PianoKey;
+use tune::notation::NotationStyle;
+use tune::note::NoteLetter;
 use tune::pitch::{Pitch, ReferencePitch};
-use tune::ratio::Ratio;
-use tune::scala::{Kbm, SclBuildError};
+use tune::presets::{self, Comma, PresetDefinition, ScalePreset};
+use tune::ratio::{FractionConstraints, Ratio};
+use tune::scala::{Ascl, Kbm, Scl, SclBuildError};
+use tune::svg::{LatticeSvg, LatticeSvgError, ScaleCircleSvg};
 use tune::tuning::Tuning;
 
 #[doc(hidden)]
@@ -31,6 +56,10 @@ struct MainOptions {
     #[structopt(long = "--of")]
     output_file: Option<PathBuf>,
 
+    /// Print the output as JSON. See `tune schema` for the format
+    #[structopt(long = "json", global = true)]
+    json: bool,
+
     #[structopt(subcommand)]
     command: MainCommand,
 }
@@ -49,6 +78,42 @@ enum MainCommand {
     #[structopt(name = "edo")]
     Edo(EdoOptions),
 
+    /// Find EDOs that approximate a set of target intervals
+    #[structopt(name = "find-edo")]
+    FindEdo(FindEdoOptions),
+
+    /// Find isomorphic keyboard layouts and export them to hardware controllers
+    #[structopt(name = "layout")]
+    Layout(LayoutOptions),
+
+    /// Analyze pitch-class sets of an EDO: normal and prime forms, interval-class vectors, complements and Z-relations
+    #[structopt(name = "pcset")]
+    PcSet(PcSetOptions),
+
+    /// Print a dissonance curve for a timbre or score the degrees of a scale against its root
+    #[structopt(name = "dissonance")]
+    Dissonance(DissonanceOptions),
+
+    /// Find the best realizations of just intonation chords in a scale
+    #[structopt(name = "chords")]
+    Chords(ChordsOptions),
+
+    /// Find groups of equivalent scl files in a directory, ignoring mode, description and notation
+    #[structopt(name = "find-dupes")]
+    FindDupes(FindDupesOptions),
+
+    /// Search a directory of scl files by size, period, prime limit, contained intervals, MOS-ness or EDO-ness
+    #[structopt(name = "search")]
+    Search(SearchOptions),
+
+    /// List the historical temperaments and just intonation scales available via `scl preset`
+    #[structopt(name = "presets")]
+    Presets,
+
+    /// Convert between scl, ascl, kbm, JSON and syx files. The formats are inferred from the file extensions
+    #[structopt(name = "convert")]
+    Convert(ConvertOptions),
+
     /// [out] Create a new scale
     #[structopt(name = "scale")]
     Scale(ScaleOptions),
@@ -74,6 +139,10 @@ enum MainCommand {
     /// List MIDI devices
     #[structopt(name = "devices")]
     Devices,
+
+    /// Print the JSON Schema of the JSON output
+    #[structopt(name = "schema")]
+    Schema,
 }
 
 #[derive(StructOpt)]
@@ -82,14 +151,100 @@ struct SclOptions {
     #[structopt(long = "--name")]
     name: Option<String>,
 
+    /// Output format [scl, ascl]
+    #[structopt(long = "format", default_value = "scl")]
+    format: SclOutputFormat,
+
+    /// [ascl] Reference note that should sound at its original or a custom pitch, e.g. 69@440Hz or A4@432Hz
+    #[structopt(long = "ref", default_value = "60")]
+    ref_pitch: ReferencePitch,
+
+    /// [ascl] root note / "middle note" of the scale if different from reference note
+    #[structopt(long = "root")]
+    root_note: Option<i16>,
+
+    /// [ascl] Comma-separated note names starting at the root, e.g. C,D,E,F,G,A,B. If omitted, heptatonic names of the corresponding EDO are used
+    #[structopt(long = "note-names")]
+    note_names: Option<NoteNames>,
+
+    /// [ascl] Source of the scale, e.g. a book or an author
+    #[structopt(long = "source")]
+    source: Option<String>,
+
+    /// [ascl] Link to further information about the scale
+    #[structopt(long = "link")]
+    link: Option<String>,
+
+    /// Additionally draw the scale as an SVG image to the given file
+    #[structopt(long = "svg")]
+    svg_file: Option<PathBuf>,
+
+    /// [svg] Type of the drawing [circle, lattice]. A lattice requires a 7-limit just intonation scale
+    #[structopt(long = "diagram", default_value = "circle")]
+    diagram: SclDiagram,
+
     #[structopt(subcommand)]
     command: SclCommand,
 }
 
+enum SclOutputFormat {
+    Scl,
+    Ascl,
+}
+
+impl FromStr for SclOutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scl" => Ok(SclOutputFormat::Scl),
+            "ascl" => Ok(SclOutputFormat::Ascl),
+            _ => Err(format!("Unknown format '{}'. Use scl or ascl", s)),
+        }
+    }
+}
+
+enum SclDiagram {
+    Circle,
+    Lattice,
+}
+
+impl FromStr for SclDiagram {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "circle" => Ok(SclDiagram::Circle),
+            "lattice" => Ok(SclDiagram::Lattice),
+            _ => Err(format!("Unknown diagram '{}'. Use circle or lattice", s)),
+        }
+    }
+}
+
+struct NoteNames(Vec<String>);
+
+impl FromStr for NoteNames {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(NoteNames(
+            s.split(',').map(|name| name.trim().to_owned()).collect(),
+        ))
+    }
+}
+
 #[derive(StructOpt)]
 struct EdoOptions {
     /// Number of steps per octave
     num_steps_per_octave: u16,
+
+    /// Additionally print the scale steps in the given notation [ups, sagittal, heji]
+    #[structopt(long = "notation")]
+    notation: Option<NotationStyle>,
+
+    /// Additionally draw the keyboard layout as an SVG image to the given file
+    #[structopt(long = "svg")]
+    svg_file: Option<PathBuf>,
 }
 
 #[derive(StructOpt)]
@@ -121,7 +276,7 @@ struct DiffOptions {
 
 #[derive(StructOpt)]
 struct KbmOptions {
-    /// Reference note that should sound at its original or a custom pitch, e.g. 69@440Hz
+    /// Reference note that should sound at its original or a custom pitch, e.g. 69@440Hz or A4@432Hz
     ref_pitch: ReferencePitch,
 
     /// root note / "middle note" of the scale if different from reference note
@@ -134,6 +289,18 @@ struct LimitOptions {
     /// Largest acceptable numerator or denominator (ignoring powers of two)
     #[structopt(short = "l", default_value = "11")]
     limit: u16,
+
+    /// Largest acceptable prime factor of numerator or denominator
+    #[structopt(long = "prime-lim")]
+    prime_limit: Option<u16>,
+
+    /// Largest acceptable Tenney height log2(numer*denom)
+    #[structopt(long = "tenney")]
+    max_tenney_height: Option<f64>,
+
+    /// Largest acceptable deviation in cents
+    #[structopt(long = "max-err")]
+    max_error_in_cents: Option<f64>,
 }
 
 pub fn run_in_shell_env(args: impl IntoIterator<Item = String>) -> CliResult<()> {
@@ -155,6 +322,7 @@ pub fn run_in_shell_env(args: impl IntoIterator<Item = String>) -> CliResult<()>
         input,
         output,
         error,
+        json: options.json,
     };
     app.run(options.command)
 }
@@ -163,44 +331,152 @@ struct App<'a> {
     input: Box<dyn 'a + Read>,
     output: Box<dyn 'a + Write>,
     error: Box<dyn 'a + Write>,
+    json: bool,
 }
 
 impl App<'_> {
     fn run(&mut self, command: MainCommand) -> CliResult<()> {
         match command {
-            MainCommand::Scl(SclOptions { name, command }) => {
-                self.execute_scl_command(name, command)?
-            }
+            MainCommand::Scl(options) => self.execute_scl_command(options)?,
             MainCommand::Kbm(kbm) => self.execute_kbm_command(kbm)?,
             MainCommand::Edo(EdoOptions {
                 num_steps_per_octave,
-            }) => edo::print_info(&mut self.output, num_steps_per_octave)?,
+                notation,
+                svg_file,
+            }) => {
+                if self.json {
+                    self.write_json(TuneDto::Edo(edo::to_dto(num_steps_per_octave, notation)))?
+                } else {
+                    edo::print_info(&mut self.output, num_steps_per_octave, notation)?
+                }
+                if let Some(svg_file) = svg_file {
+                    edo::write_svg(File::create(svg_file)?, num_steps_per_octave)?
+                }
+            }
+            MainCommand::FindEdo(options) => options.run(self)?,
+            MainCommand::Layout(options) => options.run(self)?,
+            MainCommand::PcSet(options) => options.run(self)?,
+            MainCommand::Dissonance(options) => options.run(self)?,
+            MainCommand::Chords(options) => options.run(self)?,
+            MainCommand::FindDupes(options) => options.run(self)?,
+            MainCommand::Search(options) => options.run(self)?,
+            MainCommand::Presets => self.list_presets()?,
+            MainCommand::Convert(options) => options.run(self)?,
             MainCommand::Scale(ScaleOptions {
                 kbm_params,
                 command,
             }) => self.execute_scale_command(kbm_params, command)?,
             MainCommand::Dump(DumpOptions { limit_params }) => {
-                self.dump_scale(limit_params.limit)?
+                self.dump_scale(limit_params.to_constraints())?
             }
             MainCommand::Diff(DiffOptions {
                 limit_params,
                 key_map_params,
                 command,
-            }) => self.diff_scale(key_map_params, limit_params.limit, command)?,
+            }) => self.diff_scale(key_map_params, limit_params.to_constraints(), command)?,
             MainCommand::Mts(options) => options.run(self)?,
             MainCommand::Live(options) => options.run(self)?,
-            MainCommand::Devices => shared::print_midi_devices(&mut self.output, "tune-cli")?,
+            MainCommand::Devices => {
+                if self.json {
+                    let (inputs, outputs) = shared::list_midi_devices("tune-cli");
+                    self.write_json(TuneDto::Devices(DevicesDto { inputs, outputs }))?
+                } else {
+                    shared::print_midi_devices(&mut self.output, "tune-cli")?
+                }
+            }
+            MainCommand::Schema => self.writeln(format_args!(
+                "{}",
+                serde_json::to_string_pretty(&dto::json_schema()).map_err(io::Error::from)?
+            ))?,
         }
         Ok(())
     }
 
-    fn execute_scl_command(&mut self, name: Option<String>, command: SclCommand) -> CliResult<()> {
-        self.write(format_args!("{}", command.to_scl(name)?.export()))
-            .map_err(Into::into)
+    fn execute_scl_command(&mut self, options: SclOptions) -> CliResult<()> {
+        let (scl, tempering_report) = options.command.to_scl_with_tempering_report(options.name)?;
+        if let Some(svg_file) = &options.svg_file {
+            let svg = match options.diagram {
+                SclDiagram::Circle => ScaleCircleSvg::new(&scl).to_string(),
+                SclDiagram::Lattice => LatticeSvg::new(&scl)?.to_string(),
+            };
+            fs::write(svg_file, svg)?;
+        }
+        match options.format {
+            SclOutputFormat::Scl if self.json => self.write_json(TuneDto::Scl(scl_dto(&scl)))?,
+            SclOutputFormat::Ascl if self.json => {
+                return Err("JSON output is not available for the ascl format"
+                    .to_owned()
+                    .into())
+            }
+            SclOutputFormat::Scl => self.write(format_args!("{}", scl.export()))?,
+            SclOutputFormat::Ascl => {
+                let ref_pitch = options.ref_pitch;
+                let kbm = Kbm {
+                    ref_pitch,
+                    root_key: options
+                        .root_note
+                        .map(i32::from)
+                        .map(PianoKey::from_midi_number)
+                        .unwrap_or_else(|| ref_pitch.key()),
+                };
+                let mut ascl = Ascl::new(scl, kbm);
+                ascl = match options.note_names {
+                    Some(NoteNames(note_names)) => {
+                        if note_names.len() != ascl.scl.size() {
+                            return Err(format!(
+                                "Expected {} note names but got {}",
+                                ascl.scl.size(),
+                                note_names.len()
+                            )
+                            .into());
+                        }
+                        ascl.with_note_names(note_names)
+                    }
+                    None => ascl.with_heptatonic_note_names(),
+                };
+                ascl.source = options.source;
+                ascl.link = options.link;
+                self.write(format_args!("{}", ascl.export()))?
+            }
+        }
+
+        if let Some(approximations) = tempering_report {
+            for (index, approximation) in approximations.iter().enumerate() {
+                self.errln(format_args!(
+                    "degree {:>3}: {:>4} steps, error {:>+7.3}c",
+                    index + 1,
+                    approximation.approx_value,
+                    approximation.deviation.as_cents()
+                ))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn list_presets(&mut self) -> CliResult<()> {
+        if self.json {
+            self.write_json(TuneDto::Presets(
+                presets::SCALE_PRESETS.iter().map(preset_dto).collect(),
+            ))?;
+        } else {
+            for preset in presets::SCALE_PRESETS {
+                self.writeln(format_args!("{:<16} {}", preset.name, preset.description))?;
+            }
+        }
+        Ok(())
     }
 
     fn execute_kbm_command(&mut self, key_map_params: KbmOptions) -> io::Result<()> {
-        self.write(format_args!("{}", key_map_params.to_kbm().export()))
+        let kbm = key_map_params.to_kbm();
+        if self.json {
+            self.write_json(TuneDto::Kbm(KbmDto {
+                root_key_midi_number: kbm.root_key.midi_number(),
+                ref_key_midi_number: kbm.ref_pitch.key().midi_number(),
+                ref_pitch_in_hz: kbm.ref_pitch.pitch().as_hz(),
+            }))
+        } else {
+            self.write(format_args!("{}", kbm.export()))
+        }
     }
 
     fn execute_scale_command(
@@ -208,39 +484,20 @@ impl App<'_> {
         key_map_params: KbmOptions,
         command: SclCommand,
     ) -> CliResult<()> {
-        let key_map = key_map_params.to_kbm();
-        let tuning = (&command.to_scl(None)?, &key_map);
-
-        let items = scale_iter(tuning)
-            .map(|scale_item| ScaleItemDto {
-                key_midi_number: scale_item.piano_key.midi_number(),
-                pitch_in_hz: scale_item.pitch.as_hz(),
-            })
-            .collect();
-
-        let dump = ScaleDto {
-            root_key_midi_number: key_map.root_key.midi_number(),
-            root_pitch_in_hz: tuning.pitch_of(0).as_hz(),
-            items,
-        };
-
-        let dto = TuneDto::Scale(dump);
-
-        self.writeln(format_args!(
-            "{}",
-            serde_json::to_string_pretty(&dto).map_err(io::Error::from)?
-        ))
-        .map_err(Into::into)
+        let ascl = Ascl::new(command.to_scl(None)?, key_map_params.to_kbm());
+        let dto = TuneDto::Scale(scale_dto(&ascl.with_heptatonic_note_names()));
+        self.write_json(dto).map_err(Into::into)
     }
 
-    fn dump_scale(&mut self, limit: u16) -> io::Result<()> {
+    fn dump_scale(&mut self, constraints: FractionConstraints) -> io::Result<()> {
         let in_scale = ScaleDto::read(&mut self.input)?;
 
         let mut printer = ScaleTablePrinter {
             app: self,
             root_key: PianoKey::from_midi_number(in_scale.root_key_midi_number),
             root_pitch: Pitch::from_hz(in_scale.root_pitch_in_hz),
-            limit,
+            constraints,
+            rows: Vec::new(),
         };
 
         printer.print_table_header()?;
@@ -254,17 +511,17 @@ impl App<'_> {
                 PianoKey::from_midi_number(scale_item.key_midi_number),
                 pitch,
                 approx_value.midi_number(),
-                format!("{:>6} {:>2}", letter, octave.octave_number()),
+                TargetIndex::Note(letter, octave.octave_number()),
                 approximation.deviation,
             )?;
         }
-        Ok(())
+        printer.finish()
     }
 
     fn diff_scale(
         &mut self,
         key_map_params: KbmOptions,
-        limit: u16,
+        constraints: FractionConstraints,
         command: SclCommand,
     ) -> CliResult<()> {
         let in_scale = ScaleDto::read(&mut self.input)?;
@@ -276,7 +533,8 @@ impl App<'_> {
             app: self,
             root_pitch: Pitch::from_hz(in_scale.root_pitch_in_hz),
             root_key: PianoKey::from_midi_number(in_scale.root_key_midi_number),
-            limit,
+            constraints,
+            rows: Vec::new(),
         };
 
         printer.print_table_header()?;
@@ -290,11 +548,11 @@ impl App<'_> {
                 PianoKey::from_midi_number(item.key_midi_number),
                 pitch,
                 approximation.approx_value.midi_number(),
-                format!("IDX {:>5}", index),
+                TargetIndex::Index(index),
                 approximation.deviation,
             )?;
         }
-        Ok(())
+        printer.finish().map_err(Into::into)
     }
 
     pub fn write(&mut self, args: Arguments) -> io::Result<()> {
@@ -305,6 +563,14 @@ impl App<'_> {
         writeln!(&mut self.output, "{}", args)
     }
 
+    pub fn write_json(&mut self, content: TuneDto) -> io::Result<()> {
+        let document = TuneDocumentDto::new(content);
+        self.writeln(format_args!(
+            "{}",
+            serde_json::to_string_pretty(&document).map_err(io::Error::from)?
+        ))
+    }
+
     pub fn errln(&mut self, args: Arguments) -> io::Result<()> {
         writeln!(&mut self.error, "{}", args)
     }
@@ -314,6 +580,17 @@ impl App<'_> {
     }
 }
 
+impl LimitOptions {
+    fn to_constraints(&self) -> FractionConstraints {
+        FractionConstraints {
+            odd_limit: self.limit,
+            prime_limit: self.prime_limit,
+            max_tenney_height: self.max_tenney_height,
+            max_error_in_cents: self.max_error_in_cents,
+        }
+    }
+}
+
 impl KbmOptions {
     pub fn to_kbm(&self) -> Kbm {
         Kbm {
@@ -327,6 +604,87 @@ impl KbmOptions {
     }
 }
 
+fn scale_dto(ascl: &Ascl) -> ScaleDto {
+    let tuning = (&ascl.scl, &ascl.kbm);
+    let size = ascl.scl.size() as i32;
+
+    let items = scale_iter(tuning)
+        .map(|scale_item| {
+            let index = ascl.kbm.root_key.num_keys_before(scale_item.piano_key);
+            let scale_degree = index.rem_euclid(size);
+            ScaleItemDto {
+                key_midi_number: scale_item.piano_key.midi_number(),
+                pitch_in_hz: scale_item.pitch.as_hz(),
+                scale_degree: Some(scale_degree),
+                period_index: Some(index.div_euclid(size)),
+                note_name: ascl.note_names.get(scale_degree as usize).cloned(),
+            }
+        })
+        .collect();
+
+    ScaleDto {
+        root_key_midi_number: ascl.kbm.root_key.midi_number(),
+        root_pitch_in_hz: tuning.pitch_of(0).as_hz(),
+        items,
+    }
+}
+
+fn scl_dto(scl: &Scl) -> SclDto {
+    let items = (1..=scl.size() as i32)
+        .map(|degree| {
+            let fraction = scl.fraction_of(degree);
+            SclItemDto {
+                degree,
+                pitch_in_cents: scl.relative_pitch_of(degree).as_cents(),
+                numer: fraction.map(|(numer, _)| numer),
+                denom: fraction.map(|(_, denom)| denom),
+            }
+        })
+        .collect();
+
+    SclDto {
+        description: scl.description().to_owned(),
+        period_in_cents: scl.period().as_cents(),
+        items,
+    }
+}
+
+fn preset_dto(preset: &ScalePreset) -> PresetDto {
+    let definition = match preset.definition {
+        PresetDefinition::FifthsChain {
+            num_fifths_below_root,
+            fifths,
+        } => PresetDefinitionDto::FifthsChain {
+            num_fifths_below_root,
+            fifths: fifths
+                .iter()
+                .map(|fifth| TemperedFifthDto {
+                    numer: fifth.numer,
+                    denom: fifth.denom,
+                    comma: match fifth.comma {
+                        Comma::Syntonic => "syntonic",
+                        Comma::Pythagorean => "pythagorean",
+                        Comma::Schisma => "schisma",
+                    }
+                    .to_owned(),
+                })
+                .collect(),
+        },
+        PresetDefinition::Just(fractions) => PresetDefinitionDto::Just {
+            fractions: fractions
+                .iter()
+                .map(|&(numer, denom)| FractionDto { numer, denom })
+                .collect(),
+        },
+    };
+
+    PresetDto {
+        name: preset.name.to_owned(),
+        description: preset.description.to_owned(),
+        definition,
+    }
+}
+
 fn scale_iter(tuning: impl Tuning<PianoKey>) -> impl Iterator<Item = ScaleItem> {
     (1..128).map(move |midi_number| {
         let piano_key = PianoKey::from_midi_number(midi_number);
@@ -346,16 +704,26 @@ struct ScaleTablePrinter<'a, 'b> {
     app: &'a mut App<'b>,
     root_key: PianoKey,
     root_pitch: Pitch,
-    limit: u16,
+    constraints: FractionConstraints,
+    rows: Vec<ComparisonItemDto>,
+}
+
+enum TargetIndex {
+    Note(NoteLetter, i32),
+    Index(i32),
 }
 
 impl ScaleTablePrinter<'_, '_> {
     fn print_table_header(&mut self) -> io::Result<()> {
+        if self.app.json {
+            return Ok(());
+        }
         self.app.writeln(format_args!(
-            "  {source:-^33} ‖ {pitch:-^14} ‖ {target:-^28}",
+            "  {source:-^33} ‖ {pitch:-^14} ‖ {target:-^28} ‖ {interval:-^26}",
             source = "Source Scale",
             pitch = "Pitch",
-            target = "Target Scale"
+            target = "Target Scale",
+            interval = "Interval"
         ))
     }
 
@@ -364,35 +732,85 @@ impl ScaleTablePrinter<'_, '_> {
         source_key: PianoKey,
         pitch: Pitch,
         target_midi: i32,
-        target_index: String,
+        target_index: TargetIndex,
         deviation: Ratio,
     ) -> io::Result<()> {
         let source_index = self.root_key.num_keys_before(source_key);
+
+        let nearest_fraction =
+            Ratio::between_pitches(self.root_pitch, pitch).nearest_fraction_with(&self.constraints);
+        let interval_name = nearest_fraction.as_ref().and_then(|nearest_fraction| {
+            interval::find_ji_fraction(nearest_fraction.numer.into(), nearest_fraction.denom.into())
+                .map(|interval| interval.name)
+        });
+
+        if self.app.json {
+            let (target_index, target_note_name) = match target_index {
+                TargetIndex::Note(letter, octave) => (None, Some(format!("{} {}", letter, octave))),
+                TargetIndex::Index(index) => (Some(index), None),
+            };
+            self.rows.push(ComparisonItemDto {
+                key_midi_number: source_key.midi_number(),
+                source_index,
+                pitch_in_hz: pitch.as_hz(),
+                nearest_fraction: nearest_fraction.map(|nearest_fraction| NearestFractionDto {
+                    numer: nearest_fraction.numer,
+                    denom: nearest_fraction.denom,
+                    deviation_in_cents: nearest_fraction.deviation.as_cents(),
+                    num_octaves: nearest_fraction.num_octaves,
+                }),
+                interval_name: interval_name.map(str::to_owned),
+                target_key_midi_number: target_midi,
+                target_index,
+                target_note_name,
+                deviation_in_cents: deviation.as_cents(),
+            });
+            return Ok(());
+        }
+
         if source_index == 0 {
             self.app.write(format_args!("> "))?;
         } else {
             self.app.write(format_args!("  "))?;
         }
 
-        let nearest_fraction =
-            Ratio::between_pitches(self.root_pitch, pitch).nearest_fraction(self.limit);
+        let nearest_fraction = match nearest_fraction {
+            Some(nearest_fraction) => format!(
+                "{numer:>2}/{denom:<2} {fract_deviation:>+4.0}¢ {fract_octaves:>+3}o",
+                numer = nearest_fraction.numer,
+                denom = nearest_fraction.denom,
+                fract_deviation = nearest_fraction.deviation.as_cents(),
+                fract_octaves = nearest_fraction.num_octaves,
+            ),
+            None => format!("{:^16}", "-"),
+        };
+
+        let target_index = match target_index {
+            TargetIndex::Note(letter, octave) => format!("{:>6} {:>2}", letter, octave),
+            TargetIndex::Index(index) => format!("IDX {:>5}", index),
+        };
 
         self.app.writeln(format_args!(
-            "{source_midi:>3} | IDX {source_index:>4} | \
-             {numer:>2}/{denom:<2} {fract_deviation:>+4.0}¢ {fract_octaves:>+3}o ‖ \
-             {pitch:>11.3} Hz ‖ {target_midi:>4} | {target_index} | {deviation:>+8.3}¢",
+            "{source_midi:>3} | IDX {source_index:>4} | {nearest_fraction} ‖ \
+             {pitch:>11.3} Hz ‖ {target_midi:>4} | {target_index} | {deviation:>+8.3}¢ ‖ \
+             {interval_name}",
             source_midi = source_key.midi_number(),
             source_index = source_index,
+            nearest_fraction = nearest_fraction,
             pitch = pitch.as_hz(),
-            numer = nearest_fraction.numer,
-            denom = nearest_fraction.denom,
-            fract_deviation = nearest_fraction.deviation.as_cents(),
-            fract_octaves = nearest_fraction.num_octaves,
             target_midi = target_midi,
             target_index = target_index,
             deviation = deviation.as_cents(),
+            interval_name = interval_name.unwrap_or("-"),
         ))
     }
+
+    fn finish(self) -> io::Result<()> {
+        if self.app.json {
+            self.app.write_json(TuneDto::Comparison(self.rows))?;
+        }
+        Ok(())
+    }
 }
 
 pub type CliResult<T> = Result<T, CliError>;
@@ -423,6 +841,12 @@ impl From<SclBuildError> for CliError {
     }
 }
 
+impl From<LatticeSvgError> for CliError {
+    fn from(v: LatticeSvgError) -> Self {
+        CliError::CommandError(format!("Could not draw lattice ({:?})", v))
+    }
+}
+
 impl From<io::Error> for CliError {
     fn from(v: io::Error) -> Self {
         CliError::IoError(v)
@@ -71,7 +71,13 @@ struct AheadOfTimeOptions {
 }
 
 impl LiveOptions {
-    pub fn run(&self, _app: &mut App) -> CliResult<()> {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        if app.json {
+            return Err("The live command does not support JSON output"
+                .to_owned()
+                .into());
+        }
+
         let midi_in_device = self.midi_in_device;
         let out_connection = midi::connect_to_out_device(self.midi_out_device)
             .map_err(|err| format!("Could not connect to MIDI output device ({:?})", err))?;
@@ -1,4 +1,9 @@
-use crate::{dto::ScaleDto, midi, shared::SclCommand, App, CliResult, KbmOptions};
+use crate::{
+    dto::{MidiMessageDto, ScaleDto, TuneDto},
+    midi,
+    shared::SclCommand,
+    App, CliResult, KbmOptions,
+};
 use midir::MidiOutputConnection;
 use std::{
     fs::{File, OpenOptions},
@@ -120,14 +125,22 @@ impl MtsOptions {
                 .map(midi::connect_to_out_device)
                 .transpose()
                 .map_err(|err| format!("Could not connect to MIDI device ({:?})", err))?,
+
+            messages: Vec::new(),
         };
 
         match &self.command {
-            MtsCommand::FromJson(options) => options.run(app, &mut outputs),
-            MtsCommand::Octave(options) => options.run(app, &mut outputs),
-            MtsCommand::TuningProgram(options) => options.run(app, &mut outputs),
-            MtsCommand::TuningBank(options) => options.run(app, &mut outputs),
+            MtsCommand::FromJson(options) => options.run(app, &mut outputs)?,
+            MtsCommand::Octave(options) => options.run(app, &mut outputs)?,
+            MtsCommand::TuningProgram(options) => options.run(app, &mut outputs)?,
+            MtsCommand::TuningBank(options) => options.run(app, &mut outputs)?,
+        }
+
+        if app.json {
+            app.write_json(TuneDto::Mts(outputs.messages))?;
         }
+
+        Ok(())
     }
 }
 
@@ -260,12 +273,19 @@ impl DeviceIdArg {
 struct Outputs {
     open_file: Option<File>,
     midi_out: Option<MidiOutputConnection>,
+    messages: Vec<MidiMessageDto>,
 }
 
 impl Outputs {
     fn write_midi_message(&mut self, app: &mut App, message: &[u8]) -> CliResult<()> {
-        for byte in message {
-            app.writeln(format_args!("0x{:02x}", byte))?;
+        if app.json {
+            self.messages.push(MidiMessageDto {
+                bytes: message.to_vec(),
+            });
+        } else {
+            for byte in message {
+                app.writeln(format_args!("0x{:02x}", byte))?;
+            }
         }
         if let Some(open_file) = &mut self.open_file {
             open_file.write_all(message)?;
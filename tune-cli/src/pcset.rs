@@ -0,0 +1,229 @@
+use crate::{
+    dto::{PcSetAnalysisDto, SetClassDto, SetClassesDto, TuneDto},
+    App, CliResult,
+};
+use std::collections::HashMap;
+use structopt::StructOpt;
+use tune::{pcset::PitchClassSet, temperament::EqualTemperament};
+
+#[derive(StructOpt)]
+pub(crate) enum PcSetOptions {
+    /// Print the normal form, prime form, interval-class vector, complement and Z-relations of a pitch-class set
+    #[structopt(name = "analyze")]
+    Analyze(PcSetArgs),
+
+    /// List the prime forms of all set classes of a given cardinality. Large EDOs can have millions of set classes
+    #[structopt(name = "classes")]
+    Classes(ClassesOptions),
+
+    /// Create a scale file from a pitch-class set
+    #[structopt(name = "scl")]
+    Scl(PcSetArgs),
+}
+
+#[derive(StructOpt)]
+pub(crate) struct PcSetArgs {
+    /// Number of steps per octave
+    num_steps_per_octave: u16,
+
+    /// Pitch classes, e.g. 0,4,7
+    #[structopt(use_delimiter = true, required = true)]
+    pitch_classes: Vec<i32>,
+}
+
+#[derive(StructOpt)]
+pub(crate) struct ClassesOptions {
+    /// Number of steps per octave
+    num_steps_per_octave: u16,
+
+    /// Number of pitch classes per set
+    cardinality: usize,
+}
+
+impl PcSetOptions {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        match self {
+            PcSetOptions::Analyze(args) => analyze(app, &args.create()?)?,
+            PcSetOptions::Classes(options) => options.run(app)?,
+            PcSetOptions::Scl(args) => {
+                let scl = args.create()?.to_scl()?;
+                if app.json {
+                    app.write_json(TuneDto::Scl(crate::scl_dto(&scl)))?;
+                } else {
+                    app.write(format_args!("{}", scl.export()))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PcSetArgs {
+    fn create(&self) -> CliResult<PitchClassSet> {
+        if self.num_steps_per_octave == 0 {
+            return Err("Number of steps per octave must be greater than 0"
+                .to_owned()
+                .into());
+        }
+        Ok(PitchClassSet::new(
+            self.num_steps_per_octave,
+            self.pitch_classes.iter().copied(),
+        ))
+    }
+}
+
+impl ClassesOptions {
+    fn run(&self, app: &mut App) -> CliResult<()> {
+        if self.num_steps_per_octave == 0 {
+            return Err("Number of steps per octave must be greater than 0"
+                .to_owned()
+                .into());
+        }
+
+        let set_classes =
+            PitchClassSet::all_set_classes(self.num_steps_per_octave, self.cardinality);
+        let vectors = set_classes
+            .iter()
+            .map(PitchClassSet::interval_class_vector)
+            .collect::<Vec<_>>();
+        let mut num_occurrences = HashMap::new();
+        for vector in &vectors {
+            *num_occurrences.entry(vector).or_insert(0) += 1;
+        }
+        let is_z_related = |vector| num_occurrences[vector] > 1;
+
+        if app.json {
+            app.write_json(TuneDto::SetClasses(SetClassesDto {
+                num_steps_per_octave: self.num_steps_per_octave,
+                cardinality: self.cardinality,
+                set_classes: set_classes
+                    .iter()
+                    .zip(&vectors)
+                    .map(|(set_class, vector)| SetClassDto {
+                        prime_form: set_class.pitch_classes().to_vec(),
+                        interval_class_vector: vector.clone(),
+                        is_z_related: is_z_related(vector),
+                    })
+                    .collect(),
+            }))?;
+            return Ok(());
+        }
+
+        app.writeln(format_args!(
+            "---- {} set classes of cardinality {} in {}-EDO ----",
+            set_classes.len(),
+            self.cardinality,
+            self.num_steps_per_octave
+        ))?;
+        app.writeln(format_args!(""))?;
+
+        for (index, (set_class, vector)) in set_classes.iter().zip(&vectors).enumerate() {
+            app.write(format_args!(
+                "{:>4}. {:<24} {}",
+                index + 1,
+                set_class.to_string(),
+                format_vector(vector),
+            ))?;
+            if is_z_related(vector) {
+                app.write(format_args!(" Z"))?;
+            }
+            app.writeln(format_args!(""))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn analyze(app: &mut App, pcset: &PitchClassSet) -> CliResult<()> {
+    let num_steps_per_octave = pcset.num_steps_per_octave();
+    let temperament = EqualTemperament::find().by_edo(num_steps_per_octave);
+    let prime_form = PitchClassSet::new(
+        num_steps_per_octave,
+        pcset.prime_form().into_iter().map(i32::from),
+    );
+    let complement = pcset.complement();
+    let z_related = pcset.z_related_set_classes();
+    let is_inversionally_symmetric = pcset.inverted().is_transposition_of(pcset);
+
+    if app.json {
+        app.write_json(TuneDto::PcSetAnalysis(PcSetAnalysisDto {
+            num_steps_per_octave,
+            pitch_classes: pcset.pitch_classes().to_vec(),
+            note_names: pcset.note_names(&temperament),
+            normal_form: pcset.normal_form(),
+            prime_form: prime_form.pitch_classes().to_vec(),
+            interval_class_vector: pcset.interval_class_vector(),
+            is_inversionally_symmetric,
+            complement: complement.pitch_classes().to_vec(),
+            complement_prime_form: complement.prime_form(),
+            z_related_set_classes: z_related
+                .iter()
+                .map(|set_class| set_class.pitch_classes().to_vec())
+                .collect(),
+        }))?;
+        return Ok(());
+    }
+
+    app.writeln(format_args!(
+        "---- Pitch-class set {} of {}-EDO ----",
+        pcset, num_steps_per_octave
+    ))?;
+    app.writeln(format_args!(""))?;
+    app.writeln(format_args!(
+        "Notes: {}",
+        pcset.note_names(&temperament).join(", ")
+    ))?;
+    app.writeln(format_args!(
+        "Normal form: {}",
+        format_list(&pcset.normal_form())
+    ))?;
+    app.writeln(format_args!("Prime form: {}", prime_form))?;
+    app.writeln(format_args!(
+        "Interval-class vector: {}",
+        format_vector(&pcset.interval_class_vector())
+    ))?;
+    app.writeln(format_args!(
+        "Inversionally symmetric: {}",
+        if is_inversionally_symmetric {
+            "yes"
+        } else {
+            "no"
+        }
+    ))?;
+    app.writeln(format_args!("Complement: {}", complement))?;
+    app.writeln(format_args!(
+        "Complement prime form: {}",
+        format_list(&complement.prime_form())
+    ))?;
+    app.writeln(format_args!(
+        "Z-related set classes: {}",
+        if z_related.is_empty() {
+            "-".to_owned()
+        } else {
+            z_related
+                .iter()
+                .map(|set_class| set_class.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+    ))?;
+
+    Ok(())
+}
+
+fn format_list(pitch_classes: &[u16]) -> String {
+    let items = pitch_classes
+        .iter()
+        .map(|pitch_class| pitch_class.to_string())
+        .collect::<Vec<_>>();
+    format!("[{}]", items.join(", "))
+}
+
+fn format_vector(vector: &[u16]) -> String {
+    let items = vector
+        .iter()
+        .map(|count| count.to_string())
+        .collect::<Vec<_>>();
+    format!("<{}>", items.join(", "))
+}
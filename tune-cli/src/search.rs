@@ -0,0 +1,128 @@
+use crate::{
+    dto::{ArchiveEntryDto, TuneDto},
+    dupes, shared, App, CliResult,
+};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use tune::{
+    archive::{Archive, ArchiveEntry, ArchiveQuery},
+    ratio::Ratio,
+};
+
+#[derive(StructOpt)]
+pub(crate) struct SearchOptions {
+    /// Exact number of notes
+    #[structopt(long = "size")]
+    size: Option<usize>,
+
+    /// Period of the scale, e.g. 2 or 3
+    #[structopt(long = "period")]
+    period: Option<Ratio>,
+
+    /// Largest acceptable prime limit (scales must consist of fractions)
+    #[structopt(long = "prime-lim")]
+    prime_limit: Option<u32>,
+
+    /// Interval the scale must contain, e.g. 7/4. Can be repeated
+    #[structopt(long = "contains", number_of_values = 1)]
+    containing: Vec<Ratio>,
+
+    /// Only list moments of symmetry
+    #[structopt(long = "mos")]
+    mos: bool,
+
+    /// Only list subsets of the given EDO
+    #[structopt(long = "edo")]
+    edo_subset: Option<u16>,
+
+    /// Largest acceptable deviation of the pitches from the criteria above
+    #[structopt(long = "tolerance", default_value = "1c")]
+    tolerance: Ratio,
+
+    /// Directory to search for scl files recursively
+    dir: PathBuf,
+}
+
+impl SearchOptions {
+    pub fn run(&self, app: &mut App) -> CliResult<()> {
+        if self.edo_subset == Some(0) {
+            return Err("EDO must have at least one step".to_owned().into());
+        }
+
+        let archive = Archive::import_dir(&self.dir)?;
+        for (file_name, err) in archive.errors() {
+            app.errln(format_args!(
+                "Skipping {}: {}",
+                dupes::display_path(file_name),
+                shared::describe_scl_import_error(err)
+            ))?;
+        }
+
+        let query = self.to_query();
+        let results = archive.search(&query).collect::<Vec<_>>();
+
+        if app.json {
+            let dtos = results
+                .iter()
+                .map(|entry| ArchiveEntryDto {
+                    file_name: self.file_name_of(entry),
+                    description: entry.scl.description().to_owned(),
+                    size: entry.scl.size(),
+                    period_in_cents: entry.scl.period().as_cents(),
+                    prime_limit: entry.prime_limit,
+                    is_mos: entry.is_mos,
+                    edo: entry.edo,
+                })
+                .collect();
+            app.write_json(TuneDto::Archive(dtos))?;
+            return Ok(());
+        }
+
+        app.writeln(format_args!(
+            "---- {} of {} scales in {} ----",
+            results.len(),
+            archive.entries().len(),
+            self.dir.display()
+        ))?;
+        app.writeln(format_args!(""))?;
+        app.writeln(format_args!(
+            "  {:<32} | {:>4} | {:>9} | {:>5} | {:>3} | {:>3} | Description",
+            "File", "Size", "Period", "Limit", "MOS", "EDO"
+        ))?;
+        for entry in results {
+            app.writeln(format_args!(
+                "  {:<32} | {:>4} | {:>#9.1} | {:>5} | {:>3} | {:>3} | {}",
+                self.file_name_of(entry),
+                entry.scl.size(),
+                entry.scl.period(),
+                option_to_string(entry.prime_limit),
+                if entry.is_mos { "yes" } else { "no" },
+                option_to_string(entry.edo),
+                entry.scl.description(),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    fn to_query(&self) -> ArchiveQuery {
+        let mut query = ArchiveQuery::any().with_tolerance(self.tolerance);
+        query.size = self.size;
+        query.period = self.period;
+        query.max_prime_limit = self.prime_limit;
+        query.containing = self.containing.clone();
+        query.mos = if self.mos { Some(true) } else { None };
+        query.edo_subset = self.edo_subset;
+        query
+    }
+
+    fn file_name_of(&self, entry: &ArchiveEntry) -> String {
+        dupes::display_path(entry.path.strip_prefix(&self.dir).unwrap_or(&entry.path))
+    }
+}
+
+fn option_to_string(option: Option<impl ToString>) -> String {
+    option
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "-".to_owned())
+}
@@ -2,11 +2,13 @@
 
 use crate::CliError;
 use midir::{MidiInput, MidiOutput};
-use std::{fs::File, io, path::PathBuf};
-use structopt::StructOpt;
+use std::{fs::File, io, iter, path::PathBuf, str::FromStr};
+use structopt::{clap::AppSettings, StructOpt};
 use tune::{
+    presets,
     ratio::{Ratio, RatioExpression, RatioExpressionVariant},
     scala::{self, Scl, SclBuildError, SclImportError},
+    tuning::Approximation,
 };
 
 #[derive(StructOpt)]
@@ -51,51 +53,234 @@ pub enum SclCommand {
         subharmonics: bool,
     },
 
+    /// Historical temperament or just intonation scale. See `tune presets` for a list of all presets
+    #[structopt(name = "preset")]
+    Preset {
+        /// Name of the preset, e.g. werckmeister-3
+        name: String,
+    },
+
     /// Import scl file
     #[structopt(name = "import")]
     Import {
         /// The location of the file to import
         file_name: PathBuf,
     },
+
+    /// [post] Rotate a scale to the mode starting at the given degree
+    #[structopt(name = "mode", setting = AppSettings::TrailingVarArg)]
+    Mode {
+        /// Degree the mode starts on, e.g. 3
+        degree: i32,
+
+        /// Scale expression to transform, e.g. rank2 3/2 6
+        #[structopt(required = true, allow_hyphen_values = true)]
+        scale: Vec<String>,
+    },
+
+    /// [post] Mirror the steps of a scale
+    #[structopt(name = "invert", setting = AppSettings::TrailingVarArg)]
+    Invert {
+        /// Scale expression to transform, e.g. rank2 3/2 6
+        #[structopt(required = true, allow_hyphen_values = true)]
+        scale: Vec<String>,
+    },
+
+    /// [post] Take a subset of the degrees of a scale. The last degree becomes the new period
+    #[structopt(name = "subset", setting = AppSettings::TrailingVarArg)]
+    Subset {
+        /// Comma-separated degrees to keep, e.g. 2,4,5,7,9,11,12
+        degrees: Degrees,
+
+        /// Scale expression to transform, e.g. steps 1:12:2
+        #[structopt(required = true, allow_hyphen_values = true)]
+        scale: Vec<String>,
+    },
+
+    /// [post] Merge the pitches of an scl file into the period of a scale
+    #[structopt(name = "merge", setting = AppSettings::TrailingVarArg)]
+    Merge {
+        /// The location of the scl file to merge
+        file_name: PathBuf,
+
+        /// Scale expression to transform, e.g. rank2 3/2 6
+        #[structopt(required = true, allow_hyphen_values = true)]
+        scale: Vec<String>,
+    },
+
+    /// [post] Stretch or compress a scale to a new period
+    #[structopt(name = "stretch", setting = AppSettings::TrailingVarArg)]
+    Stretch {
+        /// The new period, e.g. 1203c
+        period: Ratio,
+
+        /// Scale expression to transform, e.g. rank2 3/2 6
+        #[structopt(required = true, allow_hyphen_values = true)]
+        scale: Vec<String>,
+    },
+
+    /// [post] Temper all degrees of a scale to the nearest steps of an EDO
+    #[structopt(name = "temper", setting = AppSettings::TrailingVarArg)]
+    Temper {
+        /// Number of steps per octave, e.g. 22
+        num_steps_per_octave: u16,
+
+        /// Scale expression to transform, e.g. rank2 3/2 6
+        #[structopt(required = true, allow_hyphen_values = true)]
+        scale: Vec<String>,
+    },
+
+    /// [post] Replace the steps of a scale with equal steps of the same period
+    #[structopt(name = "equalize", setting = AppSettings::TrailingVarArg)]
+    Equalize {
+        /// Scale expression to transform, e.g. rank2 3/2 6
+        #[structopt(required = true, allow_hyphen_values = true)]
+        scale: Vec<String>,
+    },
 }
 
 impl SclCommand {
     pub fn to_scl(&self, description: Option<String>) -> Result<Scl, CliError> {
+        self.to_scl_with_tempering_report(description)
+            .map(|(scl, _)| scl)
+    }
+
+    /// Like [`SclCommand::to_scl`] but also returns the tempering errors if `temper` is the outermost post-processor.
+    pub fn to_scl_with_tempering_report(
+        &self,
+        description: Option<String>,
+    ) -> Result<(Scl, Option<TemperingReport>), CliError> {
         Ok(match self {
-            SclCommand::Steps { items } => create_custom_scale(description, items)?,
+            SclCommand::Steps { items } => (create_custom_scale(description, items)?, None),
             &SclCommand::Rank2Temperament {
                 generator,
                 num_pos_generations,
                 num_neg_generations,
                 period,
-            } => scala::create_rank2_temperament_scale(
-                description,
-                generator,
-                num_pos_generations,
-                num_neg_generations,
-                period,
-            )?,
+            } => (
+                scala::create_rank2_temperament_scale(
+                    description,
+                    generator,
+                    num_pos_generations,
+                    num_neg_generations,
+                    period,
+                )?,
+                None,
+            ),
             &SclCommand::HarmonicSeries {
                 lowest_harmonic,
                 number_of_notes,
                 subharmonics,
-            } => scala::create_harmonics_scale(
-                description,
-                u32::from(lowest_harmonic),
-                u32::from(number_of_notes.unwrap_or(lowest_harmonic)),
-                subharmonics,
-            )?,
+            } => (
+                scala::create_harmonics_scale(
+                    description,
+                    u32::from(lowest_harmonic),
+                    u32::from(number_of_notes.unwrap_or(lowest_harmonic)),
+                    subharmonics,
+                )?,
+                None,
+            ),
+            SclCommand::Preset { name } => {
+                let preset = presets::find_preset(name).ok_or_else(|| {
+                    format!(
+                        "Unknown preset '{}'. Use `tune presets` to list all presets",
+                        name
+                    )
+                })?;
+                (with_description(preset.to_scl()?, description), None)
+            }
             SclCommand::Import { file_name } => {
                 let mut scale = import_scl_file(&file_name)?;
                 if let Some(description) = description {
                     scale.set_description(description)
                 }
-                scale
+                (scale, None)
+            }
+            SclCommand::Mode { degree, scale } => {
+                let scl = parse_scl(scale)?;
+                (with_description(scl.mode(*degree)?, description), None)
+            }
+            SclCommand::Invert { scale } => {
+                let scl = parse_scl(scale)?;
+                (with_description(scl.inverted()?, description), None)
+            }
+            SclCommand::Subset { degrees, scale } => {
+                let scl = parse_scl(scale)?;
+                (with_description(scl.subset(&degrees.0)?, description), None)
+            }
+            SclCommand::Merge { file_name, scale } => {
+                let scl = parse_scl(scale)?;
+                (
+                    with_description(scl.merged(&import_scl_file(file_name)?)?, description),
+                    None,
+                )
+            }
+            SclCommand::Stretch { period, scale } => {
+                let scl = parse_scl(scale)?;
+                (
+                    with_description(scl.with_period(*period)?, description),
+                    None,
+                )
+            }
+            SclCommand::Temper {
+                num_steps_per_octave,
+                scale,
+            } => {
+                let scl = parse_scl(scale)?;
+                let (tempered, approximations) = scl.tempered_to_edo(*num_steps_per_octave)?;
+                (
+                    with_description(tempered, description),
+                    Some(approximations),
+                )
+            }
+            SclCommand::Equalize { scale } => {
+                let scl = parse_scl(scale)?;
+                (with_description(scl.equalized()?, description), None)
             }
         })
     }
 }
 
+/// The deviations of the original degrees from the EDO steps they were tempered to.
+///
+/// Only available if `temper` is the outermost post-processor since all other post-processors change the degrees of the scale.
+pub type TemperingReport = Vec<Approximation<i32>>;
+
+/// Parses the scale expression following a post-processor lazily since `clap` cannot build recursive subcommands.
+fn parse_scl(args: &[String]) -> Result<Scl, CliError> {
+    let command =
+        SclCommand::from_iter_safe(iter::once("scl").chain(args.iter().map(String::as_str)))
+            .map_err(|error| error.message)?;
+    command.to_scl(None)
+}
+
+pub struct Degrees(Vec<i32>);
+
+impl FromStr for Degrees {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|degree| {
+                degree.trim().parse().map_err(|_| {
+                    format!(
+                        "Invalid degrees '{}': Must be a comma-separated list of integers (e.g. 2,4,5,7,9,11,12)",
+                        s
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Degrees)
+    }
+}
+
+fn with_description(mut scl: Scl, description: Option<String>) -> Scl {
+    if let Some(description) = description {
+        scl.set_description(description)
+    }
+    scl
+}
+
 fn create_custom_scale(
     description: impl Into<Option<String>>,
     items: &[RatioExpression],
@@ -109,7 +294,8 @@ fn create_custom_scale(
                     continue;
                 }
             }
-            RatioExpressionVariant::Fraction { numer, denom } => {
+            RatioExpressionVariant::Fraction { numer, denom }
+            | RatioExpressionVariant::Product { numer, denom } => {
                 if let (Some(numer), Some(denom)) = (as_int(numer), as_int(denom)) {
                     builder = builder.push_fraction(numer, denom);
                     continue;
@@ -135,11 +321,15 @@ fn as_int(float: f64) -> Option<u32> {
     }
 }
 
-fn import_scl_file(file_name: &PathBuf) -> Result<Scl, String> {
+pub fn import_scl_file(file_name: &PathBuf) -> Result<Scl, String> {
     let file =
         File::open(file_name).map_err(|io_err| format!("Could not read scl file: {}", io_err))?;
 
-    Scl::import(file).map_err(|err| match err {
+    Scl::import_lossless(file).map_err(|err| describe_scl_import_error(&err))
+}
+
+pub fn describe_scl_import_error(err: &SclImportError) -> String {
+    match err {
         SclImportError::IoError(err) => format!("Could not read scl file: {}", err),
         SclImportError::ParseError { line_number, kind } => format!(
             "Could not parse scl file at line {} ({:?})",
@@ -147,23 +337,39 @@ fn import_scl_file(file_name: &PathBuf) -> Result<Scl, String> {
         ),
         SclImportError::StructuralError(err) => format!("Malformed scl file ({:?})", err),
         SclImportError::BuildError(err) => format!("Unsupported scl file ({:?})", err),
-    })
+    }
 }
 
 pub fn print_midi_devices(mut dst: impl io::Write, client_name: &str) -> io::Result<()> {
-    let midi_input = MidiInput::new(client_name).unwrap();
+    let (inputs, outputs) = list_midi_devices(client_name);
+
     writeln!(dst, "Readable MIDI devices:")?;
-    for (index, port) in midi_input.ports().iter().enumerate() {
-        let port_name = midi_input.port_name(port).unwrap();
+    for (index, port_name) in inputs.iter().enumerate() {
         writeln!(dst, "({}) {}", index, port_name)?;
     }
 
-    let midi_output = MidiOutput::new(client_name).unwrap();
     writeln!(dst, "Writable MIDI devices:")?;
-    for (index, port) in midi_output.ports().iter().enumerate() {
-        let port_name = midi_output.port_name(port).unwrap();
+    for (index, port_name) in outputs.iter().enumerate() {
         writeln!(dst, "({}) {}", index, port_name)?;
     }
 
     Ok(())
 }
+
+pub fn list_midi_devices(client_name: &str) -> (Vec<String>, Vec<String>) {
+    let midi_input = MidiInput::new(client_name).unwrap();
+    let inputs = midi_input
+        .ports()
+        .iter()
+        .map(|port| midi_input.port_name(port).unwrap())
+        .collect();
+
+    let midi_output = MidiOutput::new(client_name).unwrap();
+    let outputs = midi_output
+        .ports()
+        .iter()
+        .map(|port| midi_output.port_name(port).unwrap())
+        .collect();
+
+    (inputs, outputs)
+}
@@ -41,6 +41,21 @@ fn create_7_edo() {
     check_output!("snapshots/scale_62_steps_1_7_2.stdout", output.stdout);
 }
 
+#[test]
+fn create_7_edo_using_note_names() {
+    let output = call_cli(&["scale", "D4", "steps", "1:7:2"]);
+    check_output!("snapshots/scale_62_steps_1_7_2.stdout", output.stdout);
+}
+
+#[test]
+fn dump_7_edo_at_custom_pitch() {
+    let output = call_cli_piped(&["scale", "D4@A4/4/3", "steps", "1:7:2"], &["dump"]);
+    check_output!(
+        "snapshots/scale_D4@A4-4-3_steps_1_7_2.stdout.dump.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn dump_7_edo() {
     let output = call_cli_piped(&["scale", "62", "steps", "1:7:2"], &["dump"]);
@@ -50,6 +65,18 @@ fn dump_7_edo() {
     );
 }
 
+#[test]
+fn dump_19_edo_with_5_prime_limit() {
+    let output = call_cli_piped(
+        &["scale", "62", "steps", "1:19:2"],
+        &["dump", "-l", "81", "--prime-lim", "5", "--max-err", "10"],
+    );
+    check_output!(
+        "snapshots/scale_62_steps_1_19_2.stdout.dump_-l_81_--prime-lim_5_--max-err_10.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn create_quarter_comma_and_diff_with_shifted_31_edo() {
     let output = call_cli_piped(
@@ -112,6 +139,195 @@ fn analysis_of_16_edo() {
     check_output!("snapshots/edo_16.stdout", output.stdout);
 }
 
+#[test]
+fn analysis_of_22_edo_in_ups_and_downs_notation() {
+    let output = call_cli(&["edo", "22", "--notation", "ups"]);
+    check_output!("snapshots/edo_22_ups.stdout", output.stdout);
+}
+
+#[test]
+fn lumatone_layout_of_31_edo() {
+    let output = call_cli(&[
+        "layout",
+        "lumatone",
+        "31",
+        "--preset",
+        "bosanquet",
+        "--root",
+        "0",
+        "--notes-per-channel",
+        "31",
+    ]);
+    check_output!(
+        "snapshots/layout_lumatone_31_--preset_bosanquet_--root_0_--notes-per-channel_31.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn linnstrument_layout_of_12_edo() {
+    let output = call_cli(&[
+        "layout",
+        "linnstrument",
+        "12",
+        "--preset",
+        "wicki-hayden",
+        "--root",
+        "36",
+        "--columns",
+        "16",
+    ]);
+    check_output!(
+        "snapshots/layout_linnstrument_12_--preset_wicki-hayden_--root_36_--columns_16.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn linnstrument_layout_as_json() {
+    let output = call_cli(&["--json", "layout", "linnstrument", "5", "--columns", "4"]);
+    check_output!(
+        "snapshots/--json_layout_linnstrument_5_--columns_4.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn layouts_of_edo_without_steps() {
+    let output = call_cli(&["layout", "lumatone", "0"]);
+    check_output!("snapshots/layout_lumatone_0.stderr", output.stderr);
+
+    let output = call_cli(&["layout", "linnstrument", "0"]);
+    check_output!("snapshots/layout_linnstrument_0.stderr", output.stderr);
+}
+
+#[test]
+fn find_layouts_of_53_edo() {
+    let output = call_cli(&["layout", "find", "53", "-n", "5", "3/2", "5/4", "6/5"]);
+    check_output!(
+        "snapshots/layout_find_53_-n_5_3-2_5-4_6-5.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn find_layouts_of_12_edo_as_json() {
+    let output = call_cli(&["--json", "layout", "find", "12", "-n", "2", "3/2", "5/4"]);
+    check_output!(
+        "snapshots/--json_layout_find_12_-n_2_3-2_5-4.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn find_layouts_of_edo_without_steps() {
+    let output = call_cli(&["layout", "find", "0", "3/2"]);
+    check_output!("snapshots/layout_find_0_3-2.stderr", output.stderr);
+}
+
+#[test]
+fn draw_keyboard_layout_of_31_edo_as_svg() {
+    let svg_file = env::temp_dir().join("tune-cli-edo-test.svg");
+
+    call_cli(&["edo", "31", "--svg", svg_file.to_str().unwrap()]);
+
+    check_output!("snapshots/edo_31_--svg.svg", fs::read(svg_file).unwrap());
+}
+
+#[test]
+fn draw_just_intonation_scale_as_circle_and_lattice() {
+    let circle_file = env::temp_dir().join("tune-cli-scl-circle-test.svg");
+    let lattice_file = env::temp_dir().join("tune-cli-scl-lattice-test.svg");
+    let steps = ["steps", "9/8", "5/4", "4/3", "3/2", "5/3", "15/8", "2"];
+
+    call_cli(&[&["scl", "--svg", circle_file.to_str().unwrap()], &steps[..]].concat());
+    call_cli(
+        &[
+            &[
+                "scl",
+                "--svg",
+                lattice_file.to_str().unwrap(),
+                "--diagram",
+                "lattice",
+            ],
+            &steps[..],
+        ]
+        .concat(),
+    );
+
+    check_output!(
+        "snapshots/scl_--svg_steps_9-8_5-4_4-3_3-2_5-3_15-8_2.svg",
+        fs::read(circle_file).unwrap()
+    );
+    check_output!(
+        "snapshots/scl_--svg_--diagram_lattice_steps_9-8_5-4_4-3_3-2_5-3_15-8_2.svg",
+        fs::read(lattice_file).unwrap()
+    );
+}
+
+#[test]
+fn list_presets() {
+    let output = call_cli(&["presets"]);
+    check_output!("snapshots/presets.stdout", output.stdout);
+}
+
+#[test]
+fn list_presets_as_json() {
+    let output = call_cli(&["--json", "presets"]);
+    check_output!("snapshots/--json_presets.stdout", output.stdout);
+}
+
+#[test]
+fn create_werckmeister_3_preset() {
+    let output = call_cli(&["scl", "preset", "werckmeister-3"]);
+    check_output!("snapshots/scl_preset_werckmeister-3.stdout", output.stdout);
+}
+
+#[test]
+fn analyze_all_interval_tetrad_in_12_edo() {
+    let output = call_cli(&["pcset", "analyze", "12", "0,1,4,6"]);
+    check_output!("snapshots/pcset_analyze_12_0,1,4,6.stdout", output.stdout);
+}
+
+#[test]
+fn analyze_septimal_tetrad_in_31_edo() {
+    let output = call_cli(&["pcset", "analyze", "31", "0,10,18,25"]);
+    check_output!(
+        "snapshots/pcset_analyze_31_0,10,18,25.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn analyze_all_interval_tetrad_in_12_edo_as_json() {
+    let output = call_cli(&["--json", "pcset", "analyze", "12", "0,1,4,6"]);
+    check_output!(
+        "snapshots/--json_pcset_analyze_12_0,1,4,6.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn list_tetrachords_of_12_edo() {
+    let output = call_cli(&["pcset", "classes", "12", "4"]);
+    check_output!("snapshots/pcset_classes_12_4.stdout", output.stdout);
+}
+
+#[test]
+fn list_trichords_of_12_edo_as_json() {
+    let output = call_cli(&["--json", "pcset", "classes", "12", "3"]);
+    check_output!("snapshots/--json_pcset_classes_12_3.stdout", output.stdout);
+}
+
+#[test]
+fn create_scl_from_pcset_of_19_edo() {
+    let output = call_cli(&["pcset", "scl", "19", "0,3,6,8,11,14,17"]);
+    check_output!(
+        "snapshots/pcset_scl_19_0,3,6,8,11,14,17.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn crate_custom_scale() {
     let output = call_cli(&[
@@ -132,3 +348,332 @@ fn crate_custom_scale() {
         output.stdout
     );
 }
+
+#[test]
+fn create_custom_scale_from_products() {
+    let output = call_cli(&["scl", "steps", "9/8", "3/2*5/6", "3/2", "3/2*5/4", "2"]);
+    check_output!(
+        "snapshots/scl_steps_9-8_3-2x5-6_3-2_3-2x5-4_2.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn find_edo_for_11_limit_targets() {
+    let output = call_cli(&[
+        "find-edo",
+        "--consistent",
+        "-n",
+        "5",
+        "--max",
+        "72",
+        "3/2",
+        "5/4",
+        "7/4",
+        "11/8",
+    ]);
+    check_output!(
+        "snapshots/find-edo_--consistent_-n_5_--max_72_3-2_5-4_7-4_11-8.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn create_mode_of_just_intonation_scale() {
+    let output = call_cli(&[
+        "scl", "mode", "5", "steps", "9/8", "5/4", "4/3", "3/2", "5/3", "15/8", "2",
+    ]);
+    check_output!(
+        "snapshots/scl_mode_5_steps_9-8_5-4_4-3_3-2_5-3_15-8_2.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn create_major_subset_of_12_edo() {
+    let output = call_cli(&["scl", "subset", "2,4,5,7,9,11,12", "steps", "1:12:2"]);
+    check_output!(
+        "snapshots/scl_subset_2-4-5-7-9-11-12_steps_1_12_2.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn temper_just_intonation_scale_to_22_edo() {
+    let output = call_cli(&[
+        "scl", "temper", "22", "steps", "9/8", "5/4", "4/3", "3/2", "5/3", "15/8", "2",
+    ]);
+    check_output!(
+        "snapshots/scl_temper_22_steps_9-8_5-4_4-3_3-2_5-3_15-8_2.stdout",
+        output.stdout
+    );
+    check_output!(
+        "snapshots/scl_temper_22_steps_9-8_5-4_4-3_3-2_5-3_15-8_2.stderr",
+        output.stderr
+    );
+}
+
+#[test]
+fn omit_tempering_errors_of_nested_temper_post_processor() {
+    let output = call_cli(&[
+        "scl", "mode", "2", "temper", "22", "steps", "9/8", "5/4", "4/3", "3/2", "5/3", "15/8", "2",
+    ]);
+    check_output!(
+        "snapshots/scl_mode_2_temper_22_steps_9-8_5-4_4-3_3-2_5-3_15-8_2.stdout",
+        output.stdout
+    );
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn temper_to_edo_without_steps() {
+    let output = call_cli(&["scl", "temper", "0", "steps", "3/2", "2"]);
+    check_output!("snapshots/scl_temper_0_steps_3-2_2.stderr", output.stderr);
+}
+
+#[test]
+fn import_scl_file_losslessly() {
+    let output = call_cli(&["scl", "import", "tests/scales/just_minor_cents.scl"]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        fs::read_to_string("tests/scales/just_minor_cents.scl").unwrap()
+    );
+}
+
+#[test]
+fn temper_imported_scl_file_keeping_comments() {
+    let output = call_cli(&[
+        "scl",
+        "temper",
+        "12",
+        "import",
+        "tests/scales/just_minor_cents.scl",
+    ]);
+    check_output!(
+        "snapshots/scl_temper_12_import_tests-scales-just_minor_cents.scl.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn export_ascl_with_reference_pitch_and_source() {
+    let output = call_cli(&[
+        "scl",
+        "--format",
+        "ascl",
+        "--ref",
+        "62",
+        "--root",
+        "60",
+        "--source",
+        "Zarlino",
+        "import",
+        "tests/scales/just_minor_cents.scl",
+    ]);
+    check_output!(
+        "snapshots/scl_--format_ascl_--ref_62_--root_60_import_tests-scales-just_minor_cents.scl.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn convert_scl_and_kbm_to_ascl() {
+    let output = call_cli(&[
+        "convert",
+        "--from",
+        "tests/scales/just_minor_cents.scl",
+        "--from",
+        "tests/scales/d4.kbm",
+        "--to",
+        "ascl",
+    ]);
+    check_output!(
+        "snapshots/convert_--from_just_minor_cents.scl_--from_d4.kbm_--to_ascl.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn convert_syx_file_back_to_kbm() {
+    let syx_file = env::temp_dir().join("tune-cli-convert-test.syx");
+    let syx_file = syx_file.to_str().unwrap();
+
+    call_cli(&[
+        "convert",
+        "--from",
+        "tests/scales/just_minor_cents.scl",
+        "--from",
+        "tests/scales/d4.kbm",
+        "--to",
+        syx_file,
+    ]);
+
+    let output = call_cli(&["convert", "--from", syx_file, "--to", "kbm"]);
+    check_output!(
+        "snapshots/convert_--from_syx_--to_kbm.stdout",
+        output.stdout
+    );
+    check_output!(
+        "snapshots/convert_--from_syx_--to_kbm.stderr",
+        output.stderr
+    );
+}
+
+#[test]
+fn convert_scl_without_kbm_to_json() {
+    let output = call_cli(&[
+        "convert",
+        "--from",
+        "tests/scales/just_minor_cents.scl",
+        "--to",
+        "json",
+    ]);
+    check_output!(
+        "snapshots/convert_--from_just_minor_cents.scl_--to_json.stderr",
+        output.stderr
+    );
+}
+
+#[test]
+fn find_equivalent_scl_files() {
+    let output = call_cli(&["find-dupes", "tests/scales"]);
+    check_output!("snapshots/find-dupes_tests-scales.stdout", output.stdout);
+    check_output!("snapshots/find-dupes_tests-scales.stderr", output.stderr);
+}
+
+#[test]
+fn search_5_limit_heptatonic_scales() {
+    let output = call_cli(&[
+        "search",
+        "tests/scales",
+        "--size",
+        "7",
+        "--prime-lim",
+        "5",
+        "--contains",
+        "5/3",
+    ]);
+    check_output!(
+        "snapshots/search_tests-scales_--size_7_--prime-lim_5_--contains_5_3.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn search_mos_scales_as_json() {
+    let output = call_cli(&["search", "tests/scales", "--mos", "--json"]);
+    check_output!(
+        "snapshots/search_tests-scales_--mos_--json.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn search_subsets_of_edo_without_steps() {
+    let output = call_cli(&["search", "tests/scales", "--edo", "0"]);
+    check_output!(
+        "snapshots/search_tests-scales_--edo_0.stderr",
+        output.stderr
+    );
+}
+
+#[test]
+fn find_septimal_tetrad_in_22_edo() {
+    let output = call_cli(&[
+        "chords", "--chord", "4:5:6:7", "69@440Hz", "-r", "62", "steps", "1:22:2",
+    ]);
+    check_output!(
+        "snapshots/chords_--chord_4-5-6-7_69@440Hz_-r_62_steps_1_22_2.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn find_9_odd_limit_triads_in_22_edo() {
+    let output = call_cli(&["chords", "--odd-lim", "9", "62", "steps", "1:22:2"]);
+    check_output!(
+        "snapshots/chords_--odd-lim_9_62_steps_1_22_2.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dissonance_curve_of_sawtooth() {
+    let output = call_cli(&["dissonance", "--step", "20c"]);
+    check_output!("snapshots/dissonance_--step_20c.stdout", output.stdout);
+}
+
+#[test]
+fn dissonance_curve_of_custom_spectrum() {
+    let output = call_cli(&[
+        "dissonance",
+        "--spectrum",
+        "1,2.76:0.5,5.4:0.25,8.93:0.125",
+        "--step",
+        "50c",
+    ]);
+    check_output!(
+        "snapshots/dissonance_--spectrum_1,2.76-0.5,5.4-0.25,8.93-0.125_--step_50c.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dissonance_of_just_major_scale_with_square_timbre() {
+    let output = call_cli(&[
+        "dissonance",
+        "--timbre",
+        "square",
+        "steps",
+        "9/8",
+        "5/4",
+        "4/3",
+        "3/2",
+        "5/3",
+        "15/8",
+        "2",
+    ]);
+    check_output!(
+        "snapshots/dissonance_--timbre_square_steps_9-8_5-4_4-3_3-2_5-3_15-8_2.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn print_scl_as_json() {
+    let output = call_cli(&["--json", "scl", "steps", "9/8", "5/4", "700c", "2"]);
+    check_output!(
+        "snapshots/--json_scl_steps_9-8_5-4_700c_2.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn find_edos_as_json() {
+    let output = call_cli(&["--json", "find-edo", "-n", "2", "3/2", "5/4"]);
+    check_output!(
+        "snapshots/--json_find-edo_-n_2_3-2_5-4.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn convert_unversioned_json_to_kbm() {
+    let output = call_cli(&[
+        "convert",
+        "--from",
+        "tests/scales/legacy.json",
+        "--to",
+        "kbm",
+    ]);
+    check_output!(
+        "snapshots/convert_--from_legacy.json_--to_kbm.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn publish_json_schema() {
+    let output = call_cli(&["schema"]);
+    check_output!("../tune.schema.json", output.stdout);
+}
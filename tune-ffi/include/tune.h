@@ -0,0 +1,127 @@
+#ifndef TUNE_H
+#define TUNE_H
+
+/* This file is generated by cbindgen. Do not edit it manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of a call to the `tune` C API.
+typedef enum TuneStatus {
+  TUNE_STATUS_OK = 0,
+  // A required pointer argument was null.
+  TUNE_STATUS_NULL_POINTER = 1,
+  // An argument had an invalid value, e.g. a negative pitch.
+  TUNE_STATUS_INVALID_ARGUMENT = 2,
+  // The input could not be read.
+  TUNE_STATUS_IO_ERROR = 3,
+  // A line of the input could not be parsed.
+  TUNE_STATUS_PARSE_ERROR = 4,
+  // The input is incomplete or inconsistent.
+  TUNE_STATUS_STRUCTURAL_ERROR = 5,
+  // The input describes a scale which is not supported.
+  TUNE_STATUS_BUILD_ERROR = 6,
+  // A MIDI value was outside the allowed range.
+  TUNE_STATUS_OUT_OF_RANGE = 7,
+  // The provided buffer is too small. The required size has been written to `out_len`.
+  TUNE_STATUS_BUFFER_TOO_SMALL = 8,
+  // An unexpected internal error occurred.
+  TUNE_STATUS_PANIC = 9,
+} TuneStatus;
+
+// Opaque handle to a Scala keyboard mapping.
+typedef struct TuneKbm TuneKbm;
+
+// Opaque handle to a Scala scale file.
+typedef struct TuneScl TuneScl;
+
+// Opaque handle to the tuning defined by a scale and a keyboard mapping.
+typedef struct TuneTuning TuneTuning;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Imports the content of a scl file. The original notation and comments are preserved on export.
+//
+// On success, `*out_scl` receives a handle which must be released with `tune_scl_free`.
+enum TuneStatus tune_scl_import(const uint8_t *data, size_t len, struct TuneScl **out_scl);
+
+// Writes the scl file representation of `scl` as a NUL-terminated string.
+enum TuneStatus tune_scl_export(const struct TuneScl *scl,
+                                char *buf,
+                                size_t buf_len,
+                                size_t *out_len);
+
+// Writes the number of notes per period of `scl` to `*out_size`.
+enum TuneStatus tune_scl_size(const struct TuneScl *scl, size_t *out_size);
+
+// Releases a handle created by `tune_scl_import`. Passing null is a no-op.
+void tune_scl_free(struct TuneScl *scl);
+
+// Creates a linear keyboard mapping where `root_key` is mapped to degree 0 and `ref_key` sounds at `ref_pitch_hz`.
+//
+// On success, `*out_kbm` receives a handle which must be released with `tune_kbm_free`.
+enum TuneStatus tune_kbm_new(int32_t root_key,
+                             int32_t ref_key,
+                             double ref_pitch_hz,
+                             struct TuneKbm **out_kbm);
+
+// Imports the content of a kbm file. Only linear mappings are supported.
+//
+// On success, `*out_kbm` receives a handle which must be released with `tune_kbm_free`.
+enum TuneStatus tune_kbm_import(const uint8_t *data, size_t len, struct TuneKbm **out_kbm);
+
+// Writes the kbm file representation of `kbm` as a NUL-terminated string.
+enum TuneStatus tune_kbm_export(const struct TuneKbm *kbm,
+                                char *buf,
+                                size_t buf_len,
+                                size_t *out_len);
+
+// Releases a handle created by `tune_kbm_new` or `tune_kbm_import`. Passing null is a no-op.
+void tune_kbm_free(struct TuneKbm *kbm);
+
+// Creates the tuning defined by `scl` and `kbm`. Both handles are copied and can be released afterwards.
+//
+// On success, `*out_tuning` receives a handle which must be released with `tune_tuning_free`.
+enum TuneStatus tune_tuning_new(const struct TuneScl *scl,
+                                const struct TuneKbm *kbm,
+                                struct TuneTuning **out_tuning);
+
+// Writes the pitch of the given MIDI key to `*out_pitch_hz`.
+enum TuneStatus tune_tuning_pitch_of(const struct TuneTuning *tuning,
+                                     int32_t key,
+                                     double *out_pitch_hz);
+
+// Finds the MIDI key whose pitch is closest to `pitch_hz` and writes it to `*out_key`.
+//
+// The deviation from the key's pitch is written to `*out_deviation_cents` unless the pointer is null.
+enum TuneStatus tune_tuning_find_by_pitch(const struct TuneTuning *tuning,
+                                          double pitch_hz,
+                                          int32_t *out_key,
+                                          double *out_deviation_cents);
+
+// Writes a MIDI Tuning Standard Single Note Tuning Change message retuning all keys of `tuning` to `buf`.
+//
+// Keys whose pitch cannot be represented by the message are left untouched.
+enum TuneStatus tune_tuning_single_note_tuning_message(const struct TuneTuning *tuning,
+                                                       uint8_t device_id,
+                                                       uint8_t tuning_program,
+                                                       uint8_t *buf,
+                                                       size_t buf_len,
+                                                       size_t *out_len);
+
+// Releases a handle created by `tune_tuning_new`. Passing null is a no-op.
+void tune_tuning_free(struct TuneTuning *tuning);
+
+// Writes the description of the most recent error on the current thread as a NUL-terminated string.
+enum TuneStatus tune_last_error_message(char *buf, size_t buf_len, size_t *out_len);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* TUNE_H */
@@ -0,0 +1,431 @@
+//! C ABI for the [tune](https://crates.io/crates/tune) library.
+//!
+//! The matching C header is located at `include/tune.h`.
+//!
+//! # Conventions
+//!
+//! - Every function returns a [`TuneStatus`]. On failure, out parameters are left untouched and a description of the error can be obtained via [`tune_last_error_message`].
+//! - Handles created by a `tune_*_import` or `tune_*_new` function are owned by the caller and must be released with the matching `tune_*_free` function.
+//! - Pointer arguments must either be null or valid for the duration of the call. Null pointers are reported as [`TuneStatus::NullPointer`].
+//! - Functions writing to a caller-provided buffer report the required buffer size in `out_len`, even if the buffer is too small. Passing a null buffer with `buf_len == 0` can be used to query the size.
+
+#![allow(clippy::missing_safety_doc)]
+
+use std::{
+    cell::RefCell,
+    os::raw::c_char,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+use tune::{
+    key::PianoKey,
+    mts::{DeviceId, SingleNoteTuningChangeMessage, TuningError},
+    pitch::{Pitch, ReferencePitch},
+    scala::{Kbm, KbmImportError, Scl, SclImportError},
+    tuning::{Approximation, Tuning},
+};
+
+/// Opaque handle to a Scala scale file.
+pub struct TuneScl(Scl);
+
+/// Opaque handle to a Scala keyboard mapping.
+pub struct TuneKbm(Kbm);
+
+/// Opaque handle to the tuning defined by a scale and a keyboard mapping.
+pub struct TuneTuning(Scl, Kbm);
+
+/// Result of a call to the `tune` C API.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TuneStatus {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// An argument had an invalid value, e.g. a negative pitch.
+    InvalidArgument = 2,
+    /// The input could not be read.
+    IoError = 3,
+    /// A line of the input could not be parsed.
+    ParseError = 4,
+    /// The input is incomplete or inconsistent.
+    StructuralError = 5,
+    /// The input describes a scale which is not supported.
+    BuildError = 6,
+    /// A MIDI value was outside the allowed range.
+    OutOfRange = 7,
+    /// The provided buffer is too small. The required size has been written to `out_len`.
+    BufferTooSmall = 8,
+    /// An unexpected internal error occurred.
+    Panic = 9,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+struct Error {
+    status: TuneStatus,
+    message: String,
+}
+
+impl Error {
+    fn new(status: TuneStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<SclImportError> for Error {
+    fn from(err: SclImportError) -> Self {
+        match err {
+            SclImportError::IoError(err) => {
+                Error::new(TuneStatus::IoError, format!("Could not read scl: {}", err))
+            }
+            SclImportError::ParseError { line_number, kind } => Error::new(
+                TuneStatus::ParseError,
+                format!("Could not parse scl at line {} ({:?})", line_number, kind),
+            ),
+            SclImportError::StructuralError(err) => Error::new(
+                TuneStatus::StructuralError,
+                format!("Malformed scl ({:?})", err),
+            ),
+            SclImportError::BuildError(err) => Error::new(
+                TuneStatus::BuildError,
+                format!("Unsupported scl ({:?})", err),
+            ),
+        }
+    }
+}
+
+impl From<KbmImportError> for Error {
+    fn from(err: KbmImportError) -> Self {
+        match err {
+            KbmImportError::IoError(err) => {
+                Error::new(TuneStatus::IoError, format!("Could not read kbm: {}", err))
+            }
+            KbmImportError::ParseError { line_number, kind } => Error::new(
+                TuneStatus::ParseError,
+                format!("Could not parse kbm at line {} ({:?})", line_number, kind),
+            ),
+            KbmImportError::StructuralError(err) => Error::new(
+                TuneStatus::StructuralError,
+                format!("Unsupported kbm ({:?})", err),
+            ),
+        }
+    }
+}
+
+impl From<TuningError> for Error {
+    fn from(err: TuningError) -> Self {
+        Error::new(
+            TuneStatus::OutOfRange,
+            format!("Could not create MTS message ({:?})", err),
+        )
+    }
+}
+
+/// Imports the content of a scl file. The original notation and comments are preserved on export.
+///
+/// On success, `*out_scl` receives a handle which must be released with `tune_scl_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tune_scl_import(
+    data: *const u8,
+    len: usize,
+    out_scl: *mut *mut TuneScl,
+) -> TuneStatus {
+    guard(|| {
+        let data = input(data, len)?;
+        let out_scl = output(out_scl)?;
+        *out_scl = Box::into_raw(Box::new(TuneScl(Scl::import_lossless(data)?)));
+        Ok(())
+    })
+}
+
+/// Writes the scl file representation of `scl` as a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tune_scl_export(
+    scl: *const TuneScl,
+    buf: *mut c_char,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> TuneStatus {
+    guard(|| {
+        let scl = handle(scl)?;
+        write_str(&scl.0.export().to_string(), buf, buf_len, out_len)
+    })
+}
+
+/// Writes the number of notes per period of `scl` to `*out_size`.
+#[no_mangle]
+pub unsafe extern "C" fn tune_scl_size(scl: *const TuneScl, out_size: *mut usize) -> TuneStatus {
+    guard(|| {
+        let scl = handle(scl)?;
+        *output(out_size)? = scl.0.size();
+        Ok(())
+    })
+}
+
+/// Releases a handle created by `tune_scl_import`. Passing null is a no-op.
+#[no_mangle]
+pub unsafe extern "C" fn tune_scl_free(scl: *mut TuneScl) {
+    release(scl)
+}
+
+/// Creates a linear keyboard mapping where `root_key` is mapped to degree 0 and `ref_key` sounds at `ref_pitch_hz`.
+///
+/// On success, `*out_kbm` receives a handle which must be released with `tune_kbm_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tune_kbm_new(
+    root_key: i32,
+    ref_key: i32,
+    ref_pitch_hz: f64,
+    out_kbm: *mut *mut TuneKbm,
+) -> TuneStatus {
+    guard(|| {
+        let ref_pitch = pitch(ref_pitch_hz)?;
+        let out_kbm = output(out_kbm)?;
+        *out_kbm = Box::into_raw(Box::new(TuneKbm(Kbm {
+            ref_pitch: ReferencePitch::from_key_and_pitch(
+                PianoKey::from_midi_number(ref_key),
+                ref_pitch,
+            ),
+            root_key: PianoKey::from_midi_number(root_key),
+        })));
+        Ok(())
+    })
+}
+
+/// Imports the content of a kbm file. Only linear mappings are supported.
+///
+/// On success, `*out_kbm` receives a handle which must be released with `tune_kbm_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tune_kbm_import(
+    data: *const u8,
+    len: usize,
+    out_kbm: *mut *mut TuneKbm,
+) -> TuneStatus {
+    guard(|| {
+        let data = input(data, len)?;
+        let out_kbm = output(out_kbm)?;
+        *out_kbm = Box::into_raw(Box::new(TuneKbm(Kbm::import(data)?)));
+        Ok(())
+    })
+}
+
+/// Writes the kbm file representation of `kbm` as a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tune_kbm_export(
+    kbm: *const TuneKbm,
+    buf: *mut c_char,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> TuneStatus {
+    guard(|| {
+        let kbm = handle(kbm)?;
+        write_str(&kbm.0.export().to_string(), buf, buf_len, out_len)
+    })
+}
+
+/// Releases a handle created by `tune_kbm_new` or `tune_kbm_import`. Passing null is a no-op.
+#[no_mangle]
+pub unsafe extern "C" fn tune_kbm_free(kbm: *mut TuneKbm) {
+    release(kbm)
+}
+
+/// Creates the tuning defined by `scl` and `kbm`. Both handles are copied and can be released afterwards.
+///
+/// On success, `*out_tuning` receives a handle which must be released with `tune_tuning_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tune_tuning_new(
+    scl: *const TuneScl,
+    kbm: *const TuneKbm,
+    out_tuning: *mut *mut TuneTuning,
+) -> TuneStatus {
+    guard(|| {
+        let scl = handle(scl)?;
+        let kbm = handle(kbm)?;
+        let out_tuning = output(out_tuning)?;
+        *out_tuning = Box::into_raw(Box::new(TuneTuning(scl.0.clone(), kbm.0.clone())));
+        Ok(())
+    })
+}
+
+/// Writes the pitch of the given MIDI key to `*out_pitch_hz`.
+#[no_mangle]
+pub unsafe extern "C" fn tune_tuning_pitch_of(
+    tuning: *const TuneTuning,
+    key: i32,
+    out_pitch_hz: *mut f64,
+) -> TuneStatus {
+    guard(|| {
+        let tuning = handle(tuning)?;
+        let pitch = (&tuning.0, &tuning.1).pitch_of(PianoKey::from_midi_number(key));
+        *output(out_pitch_hz)? = pitch.as_hz();
+        Ok(())
+    })
+}
+
+/// Finds the MIDI key whose pitch is closest to `pitch_hz` and writes it to `*out_key`.
+///
+/// The deviation from the key's pitch is written to `*out_deviation_cents` unless the pointer is null.
+#[no_mangle]
+pub unsafe extern "C" fn tune_tuning_find_by_pitch(
+    tuning: *const TuneTuning,
+    pitch_hz: f64,
+    out_key: *mut i32,
+    out_deviation_cents: *mut f64,
+) -> TuneStatus {
+    guard(|| {
+        let tuning = handle(tuning)?;
+        let pitch = pitch(pitch_hz)?;
+        let out_key = output(out_key)?;
+        let approximation: Approximation<PianoKey> = (&tuning.0, &tuning.1).find_by_pitch(pitch);
+        *out_key = approximation.approx_value.midi_number();
+        if let Some(out_deviation_cents) = out_deviation_cents.as_mut() {
+            *out_deviation_cents = approximation.deviation.as_cents();
+        }
+        Ok(())
+    })
+}
+
+/// Writes a MIDI Tuning Standard Single Note Tuning Change message retuning all keys of `tuning` to `buf`.
+///
+/// Keys whose pitch cannot be represented by the message are left untouched.
+#[no_mangle]
+pub unsafe extern "C" fn tune_tuning_single_note_tuning_message(
+    tuning: *const TuneTuning,
+    device_id: u8,
+    tuning_program: u8,
+    buf: *mut u8,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> TuneStatus {
+    guard(|| {
+        let tuning = handle(tuning)?;
+        let device_id = DeviceId::from(device_id).ok_or_else(|| {
+            Error::new(
+                TuneStatus::OutOfRange,
+                format!("Device ID {} is out of range (0..128)", device_id),
+            )
+        })?;
+        let message = SingleNoteTuningChangeMessage::from_scale(
+            (&tuning.0, &tuning.1),
+            device_id,
+            tuning_program,
+        )?;
+        write_bytes(message.sysex_bytes(), buf, buf_len, out_len)
+    })
+}
+
+/// Releases a handle created by `tune_tuning_new`. Passing null is a no-op.
+#[no_mangle]
+pub unsafe extern "C" fn tune_tuning_free(tuning: *mut TuneTuning) {
+    release(tuning)
+}
+
+/// Writes the description of the most recent error on the current thread as a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tune_last_error_message(
+    buf: *mut c_char,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> TuneStatus {
+    let message = LAST_ERROR.with(|last_error| last_error.borrow().clone());
+    match write_str(&message, buf, buf_len, out_len) {
+        Ok(()) => TuneStatus::Ok,
+        Err(err) => err.status,
+    }
+}
+
+fn guard(call: impl FnOnce() -> Result<(), Error>) -> TuneStatus {
+    let result = panic::catch_unwind(AssertUnwindSafe(call))
+        .unwrap_or_else(|_| Err(Error::new(TuneStatus::Panic, "Unexpected panic")));
+    match result {
+        Ok(()) => TuneStatus::Ok,
+        Err(Error { status, message }) => {
+            LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+            status
+        }
+    }
+}
+
+unsafe fn handle<'a, T>(ptr: *const T) -> Result<&'a T, Error> {
+    ptr.as_ref()
+        .ok_or_else(|| Error::new(TuneStatus::NullPointer, "Handle must not be null"))
+}
+
+unsafe fn output<'a, T>(ptr: *mut T) -> Result<&'a mut T, Error> {
+    ptr.as_mut()
+        .ok_or_else(|| Error::new(TuneStatus::NullPointer, "Output parameter must not be null"))
+}
+
+unsafe fn input<'a>(data: *const u8, len: usize) -> Result<&'a [u8], Error> {
+    if len == 0 {
+        Ok(&[])
+    } else if data.is_null() {
+        Err(Error::new(
+            TuneStatus::NullPointer,
+            "Input must not be null",
+        ))
+    } else {
+        Ok(slice::from_raw_parts(data, len))
+    }
+}
+
+unsafe fn release<T>(ptr: *mut T) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr));
+    }
+}
+
+fn pitch(pitch_hz: f64) -> Result<Pitch, Error> {
+    if pitch_hz.is_finite() && pitch_hz > 0.0 {
+        Ok(Pitch::from_hz(pitch_hz))
+    } else {
+        Err(Error::new(
+            TuneStatus::InvalidArgument,
+            format!("Pitch must be positive and finite but was {}", pitch_hz),
+        ))
+    }
+}
+
+unsafe fn write_str(
+    text: &str,
+    buf: *mut c_char,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> Result<(), Error> {
+    let mut bytes = Vec::with_capacity(text.len() + 1);
+    bytes.extend_from_slice(text.as_bytes());
+    bytes.push(0);
+    write_bytes(&bytes, buf.cast(), buf_len, out_len)
+}
+
+unsafe fn write_bytes(
+    bytes: &[u8],
+    buf: *mut u8,
+    buf_len: usize,
+    out_len: *mut usize,
+) -> Result<(), Error> {
+    *output(out_len)? = bytes.len();
+    if buf_len < bytes.len() {
+        return Err(Error::new(
+            TuneStatus::BufferTooSmall,
+            format!(
+                "Buffer of size {} is too small ({} bytes required)",
+                buf_len,
+                bytes.len()
+            ),
+        ));
+    }
+    if buf.is_null() {
+        return Err(Error::new(
+            TuneStatus::NullPointer,
+            "Buffer must not be null",
+        ));
+    }
+    ptr::copy_nonoverlapping(bytes.as_ptr(), buf, bytes.len());
+    Ok(())
+}
@@ -0,0 +1,97 @@
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "tune.h"
+
+#define CHECK(condition)                                                       \
+  do {                                                                         \
+    if (!(condition)) {                                                        \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__,         \
+              #condition);                                                     \
+      return 1;                                                                \
+    }                                                                          \
+  } while (0)
+
+static const char SCL[] = "! 7-EDO\n"
+                          "7-EDO\n"
+                          "7\n"
+                          "171.42857\n"
+                          "342.85714\n"
+                          "514.28571\n"
+                          "685.71429\n"
+                          "857.14286\n"
+                          "1028.57143\n"
+                          "2/1\n";
+
+static const char KBM[] = "0\n0\n127\n62\n69\n440.0\n0\n";
+
+int main(void) {
+  TuneScl *scl = NULL;
+  CHECK(tune_scl_import((const uint8_t *)SCL, strlen(SCL), &scl) ==
+        TUNE_STATUS_OK);
+
+  size_t size = 0;
+  CHECK(tune_scl_size(scl, &size) == TUNE_STATUS_OK);
+  CHECK(size == 7);
+
+  size_t len = 0;
+  CHECK(tune_scl_export(scl, NULL, 0, &len) == TUNE_STATUS_BUFFER_TOO_SMALL);
+  char exported[256];
+  CHECK(len <= sizeof(exported));
+  CHECK(tune_scl_export(scl, exported, sizeof(exported), &len) ==
+        TUNE_STATUS_OK);
+  CHECK(strlen(exported) + 1 == len);
+  CHECK(strstr(exported, "171.42857") != NULL);
+
+  TuneKbm *kbm = NULL;
+  CHECK(tune_kbm_import((const uint8_t *)KBM, strlen(KBM), &kbm) ==
+        TUNE_STATUS_OK);
+  CHECK(tune_kbm_export(kbm, exported, sizeof(exported), &len) ==
+        TUNE_STATUS_OK);
+  CHECK(strstr(exported, "440") != NULL);
+
+  TuneTuning *tuning = NULL;
+  CHECK(tune_tuning_new(scl, kbm, &tuning) == TUNE_STATUS_OK);
+  tune_scl_free(scl);
+  tune_kbm_free(kbm);
+
+  double pitch_hz = 0.0;
+  CHECK(tune_tuning_pitch_of(tuning, 69, &pitch_hz) == TUNE_STATUS_OK);
+  CHECK(fabs(pitch_hz - 440.0) < 1e-6);
+  CHECK(tune_tuning_pitch_of(tuning, 62, &pitch_hz) == TUNE_STATUS_OK);
+  CHECK(fabs(pitch_hz - 220.0) < 1e-6);
+
+  int32_t key = 0;
+  double deviation_cents = 0.0;
+  CHECK(tune_tuning_find_by_pitch(tuning, 445.0, &key, &deviation_cents) ==
+        TUNE_STATUS_OK);
+  CHECK(key == 69);
+  CHECK(fabs(deviation_cents - 1200.0 * log2(445.0 / 440.0)) < 1e-6);
+  CHECK(tune_tuning_find_by_pitch(tuning, -1.0, &key, NULL) ==
+        TUNE_STATUS_INVALID_ARGUMENT);
+
+  uint8_t message[1024];
+  CHECK(tune_tuning_single_note_tuning_message(tuning, 0x7f, 0, message,
+                                               sizeof(message),
+                                               &len) == TUNE_STATUS_OK);
+  CHECK(message[0] == 0xf0);
+  CHECK(message[len - 1] == 0xf7);
+  CHECK(tune_tuning_single_note_tuning_message(tuning, 128, 0, message,
+                                               sizeof(message), &len) ==
+        TUNE_STATUS_OUT_OF_RANGE);
+  tune_tuning_free(tuning);
+
+  TuneScl *invalid = NULL;
+  const char *invalid_scl = "Description\n1\nx\n";
+  CHECK(tune_scl_import((const uint8_t *)invalid_scl, strlen(invalid_scl),
+                        &invalid) == TUNE_STATUS_PARSE_ERROR);
+  CHECK(invalid == NULL);
+  char error[256];
+  CHECK(tune_last_error_message(error, sizeof(error), &len) == TUNE_STATUS_OK);
+  CHECK(strstr(error, "line 3") != NULL);
+
+  CHECK(tune_scl_size(NULL, &size) == TUNE_STATUS_NULL_POINTER);
+
+  return 0;
+}
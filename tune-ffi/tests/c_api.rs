@@ -0,0 +1,28 @@
+#![cfg(target_os = "linux")]
+
+use std::{env, path::Path, process::Command};
+
+#[test]
+fn run_c_test_program() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // Integration tests are located in target/<profile>/deps, next to the static library's directory
+    let target_dir = env::current_exe().unwrap();
+    let target_dir = target_dir.parent().unwrap().parent().unwrap();
+    let executable = target_dir.join("test_tune");
+
+    let status = Command::new(env::var_os("CC").unwrap_or_else(|| "cc".into()))
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg(crate_dir.join("tests/c/test_tune.c"))
+        .arg(target_dir.join("libtune_ffi.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&executable)
+        .status()
+        .unwrap();
+    assert!(status.success(), "Compilation of the C test program failed");
+
+    let status = Command::new(executable).status().unwrap();
+    assert!(status.success(), "The C test program failed");
+}
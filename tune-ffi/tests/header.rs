@@ -0,0 +1,23 @@
+use std::{env, fs, path::Path};
+
+#[test]
+fn header_is_up_to_date() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+
+    let mut generated = Vec::new();
+    cbindgen::generate_with_config(crate_dir, config)
+        .unwrap()
+        .write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    let header_path = crate_dir.join("include/tune.h");
+    if env::var_os("TUNE_FFI_BLESS").is_some() {
+        fs::write(&header_path, &generated).unwrap();
+    }
+    assert_eq!(
+        fs::read_to_string(header_path).unwrap_or_default(),
+        generated,
+        "include/tune.h is outdated, run `TUNE_FFI_BLESS=1 cargo test` to update it"
+    );
+}